@@ -14,6 +14,8 @@ extern crate alloc;
 
 use alloc::format;
 use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
 use core::sync::atomic::{AtomicU64, Ordering};
 
 // ============================================================================
@@ -29,6 +31,12 @@ extern "C" {
     /// Get context JSON (returns length of JSON string)
     fn get_context() -> i64;
 
+    /// Size of the current hook's JSON payload in bytes.
+    fn get_hook_payload_len() -> i64;
+
+    /// Copy the current hook's JSON payload into a buffer.
+    fn get_hook_payload(dst_ptr: i32, dst_len: i32) -> i64;
+
     /// Register a widget in a specific UI region.
     /// region: 0=Header, 1=Footer, 2=SidebarLeft, 3=SidebarRight,
     ///         4=MainContent, 5=InputArea, 6=Overlay, 7=StatusBar,
@@ -202,6 +210,49 @@ fn emit_custom_event(event_name: &str, event_data: &str) -> bool {
     }
 }
 
+// ============================================================================
+// Hook payload helpers
+// ============================================================================
+
+/// Read the JSON payload of the hook currently being handled.
+fn read_hook_payload() -> Option<Vec<u8>> {
+    // SAFETY: FFI calls to host-provided payload functions.
+    // The host copies at most `buf.len()` bytes into the buffer, which stays
+    // alive and exclusively borrowed for the duration of the call.
+    unsafe {
+        let len = get_hook_payload_len();
+        if len < 0 {
+            return None;
+        }
+        let mut buf = vec![0u8; len as usize];
+        let written = get_hook_payload(buf.as_mut_ptr() as i32, buf.len() as i32);
+        if written < 0 {
+            return None;
+        }
+        buf.truncate(written as usize);
+        Some(buf)
+    }
+}
+
+/// Extract the raw value of a top-level `"key":value` pair from flat JSON.
+///
+/// Good enough for the fixed file-operation payload schema without pulling in
+/// a JSON parser.
+fn json_field<'a>(json: &'a str, key: &str) -> Option<&'a str> {
+    let needle = format!("\"{}\":", key);
+    let start = json.find(&needle)? + needle.len();
+    let rest = json[start..].trim_start();
+    let end = rest.find(|c| c == ',' || c == '}').unwrap_or(rest.len());
+    Some(rest[..end].trim().trim_matches('"'))
+}
+
+/// Extract a numeric field from the payload, defaulting to 0.
+fn json_u64(json: &str, key: &str) -> u64 {
+    json_field(json, key)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0)
+}
+
 // ============================================================================
 // Statistics helpers
 // ============================================================================
@@ -418,21 +469,30 @@ pub extern "C" fn cmd_stats_export() -> i32 {
 pub extern "C" fn hook_file_operation_after() -> i32 {
     log_debug("File operation hook triggered");
 
-    // In a real implementation, we would read operation details from a shared buffer.
-    // The buffer would contain:
-    // - Operation type (create, modify, delete, read)
-    // - File path
-    // - Lines changed (for create/modify operations)
-    //
-    // For this example, we simulate tracking a file modification.
-    // Each hook invocation represents one file operation.
-
-    // Simulate tracking: assume each operation modified ~10 lines
-    // In practice, this would come from the actual diff data
-    let simulated_lines_added: u64 = 5;
-    let simulated_lines_removed: u64 = 2;
-
-    record_file_modified(simulated_lines_added, simulated_lines_removed);
+    // The payload is a FileOperationPayload, e.g.
+    // {"operation":"write","path":"src/main.rs","dest_path":null,
+    //  "success":true,"lines_added":12,"lines_removed":3}
+    let Some(payload) = read_hook_payload() else {
+        log_warn("File operation hook called without a payload");
+        return 0;
+    };
+    let Ok(json) = core::str::from_utf8(&payload) else {
+        log_warn("File operation payload is not valid UTF-8");
+        return 0;
+    };
+
+    if json_field(json, "success") == Some("false") {
+        return 0;
+    }
+
+    let lines_added = json_u64(json, "lines_added");
+    let lines_removed = json_u64(json, "lines_removed");
+    match json_field(json, "operation") {
+        Some("create") => record_file_created(lines_added),
+        Some("delete") => record_file_deleted(lines_removed),
+        Some("read") => return 0,
+        _ => record_file_modified(lines_added, lines_removed),
+    }
 
     let total_ops = TOTAL_OPERATIONS.load(Ordering::Relaxed);
     log_debug(&format!("Tracked file operation #{}", total_ops));
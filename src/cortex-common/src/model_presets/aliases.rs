@@ -1,5 +1,6 @@
 //! Model alias definitions and resolution functions.
 
+use super::presets::MODEL_PRESETS;
 use super::types::ModelAlias;
 
 /// Built-in model aliases for common shortcuts.
@@ -59,6 +60,106 @@ pub const MODEL_ALIASES: &[ModelAlias] = &[
     },
 ];
 
+/// Extra short names for entries in [`MODEL_PRESETS`], used by [`resolve_model_id`].
+///
+/// Every target is a preset id. An alias from [`MODEL_ALIASES`] is only
+/// repeated here when its target there has no preset, to give it one.
+pub const PRESET_ALIASES: &[ModelAlias] = &[
+    // Claude models
+    ModelAlias {
+        alias: "sonnet",
+        model: "claude-3-5-sonnet",
+    },
+    // OpenAI models
+    ModelAlias {
+        alias: "4o",
+        model: "gpt-4o",
+    },
+    ModelAlias {
+        alias: "gpt4o",
+        model: "gpt-4o",
+    },
+    ModelAlias {
+        alias: "4o-mini",
+        model: "gpt-4o-mini",
+    },
+    ModelAlias {
+        alias: "gpt4o-mini",
+        model: "gpt-4o-mini",
+    },
+    // Google models
+    ModelAlias {
+        alias: "gemini",
+        model: "gemini-2.0-flash",
+    },
+    ModelAlias {
+        alias: "flash",
+        model: "gemini-2.0-flash",
+    },
+    ModelAlias {
+        alias: "gemini-pro",
+        model: "gemini-1.5-pro",
+    },
+    // Mistral models
+    ModelAlias {
+        alias: "mistral",
+        model: "mistral-large-latest",
+    },
+    ModelAlias {
+        alias: "codestral",
+        model: "codestral-latest",
+    },
+    // xAI models
+    ModelAlias {
+        alias: "grok",
+        model: "grok-2",
+    },
+    // Cohere models
+    ModelAlias {
+        alias: "command",
+        model: "command-r-plus",
+    },
+    // Moonshot models
+    ModelAlias {
+        alias: "kimi",
+        model: "moonshotai/Kimi-K2.5-TEE",
+    },
+];
+
+/// Resolves an input to the canonical id of a model preset.
+///
+/// Exact preset ids resolve to themselves. Otherwise the input is looked up
+/// case-insensitively in [`MODEL_ALIASES`], whose target is used when it is a
+/// preset id, and then in [`PRESET_ALIASES`]. Returns `None` if nothing matches.
+///
+/// # Examples
+///
+/// ```
+/// use cortex_common::resolve_model_id;
+///
+/// assert_eq!(resolve_model_id("sonnet"), Some("claude-3-5-sonnet"));
+/// assert_eq!(resolve_model_id("opus"), Some("anthropic/claude-opus-4.5"));
+/// assert_eq!(resolve_model_id("gpt-4o"), Some("gpt-4o"));
+/// assert_eq!(resolve_model_id("unknown-model"), None);
+/// ```
+pub fn resolve_model_id(input: &str) -> Option<&'static str> {
+    let input = input.trim();
+    if let Some(preset) = MODEL_PRESETS.iter().find(|p| p.id == input) {
+        return Some(preset.id);
+    }
+    let shared = MODEL_ALIASES
+        .iter()
+        .find(|a| a.alias.eq_ignore_ascii_case(input))
+        .and_then(|alias| MODEL_PRESETS.iter().find(|p| p.id == alias.model));
+    if let Some(preset) = shared {
+        return Some(preset.id);
+    }
+    PRESET_ALIASES
+        .iter()
+        .find(|a| a.alias.eq_ignore_ascii_case(input))
+        .map(|a| a.model)
+}
+
 /// Resolves a model alias to its full model identifier.
 ///
 /// If the input matches a known alias, returns the corresponding full model name.
@@ -124,6 +225,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_resolve_model_id_alias() {
+        assert_eq!(resolve_model_id("sonnet"), Some("claude-3-5-sonnet"));
+        assert_eq!(resolve_model_id("SONNET"), Some("claude-3-5-sonnet"));
+        assert_eq!(resolve_model_id("gemini"), Some("gemini-2.0-flash"));
+        assert_eq!(resolve_model_id("4o"), Some("gpt-4o"));
+        assert_eq!(resolve_model_id(" r1 "), Some("deepseek/deepseek-r1"));
+    }
+
+    #[test]
+    fn test_resolve_model_id_exact_and_unknown() {
+        assert_eq!(resolve_model_id("gpt-4o-mini"), Some("gpt-4o-mini"));
+        assert_eq!(resolve_model_id("not-a-model"), None);
+        assert_eq!(resolve_model_id(""), None);
+    }
+
+    #[test]
+    fn test_preset_aliases_target_existing_presets() {
+        for alias in PRESET_ALIASES {
+            assert!(
+                MODEL_PRESETS.iter().any(|p| p.id == alias.model),
+                "alias {} points at unknown preset {}",
+                alias.alias,
+                alias.model
+            );
+            assert!(
+                !MODEL_PRESETS.iter().any(|p| p.id == alias.alias),
+                "alias {} shadows a preset id",
+                alias.alias
+            );
+        }
+    }
+
+    #[test]
+    fn test_alias_tables_agree() {
+        // A repeated alias is only a fallback for a target without a preset.
+        for alias in PRESET_ALIASES {
+            if let Some(shared) = MODEL_ALIASES
+                .iter()
+                .find(|a| a.alias.eq_ignore_ascii_case(alias.alias))
+            {
+                assert!(
+                    !MODEL_PRESETS.iter().any(|p| p.id == shared.model),
+                    "alias {} shadows the preset {}",
+                    alias.alias,
+                    shared.model
+                );
+            }
+        }
+        // Aliases whose target is a preset resolve to that preset.
+        for alias in MODEL_ALIASES {
+            if MODEL_PRESETS.iter().any(|p| p.id == alias.model) {
+                assert_eq!(
+                    resolve_model_id(alias.alias),
+                    Some(alias.model),
+                    "alias {} resolves to a different model",
+                    alias.alias
+                );
+            }
+        }
+        for shared in ["opus", "deepseek", "r1"] {
+            assert_eq!(resolve_model_id(shared), Some(resolve_model_alias(shared)));
+        }
+    }
+
     #[test]
     fn test_list_model_aliases() {
         let aliases = list_model_aliases();
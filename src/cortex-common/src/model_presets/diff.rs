@@ -0,0 +1,83 @@
+//! Structured comparison between model preset snapshots.
+
+use super::types::{ModelPreset, PresetChange, PresetDiff};
+
+/// Compare two sets of model presets by id.
+///
+/// Reports presets that were added, removed, or whose fields changed between
+/// `old` and `new`. Useful for detecting accidental removals when the preset
+/// table is updated.
+pub fn diff_presets(old: &[ModelPreset], new: &[ModelPreset]) -> PresetDiff {
+    let mut diff = PresetDiff::default();
+
+    for preset in new {
+        match old.iter().find(|p| p.id == preset.id) {
+            None => diff.added.push(preset.id),
+            Some(previous) => {
+                let fields = changed_fields(previous, preset);
+                if !fields.is_empty() {
+                    diff.changed.push(PresetChange {
+                        id: preset.id,
+                        fields,
+                    });
+                }
+            }
+        }
+    }
+
+    diff.removed = old
+        .iter()
+        .filter(|p| !new.iter().any(|n| n.id == p.id))
+        .map(|p| p.id)
+        .collect();
+
+    diff
+}
+
+/// List the names of the fields that differ between two presets.
+fn changed_fields(old: &ModelPreset, new: &ModelPreset) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    if old.name != new.name {
+        fields.push("name");
+    }
+    if old.provider != new.provider {
+        fields.push("provider");
+    }
+    if old.context_window != new.context_window {
+        fields.push("context_window");
+    }
+    if old.max_output_tokens != new.max_output_tokens {
+        fields.push("max_output_tokens");
+    }
+    if old.supports_vision != new.supports_vision {
+        fields.push("supports_vision");
+    }
+    if old.supports_tools != new.supports_tools {
+        fields.push("supports_tools");
+    }
+    if old.supports_reasoning != new.supports_reasoning {
+        fields.push("supports_reasoning");
+    }
+    if old.supports_streaming != new.supports_streaming {
+        fields.push("supports_streaming");
+    }
+    if old.default_temperature != new.default_temperature {
+        fields.push("default_temperature");
+    }
+    if old.reasoning_effort != new.reasoning_effort {
+        fields.push("reasoning_effort");
+    }
+    if old.input_cost_per_mtok != new.input_cost_per_mtok {
+        fields.push("input_cost_per_mtok");
+    }
+    if old.output_cost_per_mtok != new.output_cost_per_mtok {
+        fields.push("output_cost_per_mtok");
+    }
+    if old.deprecated != new.deprecated {
+        fields.push("deprecated");
+    }
+    if old.replacement_id != new.replacement_id {
+        fields.push("replacement_id");
+    }
+    fields
+}
@@ -4,27 +4,44 @@
 //! - Model preset definitions for various AI providers
 //! - Model aliases for common shortcuts
 //! - Resolution utilities for model name lookups
+//! - Structured diffs between preset snapshots
+//! - A runtime registry for presets loaded from JSON
 
 mod aliases;
 mod constants;
+mod diff;
 mod presets;
+mod registry;
 mod resolution;
 mod types;
 
 // Re-export types
-pub use types::{ModelAlias, ModelPreset, ModelResolution};
+pub use types::{
+    ModelAlias, ModelPreset, ModelResolution, PresetChange, PresetDiff, RecommendedParams,
+};
 
 // Re-export constants
 pub use constants::{DEFAULT_MODEL, DEFAULT_MODELS, DEFAULT_PROVIDER};
 
 // Re-export preset data and helpers
 pub use presets::{
-    DEFAULT_CHUTES_MODEL, MODEL_PRESETS, get_model_preset, get_models_for_provider,
-    provider_allows_custom_models, validate_chutes_model,
+    DEFAULT_CHUTES_MODEL, MODEL_PRESETS, ProviderPolicy, estimate_cost, get_active_models,
+    get_max_output_tokens, get_model_preset, get_model_preset_fuzzy, get_models_for_provider,
+    group_models_by_provider, list_providers, model_supports_streaming,
+    provider_allows_custom_models, provider_policy, recommended_params, smallest_model_fitting,
+    validate_chutes_model, validate_model_for_provider,
 };
 
 // Re-export alias data and helpers
-pub use aliases::{MODEL_ALIASES, list_model_aliases, resolve_model_alias};
+pub use aliases::{
+    MODEL_ALIASES, PRESET_ALIASES, list_model_aliases, resolve_model_alias, resolve_model_id,
+};
+
+// Re-export the runtime registry
+pub use registry::{ModelPresetRegistry, OwnedModelPreset, PresetLoadError};
 
 // Re-export resolution functions
 pub use resolution::{resolve_model_with_info, warn_if_ambiguous_model};
+
+// Re-export diff helpers
+pub use diff::diff_presets;
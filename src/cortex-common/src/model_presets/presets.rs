@@ -1,6 +1,9 @@
 //! Model preset data definitions.
 
-use super::types::ModelPreset;
+use cortex_protocol::ReasoningEffort;
+
+use super::aliases::resolve_model_id;
+use super::types::{ModelPreset, RecommendedParams};
 
 /// Default model for Chutes provider.
 /// This is the fallback model when no specific model is provided.
@@ -13,54 +16,102 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "GPT-4o",
         provider: "openai",
         context_window: 128_000,
+        max_output_tokens: 16_384,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(2.5),
+        output_cost_per_mtok: Some(10.0),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "gpt-4o-mini",
         name: "GPT-4o Mini",
         provider: "openai",
         context_window: 128_000,
+        max_output_tokens: 16_384,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(0.15),
+        output_cost_per_mtok: Some(0.6),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "o1",
         name: "o1",
         provider: "openai",
         context_window: 200_000,
+        max_output_tokens: 100_000,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: true,
+        supports_streaming: false,
+        default_temperature: Some(1.0),
+        reasoning_effort: Some(ReasoningEffort::Medium),
+        input_cost_per_mtok: Some(15.0),
+        output_cost_per_mtok: Some(60.0),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "o1-mini",
         name: "o1-mini",
         provider: "openai",
         context_window: 128_000,
+        max_output_tokens: 65_536,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: true,
+        supports_streaming: true,
+        default_temperature: Some(1.0),
+        reasoning_effort: Some(ReasoningEffort::Medium),
+        input_cost_per_mtok: Some(1.1),
+        output_cost_per_mtok: Some(4.4),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "claude-3-5-sonnet",
         name: "Claude 3.5 Sonnet",
         provider: "anthropic",
         context_window: 200_000,
+        max_output_tokens: 8_192,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(3.0),
+        output_cost_per_mtok: Some(15.0),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "claude-3-opus",
         name: "Claude 3 Opus",
         provider: "anthropic",
         context_window: 200_000,
+        max_output_tokens: 4_096,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(15.0),
+        output_cost_per_mtok: Some(75.0),
+        deprecated: false,
+        replacement_id: None,
     },
     // Google Gemini models
     ModelPreset {
@@ -68,45 +119,85 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "Gemini 2.0 Flash (Experimental)",
         provider: "google",
         context_window: 1_048_576,
+        max_output_tokens: 8_192,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: true,
+        replacement_id: Some("gemini-2.0-flash"),
     },
     ModelPreset {
         id: "gemini-2.0-flash",
         name: "Gemini 2.0 Flash",
         provider: "google",
         context_window: 1_048_576,
+        max_output_tokens: 8_192,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(0.1),
+        output_cost_per_mtok: Some(0.4),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "gemini-1.5-pro",
         name: "Gemini 1.5 Pro",
         provider: "google",
         context_window: 2_097_152,
+        max_output_tokens: 8_192,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(1.25),
+        output_cost_per_mtok: Some(5.0),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "gemini-1.5-flash",
         name: "Gemini 1.5 Flash",
         provider: "google",
         context_window: 1_048_576,
+        max_output_tokens: 8_192,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(0.075),
+        output_cost_per_mtok: Some(0.3),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "gemini-1.5-flash-8b",
         name: "Gemini 1.5 Flash 8B",
         provider: "google",
         context_window: 1_048_576,
+        max_output_tokens: 8_192,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(0.0375),
+        output_cost_per_mtok: Some(0.15),
+        deprecated: false,
+        replacement_id: None,
     },
     // Mistral AI models
     ModelPreset {
@@ -114,63 +205,119 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "Mistral Large",
         provider: "mistral",
         context_window: 128_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(2.0),
+        output_cost_per_mtok: Some(6.0),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "mistral-medium-latest",
         name: "Mistral Medium",
         provider: "mistral",
         context_window: 32_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "mistral-small-latest",
         name: "Mistral Small",
         provider: "mistral",
         context_window: 32_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(0.2),
+        output_cost_per_mtok: Some(0.6),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "codestral-latest",
         name: "Codestral",
         provider: "mistral",
         context_window: 32_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(0.3),
+        output_cost_per_mtok: Some(0.9),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "open-mixtral-8x22b",
         name: "Mixtral 8x22B",
         provider: "mistral",
         context_window: 64_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "open-mistral-7b",
         name: "Mistral 7B",
         provider: "mistral",
         context_window: 32_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: false,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "pixtral-large-latest",
         name: "Pixtral Large",
         provider: "mistral",
         context_window: 128_000,
+        max_output_tokens: 8_192,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(2.0),
+        output_cost_per_mtok: Some(6.0),
+        deprecated: false,
+        replacement_id: None,
     },
     // Groq models (ultra-fast inference)
     ModelPreset {
@@ -178,63 +325,119 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "Llama 3.3 70B",
         provider: "groq",
         context_window: 128_000,
+        max_output_tokens: 32_768,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(0.59),
+        output_cost_per_mtok: Some(0.79),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "llama-3.1-70b-versatile",
         name: "Llama 3.1 70B",
         provider: "groq",
         context_window: 128_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: true,
+        replacement_id: Some("llama-3.3-70b-versatile"),
     },
     ModelPreset {
         id: "llama-3.1-8b-instant",
         name: "Llama 3.1 8B Instant",
         provider: "groq",
         context_window: 128_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(0.05),
+        output_cost_per_mtok: Some(0.08),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "llama3-70b-8192",
         name: "Llama 3 70B",
         provider: "groq",
         context_window: 8_192,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: true,
+        replacement_id: Some("llama-3.3-70b-versatile"),
     },
     ModelPreset {
         id: "llama3-8b-8192",
         name: "Llama 3 8B",
         provider: "groq",
         context_window: 8_192,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: true,
+        replacement_id: Some("llama-3.1-8b-instant"),
     },
     ModelPreset {
         id: "mixtral-8x7b-32768",
         name: "Mixtral 8x7B",
         provider: "groq",
         context_window: 32_768,
+        max_output_tokens: 32_768,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: true,
+        replacement_id: None,
     },
     ModelPreset {
         id: "gemma2-9b-it",
         name: "Gemma 2 9B IT",
         provider: "groq",
         context_window: 8_192,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     // Cerebras models (ultra-fast inference on Wafer-Scale Engine)
     // Cerebras is the fastest inference provider in the industry
@@ -243,27 +446,51 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "Llama 3.1 8B (Cerebras - Ultra Fast)",
         provider: "cerebras",
         context_window: 8_192,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "llama3.1-70b",
         name: "Llama 3.1 70B (Cerebras - Fast)",
         provider: "cerebras",
         context_window: 8_192,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "llama-3.3-70b",
         name: "Llama 3.3 70B (Cerebras - Latest)",
         provider: "cerebras",
         context_window: 8_192,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     // xAI (Grok) models
     ModelPreset {
@@ -271,36 +498,68 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "Grok 2",
         provider: "xai",
         context_window: 128_000,
+        max_output_tokens: 32_768,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(2.0),
+        output_cost_per_mtok: Some(10.0),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "grok-2-mini",
         name: "Grok 2 Mini",
         provider: "xai",
         context_window: 128_000,
+        max_output_tokens: 32_768,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "grok-beta",
         name: "Grok Beta",
         provider: "xai",
         context_window: 128_000,
+        max_output_tokens: 32_768,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(5.0),
+        output_cost_per_mtok: Some(15.0),
+        deprecated: true,
+        replacement_id: Some("grok-2"),
     },
     ModelPreset {
         id: "grok-vision-beta",
         name: "Grok Vision Beta",
         provider: "xai",
         context_window: 8_000,
+        max_output_tokens: 4_096,
         supports_vision: true,
         supports_tools: false,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     // GitHub Copilot models (via Copilot subscription)
     ModelPreset {
@@ -308,45 +567,85 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "GPT-4o (via Copilot)",
         provider: "github-copilot",
         context_window: 128_000,
+        max_output_tokens: 16_384,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "copilot/gpt-4o-mini",
         name: "GPT-4o Mini (via Copilot)",
         provider: "github-copilot",
         context_window: 128_000,
+        max_output_tokens: 16_384,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "copilot/claude-3.5-sonnet",
         name: "Claude 3.5 Sonnet (via Copilot)",
         provider: "github-copilot",
         context_window: 200_000,
+        max_output_tokens: 8_192,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "copilot/o1-preview",
         name: "o1-preview (via Copilot)",
         provider: "github-copilot",
         context_window: 200_000,
+        max_output_tokens: 32_768,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: true,
+        supports_streaming: false,
+        default_temperature: Some(1.0),
+        reasoning_effort: Some(ReasoningEffort::Medium),
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "copilot/o1-mini",
         name: "o1-mini (via Copilot)",
         provider: "github-copilot",
         context_window: 128_000,
+        max_output_tokens: 65_536,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: true,
+        supports_streaming: false,
+        default_temperature: Some(1.0),
+        reasoning_effort: Some(ReasoningEffort::Medium),
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     // Amazon Bedrock models (via AWS)
     ModelPreset {
@@ -354,90 +653,170 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "Claude 3.5 Sonnet v2 (Bedrock)",
         provider: "bedrock",
         context_window: 200_000,
+        max_output_tokens: 8_192,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(3.0),
+        output_cost_per_mtok: Some(15.0),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "anthropic.claude-3-5-haiku-20241022-v1:0",
         name: "Claude 3.5 Haiku (Bedrock)",
         provider: "bedrock",
         context_window: 200_000,
+        max_output_tokens: 8_192,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(0.8),
+        output_cost_per_mtok: Some(4.0),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "anthropic.claude-3-opus-20240229-v1:0",
         name: "Claude 3 Opus (Bedrock)",
         provider: "bedrock",
         context_window: 200_000,
+        max_output_tokens: 4_096,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(15.0),
+        output_cost_per_mtok: Some(75.0),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "anthropic.claude-3-sonnet-20240229-v1:0",
         name: "Claude 3 Sonnet (Bedrock)",
         provider: "bedrock",
         context_window: 200_000,
+        max_output_tokens: 4_096,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(3.0),
+        output_cost_per_mtok: Some(15.0),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "anthropic.claude-3-haiku-20240307-v1:0",
         name: "Claude 3 Haiku (Bedrock)",
         provider: "bedrock",
         context_window: 200_000,
+        max_output_tokens: 4_096,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(0.25),
+        output_cost_per_mtok: Some(1.25),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "meta.llama3-1-70b-instruct-v1:0",
         name: "Llama 3.1 70B (Bedrock)",
         provider: "bedrock",
         context_window: 128_000,
+        max_output_tokens: 2_048,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "meta.llama3-1-8b-instruct-v1:0",
         name: "Llama 3.1 8B (Bedrock)",
         provider: "bedrock",
         context_window: 128_000,
+        max_output_tokens: 2_048,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "amazon.titan-text-premier-v1:0",
         name: "Titan Text Premier (Bedrock)",
         provider: "bedrock",
         context_window: 32_000,
+        max_output_tokens: 3_072,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "amazon.titan-text-express-v1",
         name: "Titan Text Express (Bedrock)",
         provider: "bedrock",
         context_window: 8_000,
+        max_output_tokens: 8_000,
         supports_vision: false,
         supports_tools: false,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "mistral.mistral-large-2407-v1:0",
         name: "Mistral Large (Bedrock)",
         provider: "bedrock",
         context_window: 128_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     // Together AI models
     ModelPreset {
@@ -445,54 +824,102 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "Llama 3.3 70B Instruct Turbo",
         provider: "together",
         context_window: 128_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "meta-llama/Llama-3.1-405B-Instruct-Turbo",
         name: "Llama 3.1 405B Instruct Turbo",
         provider: "together",
         context_window: 128_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "mistralai/Mixtral-8x22B-Instruct-v0.1",
         name: "Mixtral 8x22B Instruct v0.1",
         provider: "together",
         context_window: 65_536,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "Qwen/Qwen2.5-72B-Instruct-Turbo",
         name: "Qwen 2.5 72B Instruct Turbo",
         provider: "together",
         context_window: 32_768,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "deepseek-ai/DeepSeek-V3",
         name: "DeepSeek V3",
         provider: "together",
         context_window: 65_536,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "google/gemma-2-27b-it",
         name: "Gemma 2 27B IT",
         provider: "together",
         context_window: 8_192,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     // DeepInfra models (serverless GPU inference)
     ModelPreset {
@@ -500,45 +927,85 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "Llama 3.1 405B Instruct (DeepInfra)",
         provider: "deepinfra",
         context_window: 128_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "deepinfra/meta-llama/Meta-Llama-3.1-70B-Instruct",
         name: "Llama 3.1 70B Instruct (DeepInfra)",
         provider: "deepinfra",
         context_window: 128_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "deepinfra/mistralai/Mixtral-8x22B-Instruct-v0.1",
         name: "Mixtral 8x22B Instruct (DeepInfra)",
         provider: "deepinfra",
         context_window: 65_536,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "deepinfra/microsoft/WizardLM-2-8x22B",
         name: "WizardLM 2 8x22B (DeepInfra)",
         provider: "deepinfra",
         context_window: 65_536,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "deepinfra/Qwen/Qwen2.5-72B-Instruct",
         name: "Qwen 2.5 72B Instruct (DeepInfra)",
         provider: "deepinfra",
         context_window: 128_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     // DeepSeek models (direct API access)
     ModelPreset {
@@ -546,27 +1013,51 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "DeepSeek-V3",
         provider: "deepseek",
         context_window: 64_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(0.27),
+        output_cost_per_mtok: Some(1.1),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "deepseek-coder",
         name: "DeepSeek-Coder",
         provider: "deepseek",
         context_window: 128_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "deepseek-reasoner",
         name: "DeepSeek-R1",
         provider: "deepseek",
         context_window: 64_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: true,
+        supports_streaming: true,
+        default_temperature: Some(0.6),
+        reasoning_effort: Some(ReasoningEffort::Medium),
+        input_cost_per_mtok: Some(0.55),
+        output_cost_per_mtok: Some(2.19),
+        deprecated: false,
+        replacement_id: None,
     },
     // Perplexity AI models (search-augmented)
     // Online models (with web search and citations)
@@ -575,27 +1066,51 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "Sonar Small Online (8B)",
         provider: "perplexity",
         context_window: 128_000,
+        max_output_tokens: 4_096,
         supports_vision: false,
         supports_tools: false,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "llama-3.1-sonar-large-128k-online",
         name: "Sonar Large Online (70B)",
         provider: "perplexity",
         context_window: 128_000,
+        max_output_tokens: 4_096,
         supports_vision: false,
         supports_tools: false,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "llama-3.1-sonar-huge-128k-online",
         name: "Sonar Huge Online (405B)",
         provider: "perplexity",
         context_window: 128_000,
+        max_output_tokens: 4_096,
         supports_vision: false,
         supports_tools: false,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     // Chat models (offline, no web search)
     ModelPreset {
@@ -603,18 +1118,34 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "Sonar Small Chat (8B)",
         provider: "perplexity",
         context_window: 128_000,
+        max_output_tokens: 4_096,
         supports_vision: false,
         supports_tools: false,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "llama-3.1-sonar-large-128k-chat",
         name: "Sonar Large Chat (70B)",
         provider: "perplexity",
         context_window: 128_000,
+        max_output_tokens: 4_096,
         supports_vision: false,
         supports_tools: false,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     // Cortex models (200+ models via unified API)
     // These are the most popular models accessible through OpenRouter
@@ -624,18 +1155,34 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "Claude Opus 4.5 (via Cortex) - DEFAULT",
         provider: "cortex",
         context_window: 200_000,
+        max_output_tokens: 64_000,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: true,
+        supports_streaming: true,
+        default_temperature: Some(1.0),
+        reasoning_effort: Some(ReasoningEffort::Medium),
+        input_cost_per_mtok: Some(5.0),
+        output_cost_per_mtok: Some(25.0),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "anthropic/claude-haiku-4.5",
         name: "Claude Haiku 4.5 (via Cortex) - DEFAULT",
         provider: "cortex",
         context_window: 200_000,
+        max_output_tokens: 64_000,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(1.0),
+        output_cost_per_mtok: Some(5.0),
+        deprecated: false,
+        replacement_id: None,
     },
     // Other Cortex models
     ModelPreset {
@@ -643,117 +1190,221 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "GPT-4o (via Cortex)",
         provider: "cortex",
         context_window: 128_000,
+        max_output_tokens: 16_384,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(2.5),
+        output_cost_per_mtok: Some(10.0),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "openai/gpt-4o-mini",
         name: "GPT-4o Mini (via Cortex)",
         provider: "cortex",
         context_window: 128_000,
+        max_output_tokens: 16_384,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(0.15),
+        output_cost_per_mtok: Some(0.6),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "anthropic/claude-3.5-sonnet",
         name: "Claude 3.5 Sonnet (via Cortex)",
         provider: "cortex",
         context_window: 200_000,
+        max_output_tokens: 8_192,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(3.0),
+        output_cost_per_mtok: Some(15.0),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "anthropic/claude-3-opus",
         name: "Claude 3 Opus (via Cortex)",
         provider: "cortex",
         context_window: 200_000,
+        max_output_tokens: 4_096,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(15.0),
+        output_cost_per_mtok: Some(75.0),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "google/gemini-pro-1.5",
         name: "Gemini 1.5 Pro (via Cortex)",
         provider: "cortex",
         context_window: 2_097_152,
+        max_output_tokens: 8_192,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "google/gemini-flash-1.5",
         name: "Gemini 1.5 Flash (via Cortex)",
         provider: "cortex",
         context_window: 1_048_576,
+        max_output_tokens: 8_192,
         supports_vision: true,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "meta-llama/llama-3.1-405b-instruct",
         name: "Llama 3.1 405B Instruct (via Cortex)",
         provider: "cortex",
         context_window: 128_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "meta-llama/llama-3.1-70b-instruct",
         name: "Llama 3.1 70B Instruct (via Cortex)",
         provider: "cortex",
         context_window: 128_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "mistralai/mistral-large",
         name: "Mistral Large (via Cortex)",
         provider: "cortex",
         context_window: 128_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "mistralai/mixtral-8x22b-instruct",
         name: "Mixtral 8x22B Instruct (via Cortex)",
         provider: "cortex",
         context_window: 65_536,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "deepseek/deepseek-chat",
         name: "DeepSeek Chat (via Cortex)",
         provider: "cortex",
         context_window: 64_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "deepseek/deepseek-r1",
         name: "DeepSeek R1 (via Cortex)",
         provider: "cortex",
         context_window: 64_000,
+        max_output_tokens: 8_192,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: true,
+        supports_streaming: true,
+        default_temperature: Some(0.6),
+        reasoning_effort: Some(ReasoningEffort::Medium),
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "cohere/command-r-plus",
         name: "Command R+ (via Cortex)",
         provider: "cortex",
         context_window: 128_000,
+        max_output_tokens: 4_096,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     // Cohere models
     ModelPreset {
@@ -761,54 +1412,102 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "Command R+",
         provider: "cohere",
         context_window: 128_000,
+        max_output_tokens: 4_096,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "command-r-plus-08-2024",
         name: "Command R+ (Aug 2024)",
         provider: "cohere",
         context_window: 128_000,
+        max_output_tokens: 4_096,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(2.5),
+        output_cost_per_mtok: Some(10.0),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "command-r",
         name: "Command R",
         provider: "cohere",
         context_window: 128_000,
+        max_output_tokens: 4_096,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "command-r-08-2024",
         name: "Command R (Aug 2024)",
         provider: "cohere",
         context_window: 128_000,
+        max_output_tokens: 4_096,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: Some(0.15),
+        output_cost_per_mtok: Some(0.6),
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "command-light",
         name: "Command Light",
         provider: "cohere",
         context_window: 4_096,
+        max_output_tokens: 4_096,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     ModelPreset {
         id: "command-nightly",
         name: "Command Nightly",
         provider: "cohere",
         context_window: 128_000,
+        max_output_tokens: 4_096,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
     // Chutes TEE models (Trusted Execution Environment)
     // Security requirement: Only models with '-TEE' suffix are allowed
@@ -817,9 +1516,17 @@ pub const MODEL_PRESETS: &[ModelPreset] = &[
         name: "Kimi K2.5 (TEE)",
         provider: "chutes",
         context_window: 262_144,
+        max_output_tokens: 32_768,
         supports_vision: false,
         supports_tools: true,
         supports_reasoning: true,
+        supports_streaming: true,
+        default_temperature: Some(1.0),
+        reasoning_effort: Some(ReasoningEffort::Medium),
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: false,
+        replacement_id: None,
     },
 ];
 
@@ -828,6 +1535,54 @@ pub fn get_model_preset(id: &str) -> Option<&'static ModelPreset> {
     MODEL_PRESETS.iter().find(|m| m.id == id)
 }
 
+/// Get a model preset by ID or alias.
+///
+/// Tries an exact ID match first, then alias resolution via
+/// [`resolve_model_id`], so `sonnet` finds `claude-3-5-sonnet`.
+pub fn get_model_preset_fuzzy(input: &str) -> Option<&'static ModelPreset> {
+    get_model_preset(input).or_else(|| resolve_model_id(input).and_then(get_model_preset))
+}
+
+/// Check whether a model supports server-sent streaming.
+///
+/// Returns `false` for unknown models, so callers fall back to a
+/// non-streaming request.
+pub fn model_supports_streaming(id: &str) -> bool {
+    get_model_preset(id).is_some_and(|m| m.supports_streaming)
+}
+
+/// Get the maximum number of output tokens a model can generate per request.
+///
+/// Callers should clamp a requested `max_tokens` to this value.
+pub fn get_max_output_tokens(id: &str) -> Option<u32> {
+    get_model_preset(id).map(|m| m.max_output_tokens)
+}
+
+/// Get the recommended sampling parameters for a model.
+///
+/// Returns `None` for unknown models. Fields are `None` when the model has no
+/// preferred value, in which case the caller's defaults apply.
+pub fn recommended_params(id: &str) -> Option<RecommendedParams> {
+    get_model_preset(id).map(|m| RecommendedParams {
+        temperature: m.default_temperature,
+        reasoning_effort: m.reasoning_effort,
+    })
+}
+
+/// Estimate the cost in USD of a call with the given token counts.
+///
+/// Returns `None` when the preset's input or output pricing is unknown.
+pub fn estimate_cost(preset: &ModelPreset, input_tokens: u64, output_tokens: u64) -> Option<f64> {
+    let input = preset.input_cost_per_mtok? * input_tokens as f64;
+    let output = preset.output_cost_per_mtok? * output_tokens as f64;
+    Some((input + output) / 1_000_000.0)
+}
+
+/// Get all presets that are not deprecated.
+pub fn get_active_models() -> Vec<&'static ModelPreset> {
+    MODEL_PRESETS.iter().filter(|m| !m.deprecated).collect()
+}
+
 /// Get models for a specific provider.
 pub fn get_models_for_provider(provider: &str) -> Vec<&'static ModelPreset> {
     MODEL_PRESETS
@@ -836,22 +1591,148 @@ pub fn get_models_for_provider(provider: &str) -> Vec<&'static ModelPreset> {
         .collect()
 }
 
-/// Validates that a model is allowed for the Chutes provider.
-/// Chutes only allows TEE (Trusted Execution Environment) models for security.
-/// Any model ending with '-TEE' suffix (case-insensitive) is accepted.
+/// Find the provider's model with the smallest context window that still
+/// holds `required_tokens`.
+///
+/// Deprecated presets are skipped. Ties go to the earlier preset, and `None`
+/// is returned when no model of the provider is large enough.
+pub fn smallest_model_fitting(
+    provider: &str,
+    required_tokens: u32,
+) -> Option<&'static ModelPreset> {
+    MODEL_PRESETS
+        .iter()
+        .filter(|m| {
+            m.provider == provider
+                && !m.deprecated
+                && m.context_window >= i64::from(required_tokens)
+        })
+        .min_by_key(|m| m.context_window)
+}
+
+/// Group presets by provider, in the order each provider first appears in
+/// `MODEL_PRESETS`. Presets keep their relative order within a group.
+pub fn group_models_by_provider() -> Vec<(&'static str, Vec<&'static ModelPreset>)> {
+    let mut groups: Vec<(&'static str, Vec<&'static ModelPreset>)> = Vec::new();
+    for preset in MODEL_PRESETS {
+        match groups.iter_mut().find(|(p, _)| *p == preset.provider) {
+            Some((_, models)) => models.push(preset),
+            None => groups.push((preset.provider, vec![preset])),
+        }
+    }
+    groups
+}
+
+/// List the distinct providers in `MODEL_PRESETS`, in first-seen order.
+pub fn list_providers() -> Vec<&'static str> {
+    let mut providers: Vec<&'static str> = Vec::new();
+    for preset in MODEL_PRESETS {
+        if !providers.contains(&preset.provider) {
+            providers.push(preset.provider);
+        }
+    }
+    providers
+}
+
+/// Constraint a provider places on the model names it accepts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProviderPolicy {
+    /// Any well-formed model name is accepted.
+    None,
+    /// The model name must end with this suffix (case-insensitive).
+    RequireSuffix(String),
+    /// The model name must be one of these ids (case-insensitive).
+    AllowList(Vec<String>),
+}
+
+impl ProviderPolicy {
+    /// Check an already sanitized model name against this policy.
+    fn check(&self, provider: &str, model: &str) -> Result<(), String> {
+        match self {
+            ProviderPolicy::None => Ok(()),
+            ProviderPolicy::RequireSuffix(suffix) => {
+                if model.to_uppercase().ends_with(&suffix.to_uppercase()) {
+                    return Ok(());
+                }
+                if provider.eq_ignore_ascii_case("chutes") {
+                    return Err(format!(
+                        "Chutes provider only allows TEE models (models ending with '-TEE'). \
+                         Model '{}' is not a TEE model. Default model: {}",
+                        model, DEFAULT_CHUTES_MODEL
+                    ));
+                }
+                Err(format!(
+                    "{} provider only allows models ending with '{}'. \
+                     Model '{}' is not allowed.{}",
+                    provider,
+                    suffix,
+                    model,
+                    default_model_hint(provider)
+                ))
+            }
+            ProviderPolicy::AllowList(allowed) => {
+                if allowed.iter().any(|m| m.eq_ignore_ascii_case(model)) {
+                    return Ok(());
+                }
+                Err(format!(
+                    "{} provider only allows these models: {}. Model '{}' is not allowed.",
+                    provider,
+                    allowed.join(", "),
+                    model
+                ))
+            }
+        }
+    }
+}
+
+/// Get the model policy for a provider (case-insensitive).
+///
+/// - `chutes` only allows TEE (Trusted Execution Environment) models, i.e.
+///   models ending with `-TEE`.
+/// - Every other provider accepts any well-formed model name.
+pub fn provider_policy(provider: &str) -> ProviderPolicy {
+    match provider.to_ascii_lowercase().as_str() {
+        "chutes" => ProviderPolicy::RequireSuffix("-TEE".to_string()),
+        _ => ProviderPolicy::None,
+    }
+}
+
+/// Suffix for policy errors pointing at the provider's default model, if any.
+fn default_model_hint(provider: &str) -> String {
+    get_models_for_provider(provider)
+        .first()
+        .map(|model| format!(" Default model: {}", model.id))
+        .unwrap_or_default()
+}
+
+/// Validates that a model is allowed for a provider.
+/// The model name is sanitized first, then checked against the provider's
+/// [`ProviderPolicy`]. Surrounding whitespace is ignored.
 /// Returns Ok(()) if valid, Err with message if invalid.
 ///
 /// # Security
 /// This function performs strict validation to prevent bypass attacks:
 /// - Rejects null bytes and control characters (prevents C-string truncation attacks)
 /// - Only allows safe ASCII characters: alphanumeric, hyphen, underscore, dot, forward slash
-/// - Case-insensitive suffix check for -TEE
-pub fn validate_chutes_model(model: &str) -> Result<(), String> {
+/// - Case-insensitive policy checks (e.g. the -TEE suffix for Chutes)
+pub fn validate_model_for_provider(provider: &str, model: &str) -> Result<(), String> {
+    validate_model_with_policy(provider, model, &provider_policy(provider))
+}
+
+/// Sanitize `model`, then check it against `policy`.
+fn validate_model_with_policy(
+    provider: &str,
+    model: &str,
+    policy: &ProviderPolicy,
+) -> Result<(), String> {
     let model = model.trim();
 
     // Check for empty model
     if model.is_empty() {
-        return Err("Model name cannot be empty for Chutes provider".to_string());
+        return Err(format!(
+            "Model name cannot be empty for {} provider",
+            provider
+        ));
     }
 
     // SECURITY: Reject null bytes and control characters (CWE-626, CWE-158)
@@ -877,25 +1758,24 @@ pub fn validate_chutes_model(model: &str) -> Result<(), String> {
         );
     }
 
-    // Check suffix (case-insensitive) - any model ending with -TEE is allowed
-    if !model.to_uppercase().ends_with("-TEE") {
-        return Err(format!(
-            "Chutes provider only allows TEE models (models ending with '-TEE'). \
-             Model '{}' is not a TEE model. Default model: {}",
-            model, DEFAULT_CHUTES_MODEL
-        ));
-    }
+    policy.check(provider, model)
+}
 
-    Ok(())
+/// Validates that a model is allowed for the Chutes provider.
+/// Chutes only allows TEE (Trusted Execution Environment) models for security.
+/// Any model ending with '-TEE' suffix (case-insensitive) is accepted.
+/// Returns Ok(()) if valid, Err with message if invalid.
+///
+/// See [`validate_model_for_provider`] for the security checks applied.
+pub fn validate_chutes_model(model: &str) -> Result<(), String> {
+    validate_model_for_provider("Chutes", model)
 }
 
-/// Checks if a provider restricts custom models.
-/// All providers allow custom models, but Chutes requires -TEE suffix.
+/// Checks if a provider accepts models outside a fixed list.
+/// Providers with an [`ProviderPolicy::AllowList`] do not; Chutes accepts
+/// any model with the -TEE suffix.
 pub fn provider_allows_custom_models(provider: &str) -> bool {
-    // All providers allow custom models
-    // Chutes allows any model with -TEE suffix (validated via validate_chutes_model)
-    let _ = provider; // Used for potential future provider-specific restrictions
-    true
+    !matches!(provider_policy(provider), ProviderPolicy::AllowList(_))
 }
 
 #[cfg(test)]
@@ -1060,13 +1940,63 @@ mod tests {
 
     #[test]
     fn test_provider_allows_custom_models() {
-        // All providers allow custom models
         assert!(provider_allows_custom_models("chutes"));
         assert!(provider_allows_custom_models("Chutes"));
         assert!(provider_allows_custom_models("CHUTES"));
         assert!(provider_allows_custom_models("cortex"));
         assert!(provider_allows_custom_models("openai"));
         assert!(provider_allows_custom_models("anthropic"));
+        assert!(provider_allows_custom_models("github-copilot"));
+    }
+
+    #[test]
+    fn test_provider_policy_table() {
+        assert_eq!(
+            provider_policy("CHUTES"),
+            ProviderPolicy::RequireSuffix("-TEE".to_string())
+        );
+        assert_eq!(provider_policy("github-copilot"), ProviderPolicy::None);
+        assert_eq!(provider_policy("openai"), ProviderPolicy::None);
+    }
+
+    #[test]
+    fn test_validate_model_with_allow_list_policy() {
+        let policy = ProviderPolicy::AllowList(vec![
+            "example/small".to_string(),
+            "example/large".to_string(),
+        ]);
+        let validate = |model| validate_model_with_policy("example", model, &policy);
+
+        assert!(validate("example/small").is_ok());
+        assert!(validate(" EXAMPLE/LARGE ").is_ok());
+
+        let err = validate("example/medium").unwrap_err();
+        assert!(err.contains("only allows these models"));
+        assert!(err.contains("example/small, example/large"));
+        assert!(
+            validate("example/small\0")
+                .unwrap_err()
+                .contains("invalid characters")
+        );
+    }
+
+    #[test]
+    fn test_validate_model_for_provider_sanitizes_all_providers() {
+        assert!(validate_model_for_provider("openai", "gpt-4o").is_ok());
+        assert!(validate_model_for_provider("openai", "my-finetune_v2").is_ok());
+
+        assert!(
+            validate_model_for_provider("openai", "gpt-4o\0")
+                .unwrap_err()
+                .contains("invalid characters")
+        );
+        assert!(validate_model_for_provider("openai", "gpt 4o").is_err());
+        assert!(validate_model_for_provider("github-copilot", "copilot/gpt\n-4o").is_err());
+        assert!(
+            validate_model_for_provider("anthropic", "  ")
+                .unwrap_err()
+                .contains("cannot be empty")
+        );
     }
 
     #[test]
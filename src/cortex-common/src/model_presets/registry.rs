@@ -0,0 +1,277 @@
+//! Runtime registry of model presets, extendable from JSON.
+
+use std::path::{Path, PathBuf};
+
+use cortex_protocol::ReasoningEffort;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::presets::MODEL_PRESETS;
+use super::types::ModelPreset;
+
+/// Errors that can occur while loading custom model presets.
+#[derive(Debug, Error)]
+pub enum PresetLoadError {
+    /// Failed to read the presets file.
+    #[error("Failed to read presets file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The presets file is not a valid JSON array of presets.
+    #[error("Invalid presets JSON in {path}: {source}")]
+    Json {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// A preset has an empty or invalid field.
+    #[error("Invalid preset '{id}': {reason}")]
+    InvalidPreset { id: String, reason: String },
+}
+
+/// Owned counterpart of [`ModelPreset`], used for presets defined at runtime.
+///
+/// Capability flags and `deprecated` default to `false` except
+/// `supports_streaming`, and optional fields default to `None`, so a JSON
+/// entry only needs the id, name, provider, context window and output cap.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OwnedModelPreset {
+    pub id: String,
+    pub name: String,
+    pub provider: String,
+    pub context_window: i64,
+    pub max_output_tokens: u32,
+    #[serde(default)]
+    pub supports_vision: bool,
+    #[serde(default)]
+    pub supports_tools: bool,
+    #[serde(default)]
+    pub supports_reasoning: bool,
+    #[serde(default = "default_supports_streaming")]
+    pub supports_streaming: bool,
+    #[serde(default)]
+    pub default_temperature: Option<f32>,
+    #[serde(default)]
+    pub reasoning_effort: Option<ReasoningEffort>,
+    #[serde(default)]
+    pub input_cost_per_mtok: Option<f64>,
+    #[serde(default)]
+    pub output_cost_per_mtok: Option<f64>,
+    #[serde(default)]
+    pub deprecated: bool,
+    #[serde(default)]
+    pub replacement_id: Option<String>,
+}
+
+fn default_supports_streaming() -> bool {
+    true
+}
+
+impl From<&ModelPreset> for OwnedModelPreset {
+    fn from(preset: &ModelPreset) -> Self {
+        Self {
+            id: preset.id.to_string(),
+            name: preset.name.to_string(),
+            provider: preset.provider.to_string(),
+            context_window: preset.context_window,
+            max_output_tokens: preset.max_output_tokens,
+            supports_vision: preset.supports_vision,
+            supports_tools: preset.supports_tools,
+            supports_reasoning: preset.supports_reasoning,
+            supports_streaming: preset.supports_streaming,
+            default_temperature: preset.default_temperature,
+            reasoning_effort: preset.reasoning_effort,
+            input_cost_per_mtok: preset.input_cost_per_mtok,
+            output_cost_per_mtok: preset.output_cost_per_mtok,
+            deprecated: preset.deprecated,
+            replacement_id: preset.replacement_id.map(str::to_string),
+        }
+    }
+}
+
+impl OwnedModelPreset {
+    /// Check the fields a JSON entry could get wrong.
+    fn validate(&self) -> Result<(), PresetLoadError> {
+        let invalid = |reason: &str| PresetLoadError::InvalidPreset {
+            id: self.id.clone(),
+            reason: reason.to_string(),
+        };
+        if self.id.trim().is_empty() {
+            return Err(invalid("id cannot be empty"));
+        }
+        if self.provider.trim().is_empty() {
+            return Err(invalid("provider cannot be empty"));
+        }
+        if self.context_window <= 0 {
+            return Err(invalid("context_window must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// Model presets starting from [`MODEL_PRESETS`] and extended at runtime.
+///
+/// Custom presets replace built-in ones with the same id and are appended
+/// otherwise, so self-hosted models can be added without recompiling.
+#[derive(Debug, Clone)]
+pub struct ModelPresetRegistry {
+    presets: Vec<OwnedModelPreset>,
+}
+
+impl Default for ModelPresetRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ModelPresetRegistry {
+    /// Create a registry holding the built-in presets.
+    pub fn new() -> Self {
+        Self {
+            presets: MODEL_PRESETS.iter().map(OwnedModelPreset::from).collect(),
+        }
+    }
+
+    /// Merge presets from a JSON file containing an array of presets.
+    ///
+    /// Nothing is merged if any entry fails to parse or validate.
+    pub fn load_from_json(&mut self, path: impl AsRef<Path>) -> Result<(), PresetLoadError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|source| PresetLoadError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let presets: Vec<OwnedModelPreset> =
+            serde_json::from_str(&content).map_err(|source| PresetLoadError::Json {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        for preset in &presets {
+            preset.validate()?;
+        }
+        self.merge(presets);
+        Ok(())
+    }
+
+    /// Add presets, replacing any existing preset with the same id.
+    pub fn merge(&mut self, presets: impl IntoIterator<Item = OwnedModelPreset>) {
+        for preset in presets {
+            match self.presets.iter_mut().find(|p| p.id == preset.id) {
+                Some(existing) => *existing = preset,
+                None => self.presets.push(preset),
+            }
+        }
+    }
+
+    /// Get a model preset by ID.
+    pub fn get(&self, id: &str) -> Option<&OwnedModelPreset> {
+        self.presets.iter().find(|p| p.id == id)
+    }
+
+    /// Get models for a specific provider.
+    pub fn for_provider(&self, provider: &str) -> Vec<&OwnedModelPreset> {
+        self.presets
+            .iter()
+            .filter(|p| p.provider == provider)
+            .collect()
+    }
+
+    /// All presets, built-in ones first.
+    pub fn presets(&self) -> &[OwnedModelPreset] {
+        &self.presets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn json_file(content: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_registry_starts_from_builtin_presets() {
+        let registry = ModelPresetRegistry::new();
+        assert_eq!(registry.presets().len(), MODEL_PRESETS.len());
+        assert_eq!(registry.get("gpt-4o").unwrap().name, "GPT-4o");
+        assert_eq!(
+            registry.for_provider("anthropic").len(),
+            MODEL_PRESETS
+                .iter()
+                .filter(|p| p.provider == "anthropic")
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_load_from_json_overrides_and_adds() {
+        let file = json_file(
+            r#"[
+                {
+                    "id": "gpt-4o",
+                    "name": "GPT-4o (gateway)",
+                    "provider": "openai",
+                    "context_window": 64000,
+                    "max_output_tokens": 4096,
+                    "supports_tools": true
+                },
+                {
+                    "id": "acme-coder-7b",
+                    "name": "Acme Coder 7B",
+                    "provider": "acme",
+                    "context_window": 32768,
+                    "max_output_tokens": 8192,
+                    "reasoning_effort": "low",
+                    "input_cost_per_mtok": 0.1,
+                    "output_cost_per_mtok": 0.2
+                }
+            ]"#,
+        );
+
+        let mut registry = ModelPresetRegistry::new();
+        registry.load_from_json(file.path()).unwrap();
+
+        assert_eq!(registry.presets().len(), MODEL_PRESETS.len() + 1);
+
+        let overridden = registry.get("gpt-4o").unwrap();
+        assert_eq!(overridden.name, "GPT-4o (gateway)");
+        assert_eq!(overridden.context_window, 64_000);
+        assert!(!overridden.supports_vision);
+        assert!(overridden.supports_streaming);
+
+        let added = registry.get("acme-coder-7b").unwrap();
+        assert_eq!(added.reasoning_effort, Some(ReasoningEffort::Low));
+        assert_eq!(registry.for_provider("acme"), vec![added]);
+    }
+
+    #[test]
+    fn test_load_from_json_rejects_invalid_files() {
+        let mut registry = ModelPresetRegistry::new();
+
+        let err = registry.load_from_json("/nonexistent/presets.json");
+        assert!(matches!(err, Err(PresetLoadError::Io { .. })));
+
+        let file = json_file("{ not json");
+        let err = registry.load_from_json(file.path());
+        assert!(matches!(err, Err(PresetLoadError::Json { .. })));
+
+        let file = json_file(
+            r#"[
+                {"id": "ok-model", "name": "Ok", "provider": "acme", "context_window": 1000, "max_output_tokens": 100},
+                {"id": "", "name": "Empty", "provider": "acme", "context_window": 1000, "max_output_tokens": 100}
+            ]"#,
+        );
+        let err = registry.load_from_json(file.path());
+        assert!(matches!(err, Err(PresetLoadError::InvalidPreset { .. })));
+        assert!(registry.get("ok-model").is_none());
+    }
+}
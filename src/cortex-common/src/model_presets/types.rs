@@ -1,5 +1,7 @@
 //! Type definitions for model presets.
 
+use cortex_protocol::ReasoningEffort;
+
 /// Model preset information.
 #[derive(Debug, Clone)]
 pub struct ModelPreset {
@@ -7,9 +9,33 @@ pub struct ModelPreset {
     pub name: &'static str,
     pub provider: &'static str,
     pub context_window: i64,
+    /// Maximum number of tokens the model generates in one response.
+    pub max_output_tokens: u32,
     pub supports_vision: bool,
     pub supports_tools: bool,
     pub supports_reasoning: bool,
+    /// Whether the model can stream responses as server-sent events.
+    pub supports_streaming: bool,
+    /// Recommended sampling temperature, if the model prefers a specific one.
+    pub default_temperature: Option<f32>,
+    /// Recommended reasoning effort for reasoning-capable models.
+    pub reasoning_effort: Option<ReasoningEffort>,
+    /// Price in USD per million input tokens, if known.
+    pub input_cost_per_mtok: Option<f64>,
+    /// Price in USD per million output tokens, if known.
+    pub output_cost_per_mtok: Option<f64>,
+    /// Whether the model has been retired and should no longer be offered.
+    pub deprecated: bool,
+    /// Id of the preset to migrate to when this one is deprecated.
+    pub replacement_id: Option<&'static str>,
+}
+
+/// Recommended generation parameters for a model, used when the user does not
+/// override them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecommendedParams {
+    pub temperature: Option<f32>,
+    pub reasoning_effort: Option<ReasoningEffort>,
 }
 
 /// Model alias entry mapping a short name to a full model identifier.
@@ -33,3 +59,30 @@ pub struct ModelResolution {
     /// Other models that also matched (for ambiguity warnings).
     pub other_matches: Vec<String>,
 }
+
+/// A preset present in both snapshots whose fields differ.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresetChange {
+    /// The preset identifier.
+    pub id: &'static str,
+    /// Names of the fields that differ (e.g., "context_window").
+    pub fields: Vec<&'static str>,
+}
+
+/// Structured difference between two sets of model presets, keyed by id.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PresetDiff {
+    /// Ids present in the new set but not the old one, in new-set order.
+    pub added: Vec<&'static str>,
+    /// Ids present in the old set but not the new one, in old-set order.
+    pub removed: Vec<&'static str>,
+    /// Presets present in both sets with differing fields, in new-set order.
+    pub changed: Vec<PresetChange>,
+}
+
+impl PresetDiff {
+    /// Returns true if the two preset sets are identical.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
@@ -163,3 +163,268 @@ fn test_model_preset_clone() {
     assert_eq!(preset.name, cloned.name);
     assert_eq!(preset.provider, cloned.provider);
 }
+
+#[test]
+fn test_diff_presets_identical() {
+    let diff = diff_presets(MODEL_PRESETS, MODEL_PRESETS);
+    assert!(diff.is_empty());
+}
+
+#[test]
+fn test_diff_presets_against_current() {
+    // Hand-built "previous release" snapshot: two models missing, one retired
+    // model present, and gpt-4o with a different context window.
+    let mut old: Vec<ModelPreset> = MODEL_PRESETS
+        .iter()
+        .filter(|p| p.id != "o1" && p.id != "deepseek-reasoner")
+        .cloned()
+        .collect();
+    old.push(ModelPreset {
+        id: "retired-model",
+        name: "Retired Model",
+        provider: "openai",
+        context_window: 8_192,
+        max_output_tokens: 4_096,
+        supports_vision: false,
+        supports_tools: false,
+        supports_reasoning: false,
+        supports_streaming: true,
+        default_temperature: None,
+        reasoning_effort: None,
+        input_cost_per_mtok: None,
+        output_cost_per_mtok: None,
+        deprecated: true,
+        replacement_id: None,
+    });
+    let gpt4o = old
+        .iter_mut()
+        .find(|p| p.id == "gpt-4o")
+        .expect("gpt-4o should exist");
+    gpt4o.context_window = 32_000;
+
+    let diff = diff_presets(&old, MODEL_PRESETS);
+
+    assert_eq!(diff.added, vec!["o1", "deepseek-reasoner"]);
+    assert_eq!(diff.removed, vec!["retired-model"]);
+    assert_eq!(
+        diff.changed,
+        vec![PresetChange {
+            id: "gpt-4o",
+            fields: vec!["context_window"],
+        }]
+    );
+    assert!(!diff.is_empty());
+}
+
+#[test]
+fn test_recommended_params_reasoning_model() {
+    let params = recommended_params("o1").expect("o1 should exist");
+    assert_eq!(
+        params.reasoning_effort,
+        Some(cortex_protocol::ReasoningEffort::Medium)
+    );
+    assert_eq!(params.temperature, Some(1.0));
+}
+
+#[test]
+fn test_recommended_params_chat_model() {
+    let params = recommended_params("gpt-4o").expect("gpt-4o should exist");
+    assert_eq!(params.reasoning_effort, None);
+    assert_eq!(params.temperature, None);
+}
+
+#[test]
+fn test_recommended_params_unknown_model() {
+    assert!(recommended_params("nonexistent-model").is_none());
+}
+
+#[test]
+fn test_reasoning_presets_declare_reasoning_effort() {
+    for preset in MODEL_PRESETS {
+        assert_eq!(
+            preset.supports_reasoning,
+            preset.reasoning_effort.is_some(),
+            "reasoning_effort mismatch for {}",
+            preset.id
+        );
+    }
+}
+
+#[test]
+fn test_estimate_cost_known_pricing() {
+    let preset = get_model_preset("gpt-4o").expect("gpt-4o should exist");
+    let cost = estimate_cost(preset, 1_000_000, 500_000).expect("gpt-4o has pricing");
+    assert!((cost - 7.5).abs() < 1e-9);
+    assert_eq!(estimate_cost(preset, 0, 0), Some(0.0));
+}
+
+#[test]
+fn test_estimate_cost_unknown_pricing() {
+    let preset = get_model_preset("copilot/gpt-4o").expect("copilot/gpt-4o should exist");
+    assert!(preset.input_cost_per_mtok.is_none());
+    assert_eq!(estimate_cost(preset, 1_000, 1_000), None);
+}
+
+#[test]
+fn test_preset_costs_are_paired_and_non_negative() {
+    for preset in MODEL_PRESETS {
+        assert_eq!(
+            preset.input_cost_per_mtok.is_some(),
+            preset.output_cost_per_mtok.is_some(),
+            "cost fields should be set together for {}",
+            preset.id
+        );
+        for cost in [preset.input_cost_per_mtok, preset.output_cost_per_mtok]
+            .into_iter()
+            .flatten()
+        {
+            assert!(cost >= 0.0, "negative cost for {}", preset.id);
+        }
+    }
+}
+
+#[test]
+fn test_get_max_output_tokens_representative_models() {
+    assert_eq!(get_max_output_tokens("gpt-4o"), Some(16_384));
+    assert_eq!(get_max_output_tokens("claude-3-5-sonnet"), Some(8_192));
+    assert_eq!(get_max_output_tokens("claude-3-opus"), Some(4_096));
+    assert_eq!(get_max_output_tokens("o1"), Some(100_000));
+    assert_eq!(get_max_output_tokens("command-r"), Some(4_096));
+}
+
+#[test]
+fn test_get_max_output_tokens_unknown_model() {
+    assert_eq!(get_max_output_tokens("nonexistent-model"), None);
+}
+
+#[test]
+fn test_max_output_tokens_within_context_window() {
+    for preset in MODEL_PRESETS {
+        assert!(
+            preset.max_output_tokens > 0,
+            "zero output cap for {}",
+            preset.id
+        );
+        assert!(
+            i64::from(preset.max_output_tokens) <= preset.context_window,
+            "output cap exceeds context window for {}",
+            preset.id
+        );
+    }
+}
+
+#[test]
+fn test_get_model_preset_fuzzy_alias() {
+    let preset = get_model_preset_fuzzy("sonnet").expect("sonnet should resolve");
+    assert_eq!(preset.id, "claude-3-5-sonnet");
+    assert_eq!(
+        get_model_preset_fuzzy("Opus").unwrap().id,
+        "anthropic/claude-opus-4.5"
+    );
+}
+
+#[test]
+fn test_get_model_preset_fuzzy_exact_and_unknown() {
+    assert_eq!(get_model_preset_fuzzy("gpt-4o").unwrap().id, "gpt-4o");
+    assert!(get_model_preset_fuzzy("not-an-alias").is_none());
+    // The strict lookup does not resolve aliases.
+    assert!(get_model_preset("sonnet").is_none());
+}
+
+#[test]
+fn test_model_supports_streaming() {
+    assert!(model_supports_streaming("gpt-4o"));
+    assert!(model_supports_streaming("claude-3-5-sonnet"));
+    assert!(!model_supports_streaming("o1"));
+    assert!(!model_supports_streaming("copilot/o1-preview"));
+}
+
+#[test]
+fn test_model_supports_streaming_unknown_model() {
+    assert!(!model_supports_streaming("nonexistent-model"));
+}
+
+#[test]
+fn test_deprecated_model_has_replacement() {
+    let preset = get_model_preset("gemini-2.0-flash-exp").expect("preset should still resolve");
+    assert!(preset.deprecated);
+    assert_eq!(preset.replacement_id, Some("gemini-2.0-flash"));
+}
+
+#[test]
+fn test_get_active_models_excludes_deprecated() {
+    let active = get_active_models();
+    assert!(!active.iter().any(|m| m.id == "gemini-2.0-flash-exp"));
+    assert!(active.iter().any(|m| m.id == "gemini-2.0-flash"));
+    assert!(active.iter().all(|m| !m.deprecated));
+    assert!(active.len() < MODEL_PRESETS.len());
+}
+
+#[test]
+fn test_replacements_point_to_active_presets() {
+    for preset in MODEL_PRESETS {
+        if let Some(replacement) = preset.replacement_id {
+            assert!(
+                preset.deprecated,
+                "{} has a replacement but is active",
+                preset.id
+            );
+            let target = get_model_preset(replacement)
+                .unwrap_or_else(|| panic!("{} replacement {} is unknown", preset.id, replacement));
+            assert!(
+                !target.deprecated,
+                "{} replacement {} is deprecated",
+                preset.id, replacement
+            );
+        }
+    }
+}
+
+#[test]
+fn test_group_models_by_provider_covers_every_preset_once() {
+    let groups = group_models_by_provider();
+    assert_eq!(groups.len(), list_providers().len());
+
+    let grouped: Vec<&str> = groups
+        .iter()
+        .flat_map(|(_, models)| models.iter().map(|m| m.id))
+        .collect();
+    assert_eq!(grouped.len(), MODEL_PRESETS.len());
+    for preset in MODEL_PRESETS {
+        assert_eq!(
+            grouped.iter().filter(|id| **id == preset.id).count(),
+            1,
+            "{} should appear exactly once",
+            preset.id
+        );
+    }
+
+    for (provider, models) in &groups {
+        assert!(models.iter().all(|m| m.provider == *provider));
+    }
+}
+
+#[test]
+fn test_list_providers_first_seen_order() {
+    let providers = list_providers();
+    assert_eq!(&providers[..3], &["openai", "anthropic", "google"]);
+    let groups = group_models_by_provider();
+    let group_order: Vec<&str> = groups.iter().map(|(p, _)| *p).collect();
+    assert_eq!(group_order, providers);
+}
+
+#[test]
+fn test_smallest_model_fitting_upgrades_to_larger_window() {
+    let preset = smallest_model_fitting("google", 1_500_000).expect("gemini pro fits");
+    assert_eq!(preset.id, "gemini-1.5-pro");
+
+    // Several Gemini models share a 1M window; the first active one wins.
+    let preset = smallest_model_fitting("google", 500_000).unwrap();
+    assert_eq!(preset.id, "gemini-2.0-flash");
+}
+
+#[test]
+fn test_smallest_model_fitting_none_fit() {
+    assert!(smallest_model_fitting("google", 3_000_000).is_none());
+    assert!(smallest_model_fitting("nonexistent", 1).is_none());
+}
@@ -6,17 +6,27 @@
 
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
 use serde::Serialize;
 use serde_json::Value;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
 use tracing::{debug, error, info};
 
 use crate::acp::handler::{AcpHandler, AcpNotificationEvent};
 use crate::acp::protocol::{AcpError, AcpNotification, AcpRequest, AcpRequestId, AcpResponse};
 use crate::config::Config;
 
+/// Default maximum HTTP request body size (16 MiB).
+pub const DEFAULT_MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+
+/// Maximum size of the HTTP request line and headers.
+const MAX_HEADER_SIZE: usize = 64 * 1024;
+
+/// Time allowed to receive a complete HTTP request.
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// ACP Server supporting both stdio and HTTP transports.
 #[allow(dead_code)]
 pub struct AcpServer {
@@ -24,13 +34,58 @@ pub struct AcpServer {
     handler: Arc<AcpHandler>,
     /// Configuration.
     config: Config,
+    /// Maximum accepted HTTP request body size in bytes.
+    max_body_size: usize,
+}
+
+/// An HTTP request read from a connection.
+#[derive(Debug)]
+struct HttpRequest {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+/// Why an HTTP request could not be read, and the status to answer with.
+#[derive(Debug)]
+struct HttpReadError {
+    status: u16,
+    error: AcpError,
+}
+
+impl HttpReadError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: 400,
+            error: AcpError::invalid_request(message),
+        }
+    }
+
+    fn too_large(size: usize, max: usize) -> Self {
+        Self {
+            status: 413,
+            error: AcpError::invalid_request(format!(
+                "Request body of {size} bytes exceeds the {max} byte limit"
+            )),
+        }
+    }
 }
 
 impl AcpServer {
     /// Create a new ACP server.
     pub fn new(config: Config) -> Self {
         let handler = Arc::new(AcpHandler::new(config.clone()));
-        Self { handler, config }
+        Self {
+            handler,
+            config,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+
+    /// Set the maximum accepted HTTP request body size in bytes.
+    pub fn with_max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
     }
 
     /// Run the server with stdio transport.
@@ -118,6 +173,7 @@ impl AcpServer {
         // Create a simple HTTP server using tokio's TCP listener
         let listener = tokio::net::TcpListener::bind(addr).await?;
         let handler = self.handler.clone();
+        let max_body_size = self.max_body_size;
 
         loop {
             let (stream, peer_addr) = listener.accept().await?;
@@ -125,7 +181,7 @@ impl AcpServer {
 
             let handler = handler.clone();
             tokio::spawn(async move {
-                if let Err(e) = Self::handle_http_connection(stream, handler).await {
+                if let Err(e) = Self::handle_http_connection(stream, handler, max_body_size).await {
                     error!("HTTP connection error: {}", e);
                 }
             });
@@ -136,49 +192,28 @@ impl AcpServer {
     async fn handle_http_connection(
         mut stream: tokio::net::TcpStream,
         handler: Arc<AcpHandler>,
+        max_body_size: usize,
     ) -> Result<()> {
-        use tokio::io::AsyncReadExt;
-
-        let mut buffer = vec![0u8; 8192];
-        let n = stream.read(&mut buffer).await?;
-
-        if n == 0 {
-            return Ok(());
-        }
-
-        let request_str = String::from_utf8_lossy(&buffer[..n]);
-        let lines: Vec<&str> = request_str.lines().collect();
-
-        // Parse HTTP request
-        let first_line = lines.first().unwrap_or(&"");
-        let parts: Vec<&str> = first_line.split_whitespace().collect();
-
-        if parts.len() < 3 {
-            Self::send_http_error(&mut stream, 400, "Bad Request").await?;
-            return Ok(());
-        }
-
-        let method = parts[0];
-        let path = parts[1];
+        let read = tokio::time::timeout(
+            REQUEST_READ_TIMEOUT,
+            Self::read_http_request(&mut stream, max_body_size),
+        )
+        .await
+        .unwrap_or_else(|_| Err(HttpReadError::bad_request("Timed out reading request")));
+
+        let request = match read {
+            Ok(Some(request)) => request,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                let err_response = AcpResponse::error(AcpRequestId::Number(0), e.error);
+                Self::send_http_json(&mut stream, e.status, &err_response).await?;
+                return Ok(());
+            }
+        };
 
-        match (method, path) {
+        match (request.method.as_str(), request.path.as_str()) {
             ("POST", "/rpc") | ("POST", "/acp/rpc") | ("POST", "/") => {
-                // Find the body (after empty line)
-                let body_start = request_str
-                    .find("\r\n\r\n")
-                    .or_else(|| request_str.find("\n\n"));
-                let body = body_start
-                    .map(|i| {
-                        let skip = if request_str[i..].starts_with("\r\n\r\n") {
-                            4
-                        } else {
-                            2
-                        };
-                        &request_str[i + skip..]
-                    })
-                    .unwrap_or("");
-
-                let request: AcpRequest = match serde_json::from_str(body.trim()) {
+                let request: AcpRequest = match serde_json::from_slice(&request.body) {
                     Ok(req) => req,
                     Err(e) => {
                         let err_response = AcpResponse::error(
@@ -223,6 +258,93 @@ impl AcpServer {
         Ok(())
     }
 
+    /// Read one HTTP request, honoring its `Content-Length` header.
+    ///
+    /// Without a `Content-Length`, the body is whatever arrived with the
+    /// headers. Returns `Ok(None)` if the peer closed without sending
+    /// anything.
+    async fn read_http_request<R: AsyncRead + Unpin>(
+        reader: &mut R,
+        max_body_size: usize,
+    ) -> std::result::Result<Option<HttpRequest>, HttpReadError> {
+        let mut buffer = Vec::with_capacity(8192);
+        let mut chunk = vec![0u8; 8192];
+
+        let (head_end, body_start) = loop {
+            if let Some(bounds) = find_header_end(&buffer) {
+                break bounds;
+            }
+            if buffer.len() > MAX_HEADER_SIZE {
+                return Err(HttpReadError::bad_request("Request headers too large"));
+            }
+            let n = reader
+                .read(&mut chunk)
+                .await
+                .map_err(|e| HttpReadError::bad_request(e.to_string()))?;
+            if n == 0 {
+                if buffer.is_empty() {
+                    return Ok(None);
+                }
+                break (buffer.len(), buffer.len());
+            }
+            buffer.extend_from_slice(&chunk[..n]);
+        };
+
+        let head = String::from_utf8_lossy(&buffer[..head_end]).into_owned();
+        let mut lines = head.lines();
+
+        // Parse HTTP request line
+        let parts: Vec<&str> = lines.next().unwrap_or("").split_whitespace().collect();
+        if parts.len() < 3 {
+            return Err(HttpReadError::bad_request("Bad Request"));
+        }
+
+        let content_length = lines
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+            .map(|(_, value)| {
+                value
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| HttpReadError::bad_request("Invalid Content-Length header"))
+            })
+            .transpose()?;
+
+        let mut body = buffer.split_off(body_start);
+        match content_length {
+            Some(len) if len > max_body_size => {
+                return Err(HttpReadError::too_large(len, max_body_size));
+            }
+            Some(len) => {
+                while body.len() < len {
+                    let n = reader
+                        .read(&mut chunk)
+                        .await
+                        .map_err(|e| HttpReadError::bad_request(e.to_string()))?;
+                    if n == 0 {
+                        return Err(HttpReadError::bad_request(format!(
+                            "Connection closed after {} of {} body bytes",
+                            body.len(),
+                            len
+                        )));
+                    }
+                    body.extend_from_slice(&chunk[..n]);
+                }
+                body.truncate(len);
+            }
+            None if body.len() > max_body_size => {
+                return Err(HttpReadError::too_large(body.len(), max_body_size));
+            }
+            None => {}
+        }
+
+        Ok(Some(HttpRequest {
+            method: parts[0].to_string(),
+            path: parts[1].to_string(),
+            body,
+        }))
+    }
+
     /// Handle SSE stream.
     async fn handle_sse_stream(
         stream: &mut tokio::net::TcpStream,
@@ -284,6 +406,7 @@ impl AcpServer {
             200 => "OK",
             400 => "Bad Request",
             404 => "Not Found",
+            413 => "Payload Too Large",
             500 => "Internal Server Error",
             _ => "Unknown",
         };
@@ -332,3 +455,110 @@ impl AcpServer {
         self.run_stdio().await
     }
 }
+
+/// Byte offsets of the end of the headers and the start of the body.
+fn find_header_end(buffer: &[u8]) -> Option<(usize, usize)> {
+    let crlf = buffer
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .map(|i| (i, i + 4));
+    let lf = buffer
+        .windows(2)
+        .position(|w| w == b"\n\n")
+        .map(|i| (i, i + 2));
+    match (crlf, lf) {
+        (Some(a), Some(b)) => Some(if a.0 <= b.0 { a } else { b }),
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(body: &str) -> Vec<u8> {
+        format!(
+            "POST /rpc HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .into_bytes()
+    }
+
+    #[tokio::test]
+    async fn test_reads_body_larger_than_one_buffer() {
+        let image = "iVBORw0KGgo".repeat(4096);
+        let body = serde_json::to_string(
+            &AcpRequest::new(1i64, "session/prompt")
+                .with_params(serde_json::json!({ "image": image })),
+        )
+        .unwrap();
+        assert!(body.len() > 8192);
+
+        // Deliver the request in several fragments, as TCP may.
+        let raw = post(&body);
+        let mut builder = tokio_test::io::Builder::new();
+        for part in raw.chunks(3000) {
+            builder.read(part);
+        }
+        let mut reader = builder.build();
+
+        let request = AcpServer::read_http_request(&mut reader, DEFAULT_MAX_BODY_SIZE)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/rpc");
+        assert_eq!(request.body, body.as_bytes());
+
+        let parsed: AcpRequest = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(parsed.method, "session/prompt");
+        assert_eq!(parsed.params.unwrap()["image"], image);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_oversized_body() {
+        let body = "x".repeat(2048);
+        let raw = post(&body);
+        let mut reader = raw.as_slice();
+
+        let err = AcpServer::read_http_request(&mut reader, 1024)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, 413);
+        assert_eq!(err.error.code, -32600);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_truncated_body() {
+        let raw = b"POST /rpc HTTP/1.1\r\nContent-Length: 100\r\n\r\n{\"jsonrpc\"";
+        let mut reader = &raw[..];
+
+        let err = AcpServer::read_http_request(&mut reader, DEFAULT_MAX_BODY_SIZE)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, 400);
+    }
+
+    #[tokio::test]
+    async fn test_request_without_content_length() {
+        let raw = b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        let mut reader = &raw[..];
+
+        let request = AcpServer::read_http_request(&mut reader, DEFAULT_MAX_BODY_SIZE)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/health");
+        assert!(request.body.is_empty());
+
+        let mut empty: &[u8] = &[];
+        assert!(
+            AcpServer::read_http_request(&mut empty, DEFAULT_MAX_BODY_SIZE)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -86,12 +86,12 @@ impl AgentContext {
         Ok(())
     }
 
-    /// Rough estimate of tokens (4 characters per token).
+    /// Rough estimate of tokens, via [`cortex_prompt_harness::estimate_tokens`].
     fn estimate_tokens(&self, messages: &[Message]) -> u32 {
         let mut total = 0;
         for msg in messages {
             if let Some(text) = msg.content.as_text() {
-                total += (text.len() / 4) as u32;
+                total += cortex_prompt_harness::estimate_tokens(text);
             }
         }
         total
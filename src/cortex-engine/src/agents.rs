@@ -95,6 +95,9 @@ pub struct AgentMetadata {
     pub system_prompt: Option<String>,
     /// Path to system prompt file.
     pub prompt_file: Option<String>,
+    /// Base prompt the agent's prompt extends (`base` or `cortex-main`).
+    /// When unset, the agent's prompt replaces the base prompt.
+    pub extends: Option<String>,
     /// Tags for categorization.
     #[serde(default)]
     pub tags: Vec<String>,
@@ -130,6 +133,7 @@ impl AgentMetadata {
             denied_tools: Vec::new(),
             system_prompt: None,
             prompt_file: None,
+            extends: None,
             tags: Vec::new(),
             can_delegate: true,
             max_turns: None,
@@ -359,6 +363,7 @@ impl AgentRegistry {
                     denied_tools: Vec::new(),
                     system_prompt: None,
                     prompt_file: None,
+                    extends: None,
                     tags: vec!["code".to_string(), "analysis".to_string()],
                     can_delegate: false,
                     max_turns: Some(10),
@@ -383,6 +388,7 @@ impl AgentRegistry {
                     denied_tools: vec!["Execute".to_string()],
                     system_prompt: None,
                     prompt_file: None,
+                    extends: None,
                     tags: vec!["review".to_string(), "quality".to_string()],
                     can_delegate: false,
                     max_turns: Some(5),
@@ -408,6 +414,7 @@ impl AgentRegistry {
                     denied_tools: vec!["Execute".to_string()],
                     system_prompt: None,
                     prompt_file: None,
+                    extends: None,
                     tags: vec!["architecture".to_string(), "design".to_string()],
                     can_delegate: true,
                     max_turns: Some(15),
@@ -833,6 +840,7 @@ mod tests {
                 denied_tools: vec!["Execute".to_string()],
                 system_prompt: None,
                 prompt_file: None,
+                extends: None,
                 tags: Vec::new(),
                 can_delegate: true,
                 max_turns: None,
@@ -28,6 +28,9 @@ pub const CORTEX_CONFIG_ENV: &str = "CORTEX_CONFIG";
 /// Environment variable for custom config directory.
 pub const CORTEX_CONFIG_DIR_ENV: &str = "CORTEX_CONFIG_DIR";
 
+/// Environment variable overriding the configured prompt mode.
+pub const CORTEX_PROMPT_MODE_ENV: &str = "CORTEX_PROMPT_MODE";
+
 /// Find the Cortex home directory.
 ///
 /// Checks in order:
@@ -20,9 +20,10 @@ pub use config_discovery::{
     git_root, is_in_git_repo,
 };
 pub use loader::{
-    CONFIG_FILE_JSON, CONFIG_FILE_JSONC, CORTEX_CONFIG_DIR_ENV, CORTEX_CONFIG_ENV, ConfigFormat,
-    find_cortex_home, get_config_path, load_config, load_config_sync, load_merged_config,
-    load_merged_config_sync, parse_config_content, strip_json_comments,
+    CONFIG_FILE_JSON, CONFIG_FILE_JSONC, CORTEX_CONFIG_DIR_ENV, CORTEX_CONFIG_ENV,
+    CORTEX_PROMPT_MODE_ENV, ConfigFormat, find_cortex_home, get_config_path, load_config,
+    load_config_sync, load_merged_config, load_merged_config_sync, parse_config_content,
+    strip_json_comments,
 };
 pub use project_config::{
     PROJECT_CONFIG_NAMES, find_project_config, get_project_config_dir, get_project_config_path,
@@ -36,6 +37,9 @@ use std::path::PathBuf;
 
 use cortex_protocol::{AskForApproval, SandboxPolicy};
 
+/// Default cap on the combined size of loaded AGENTS.md instructions.
+pub const DEFAULT_AGENTS_MD_MAX_BYTES: usize = 64 * 1024;
+
 /// Main configuration struct.
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -91,6 +95,12 @@ pub struct Config {
     pub temperature: Option<f32>,
     /// Execution configuration for runtime behavior.
     pub execution: ExecutionConfig,
+    /// How skills are selected for the system prompt.
+    pub skill_selection: SkillSelection,
+    /// Base prompt mode: skill-based or monolithic.
+    pub prompt_mode: PromptMode,
+    /// Maximum combined size in bytes of AGENTS.md instructions.
+    pub agents_md_max_bytes: usize,
 }
 
 impl Default for Config {
@@ -122,6 +132,9 @@ impl Default for Config {
             small_model: None, // Auto-detected based on available providers
             temperature: None,
             execution: ExecutionConfig::default(),
+            skill_selection: SkillSelection::default(),
+            prompt_mode: PromptMode::default(),
+            agents_md_max_bytes: DEFAULT_AGENTS_MD_MAX_BYTES,
         }
     }
 }
@@ -138,6 +151,7 @@ impl Config {
     /// - `CORTEX_CONFIG`: Path to a specific config file
     /// - `CORTEX_CONFIG_DIR`: Directory containing config.toml
     /// - `CORTEX_HOME`: Alias for `CORTEX_CONFIG_DIR`
+    /// - `CORTEX_PROMPT_MODE`: `skills` or `monolithic`, overriding `prompt_mode`
     pub async fn load(overrides: ConfigOverrides) -> std::io::Result<Self> {
         let cortex_home = find_cortex_home()?;
 
@@ -240,10 +254,36 @@ impl Config {
             // CLI temperature override takes precedence
             temperature: overrides.temperature,
             execution: toml.execution,
+            skill_selection: toml.skill_selection.unwrap_or_default(),
+            prompt_mode: effective_prompt_mode(
+                std::env::var(CORTEX_PROMPT_MODE_ENV).ok().as_deref(),
+                toml.prompt_mode,
+            ),
+            agents_md_max_bytes: toml
+                .agents_md_max_bytes
+                .unwrap_or(DEFAULT_AGENTS_MD_MAX_BYTES),
         }
     }
 }
 
+/// Resolve the prompt mode from a `CORTEX_PROMPT_MODE` value and the config file.
+///
+/// A recognized environment value wins; unrecognized ones are ignored with a
+/// warning.
+pub(crate) fn effective_prompt_mode(
+    env_value: Option<&str>,
+    configured: Option<PromptMode>,
+) -> PromptMode {
+    let from_env = env_value.and_then(|value| {
+        let mode = PromptMode::parse(value);
+        if mode.is_none() && !value.is_empty() {
+            tracing::warn!(value = %value, "Ignoring unrecognized {}", CORTEX_PROMPT_MODE_ENV);
+        }
+        mode
+    });
+    from_env.or(configured).unwrap_or_default()
+}
+
 /// Configuration overrides from CLI.
 #[derive(Debug, Clone, Default)]
 pub struct ConfigOverrides {
@@ -147,6 +147,9 @@ pub fn merge_configs(global: ConfigToml, project: Option<ConfigToml>) -> ConfigT
         model_reasoning_summary: project
             .model_reasoning_summary
             .or(global.model_reasoning_summary),
+        skill_selection: project.skill_selection.or(global.skill_selection),
+        prompt_mode: project.prompt_mode.or(global.prompt_mode),
+        agents_md_max_bytes: project.agents_md_max_bytes.or(global.agents_md_max_bytes),
         hide_agent_reasoning: project.hide_agent_reasoning.or(global.hide_agent_reasoning),
         show_raw_agent_reasoning: project
             .show_raw_agent_reasoning
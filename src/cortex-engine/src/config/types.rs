@@ -80,6 +80,12 @@ pub struct ConfigToml {
     pub history: Option<HistoryConfig>,
     pub model_reasoning_effort: Option<ReasoningEffort>,
     pub model_reasoning_summary: Option<ReasoningSummary>,
+    /// Skill selection mode for the system prompt.
+    pub skill_selection: Option<SkillSelection>,
+    /// Base prompt mode for sessions.
+    pub prompt_mode: Option<PromptMode>,
+    /// Maximum combined size in bytes of AGENTS.md instructions.
+    pub agents_md_max_bytes: Option<usize>,
     pub hide_agent_reasoning: Option<bool>,
     pub show_raw_agent_reasoning: Option<bool>,
     pub check_for_update_on_startup: Option<bool>,
@@ -208,6 +214,54 @@ pub enum ReasoningSummary {
     Auto,
 }
 
+/// How skills are chosen when building the session system prompt.
+///
+/// In TOML this is written as `skill_selection = "auto"`, `"off"`, or
+/// `skill_selection = { explicit = ["git", "debugging"] }`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillSelection {
+    /// Pre-load exactly these skills.
+    Explicit(Vec<String>),
+    /// Detect skills from the first user message, in the skill-based
+    /// [`PromptMode`] only.
+    #[default]
+    Auto,
+    /// Use the full monolithic prompt without skills, whatever the
+    /// [`PromptMode`].
+    Off,
+}
+
+/// Which base prompt sessions are built on.
+///
+/// In TOML this is written as `prompt_mode = "skills"` or `"monolithic"`;
+/// the `CORTEX_PROMPT_MODE` environment variable overrides it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptMode {
+    /// Minimal base prompt with skills loaded on demand.
+    #[default]
+    Skills,
+    /// The full monolithic `CORTEX_MAIN_PROMPT`.
+    Monolithic,
+}
+
+impl PromptMode {
+    /// Parse a mode name as written in `CORTEX_PROMPT_MODE` (case-insensitive).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "skills" => Some(Self::Skills),
+            "monolithic" => Some(Self::Monolithic),
+            _ => None,
+        }
+    }
+
+    /// Whether this mode uses the skill-based base prompt.
+    pub fn is_skill_based(self) -> bool {
+        self == Self::Skills
+    }
+}
+
 /// TUI configuration.
 #[derive(Debug, Clone, Default, Deserialize)]
 pub struct TuiConfig {
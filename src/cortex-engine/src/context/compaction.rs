@@ -2,9 +2,15 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::conversation::Conversation;
-use crate::client::types::{Message, MessageRole};
-use crate::error::Result;
+use super::conversation::{Conversation, estimate_tokens};
+use crate::client::types::{Message, MessageContent, MessageRole};
+use crate::error::{CortexError, Result};
+
+/// Placeholder content for tool results elided by `max_tool_results`.
+pub const ELIDED_TOOL_OUTPUT: &str = "[tool output elided]";
+
+/// Prefix tagging synthetic summary messages so they are never re-summarized.
+pub const SUMMARY_TAG: &str = "[Conversation summary]";
 
 /// Compaction strategy for reducing context size.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +27,19 @@ pub struct CompactionStrategy {
     pub preserve_tools: bool,
     /// Maximum summary length.
     pub max_summary_length: usize,
+    /// Keep full content only for the N most recent tool results.
+    ///
+    /// Older tool results are replaced with a short stub rather than removed,
+    /// so the tool call/result pairing stays intact.
+    #[serde(default)]
+    pub max_tool_results: Option<usize>,
+    /// Merge adjacent plain-text messages with the same role before compacting.
+    #[serde(default = "default_merge_same_role")]
+    pub merge_same_role: bool,
+}
+
+fn default_merge_same_role() -> bool {
+    true
 }
 
 impl Default for CompactionStrategy {
@@ -32,10 +51,53 @@ impl Default for CompactionStrategy {
             preserve_system: true,
             preserve_tools: true,
             max_summary_length: 500,
+            max_tool_results: None,
+            merge_same_role: default_merge_same_role(),
         }
     }
 }
 
+/// Validated `target_ratio` and `preserve_recent` for a [`CompactionStrategy`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompactionConfig {
+    target_ratio: f32,
+    preserve_recent: usize,
+}
+
+impl CompactionConfig {
+    /// Create a config, rejecting a `target_ratio` outside `(0.0, 1.0]`.
+    ///
+    /// `preserve_recent` may exceed the conversation length; it is clamped to
+    /// the number of messages at compaction time.
+    pub fn new(target_ratio: f32, preserve_recent: usize) -> Result<Self> {
+        if !(target_ratio > 0.0 && target_ratio <= 1.0) {
+            return Err(CortexError::InvalidConfig {
+                field: "target_ratio".to_string(),
+                message: format!("must be in (0.0, 1.0], got {target_ratio}"),
+            });
+        }
+        Ok(Self {
+            target_ratio,
+            preserve_recent,
+        })
+    }
+
+    /// Fraction of the conversation to keep.
+    pub fn target_ratio(&self) -> f32 {
+        self.target_ratio
+    }
+
+    /// Number of recent messages to keep, before clamping.
+    pub fn preserve_recent(&self) -> usize {
+        self.preserve_recent
+    }
+
+    /// `preserve_recent` clamped to a conversation of `len` messages.
+    pub fn preserve_recent_for(&self, len: usize) -> usize {
+        self.preserve_recent.min(len)
+    }
+}
+
 /// Strategy type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -54,6 +116,23 @@ pub enum StrategyType {
     Custom,
 }
 
+/// Compaction policy chosen explicitly by the caller of
+/// [`CompactionStrategy::compact_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactionPolicy {
+    /// Keep the most important messages, down to `target_ratio` of the
+    /// conversation.
+    Importance,
+    /// Keep system messages and the `preserve_recent` most recent messages.
+    Recency,
+    /// Drop the least important older messages until the conversation fits
+    /// in the given number of tokens.
+    TokenBudget(u32),
+    /// Collapse older messages into a single summary message.
+    Summary,
+}
+
 impl CompactionStrategy {
     /// Create a sliding window strategy.
     pub fn sliding(preserve_recent: usize) -> Self {
@@ -82,24 +161,218 @@ impl CompactionStrategy {
         }
     }
 
-    /// Compact a conversation.
-    pub fn compact(&self, conversation: &mut Conversation) -> Result<()> {
-        match self.strategy {
-            StrategyType::Sliding => self.compact_sliding(conversation),
-            StrategyType::Summarize => self.compact_summarize(conversation),
-            StrategyType::Importance => self.compact_importance(conversation),
-            StrategyType::Hybrid => self.compact_hybrid(conversation),
-            StrategyType::TurnBased => self.compact_turn_based(conversation),
-            StrategyType::Custom => Ok(()), // No-op for custom
+    /// Apply a validated `target_ratio` / `preserve_recent` pair.
+    pub fn with_config(mut self, config: CompactionConfig) -> Self {
+        self.target_ratio = config.target_ratio;
+        self.preserve_recent = config.preserve_recent;
+        self
+    }
+
+    /// Validate this strategy's `target_ratio` and `preserve_recent`.
+    pub fn config(&self) -> Result<CompactionConfig> {
+        CompactionConfig::new(self.target_ratio, self.preserve_recent)
+    }
+
+    /// Keep full content only for the `max` most recent tool results.
+    pub fn with_max_tool_results(mut self, max: usize) -> Self {
+        self.max_tool_results = Some(max);
+        self
+    }
+
+    /// Compact a conversation with the configured `strategy`.
+    ///
+    /// Fails with [`CortexError::InvalidConfig`] if [`Self::config`] does,
+    /// leaving the conversation untouched. Returns a [`CompactionReport`] of
+    /// what the compaction dropped.
+    pub fn compact(&self, conversation: &mut Conversation) -> Result<CompactionReport> {
+        let strategy = AppliedStrategy::Configured(self.strategy);
+        self.compact_reported(conversation, strategy, |this, conversation| {
+            match this.strategy {
+                StrategyType::Sliding => this.compact_sliding(conversation),
+                StrategyType::Summarize => this.compact_summarize(conversation),
+                StrategyType::Importance => this.compact_importance(conversation),
+                StrategyType::Hybrid => this.compact_hybrid(conversation),
+                StrategyType::TurnBased => this.compact_turn_based(conversation),
+                StrategyType::Custom => Ok(()), // No-op for custom
+            }
+        })
+    }
+
+    /// Compact a conversation with an explicitly chosen policy.
+    ///
+    /// The configured `strategy` is ignored; every other setting applies.
+    /// Returns a [`CompactionReport`] of what the compaction dropped.
+    pub fn compact_with(
+        &self,
+        conversation: &mut Conversation,
+        policy: CompactionPolicy,
+    ) -> Result<CompactionReport> {
+        let strategy = AppliedStrategy::Policy(policy);
+        self.compact_reported(conversation, strategy, |this, conversation| match policy {
+            CompactionPolicy::Importance => this.compact_importance(conversation),
+            CompactionPolicy::Recency => this.compact_sliding(conversation),
+            CompactionPolicy::TokenBudget(max_tokens) => {
+                this.compact_to_token_budget(conversation, max_tokens)
+            }
+            CompactionPolicy::Summary => this.compact_with_summary(conversation, |block| {
+                create_simple_summary(block, this.max_summary_length)
+            }),
+        })
+    }
+
+    /// Run the shared passes and `compact`, then report the net change.
+    fn compact_reported<F>(
+        &self,
+        conversation: &mut Conversation,
+        strategy: AppliedStrategy,
+        compact: F,
+    ) -> Result<CompactionReport>
+    where
+        F: FnOnce(&Self, &mut Conversation) -> Result<()>,
+    {
+        self.config()?;
+
+        let before_count = conversation.len();
+        let before_tokens = conversation_tokens(conversation);
+
+        self.prepare(conversation);
+        compact(self, conversation)?;
+
+        Ok(CompactionReport {
+            removed_count: before_count.saturating_sub(conversation.len()),
+            removed_token_estimate: before_tokens.saturating_sub(conversation_tokens(conversation)),
+            preserved_count: conversation.len(),
+            strategy,
+        })
+    }
+
+    /// Passes applied before any strategy.
+    fn prepare(&self, conversation: &mut Conversation) {
+        if self.merge_same_role {
+            merge_same_role_messages(conversation.messages_mut());
+        }
+        if let Some(max) = self.max_tool_results {
+            elide_old_tool_results(conversation.messages_mut(), max);
         }
     }
 
+    /// Drop the lowest-importance messages until the conversation fits in
+    /// `max_tokens`, using the conversation's ~4 chars/token estimate.
+    ///
+    /// The `preserve_recent` most recent messages are always kept; if they
+    /// alone exceed the budget, the conversation is left untouched and
+    /// [`CortexError::ContextWindowExceeded`] is returned.
+    pub fn compact_to_token_budget(
+        &self,
+        conversation: &mut Conversation,
+        max_tokens: u32,
+    ) -> Result<()> {
+        self.compact_to_token_budget_with(conversation, max_tokens, estimate_tokens)
+    }
+
+    /// Like [`Self::compact_to_token_budget`] with a custom per-message
+    /// token estimator.
+    pub fn compact_to_token_budget_with<F>(
+        &self,
+        conversation: &mut Conversation,
+        max_tokens: u32,
+        estimate: F,
+    ) -> Result<()>
+    where
+        F: Fn(&Message) -> u32,
+    {
+        let config = self.config()?;
+        let messages = conversation.messages_mut();
+        let total = messages.len();
+        let start_recent = total - config.preserve_recent_for(total);
+
+        let tokens: Vec<u32> = messages.iter().map(&estimate).collect();
+        let preserved: u32 = tokens[start_recent..].iter().sum();
+        if preserved > max_tokens {
+            return Err(CortexError::ContextWindowExceeded {
+                used: preserved as i64,
+                limit: max_tokens as i64,
+            });
+        }
+
+        let mut used: u32 = tokens.iter().sum();
+        if used <= max_tokens {
+            return Ok(());
+        }
+
+        // Drop older messages, least important first
+        let mut candidates: Vec<(usize, f32)> = messages[..start_recent]
+            .iter()
+            .enumerate()
+            .map(|(i, msg)| (i, calculate_importance(msg, i, total)))
+            .collect();
+        candidates.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut drop = vec![false; total];
+        for (i, _) in candidates {
+            if used <= max_tokens {
+                break;
+            }
+            drop[i] = true;
+            used -= tokens[i];
+        }
+
+        let mut index = 0;
+        messages.retain(|_| {
+            let keep = !drop[index];
+            index += 1;
+            keep
+        });
+
+        Ok(())
+    }
+
+    /// Collapse the longest contiguous block of older messages into a single
+    /// summary message produced by `summarize`.
+    ///
+    /// The `preserve_recent` most recent messages, system messages and
+    /// earlier summaries are never part of the block. The summary is a system
+    /// message tagged with [`SUMMARY_TAG`].
+    pub fn compact_with_summary<F>(
+        &self,
+        conversation: &mut Conversation,
+        mut summarize: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&[Message]) -> String,
+    {
+        let config = self.config()?;
+        let messages = conversation.messages_mut();
+        let start_recent = messages.len() - config.preserve_recent_for(messages.len());
+
+        let Some((start, mut end)) = longest_summarizable_run(&messages[..start_recent]) else {
+            return Ok(());
+        };
+        // Keep a trailing tool call next to its results
+        while end > start && messages[end - 1].tool_calls.is_some() {
+            end -= 1;
+        }
+        if end - start < 2 {
+            return Ok(());
+        }
+
+        let summary = summarize(&messages[start..end]);
+        messages.splice(
+            start..end,
+            [Message::system(format!("{SUMMARY_TAG}\n{summary}"))],
+        );
+
+        Ok(())
+    }
+
     /// Sliding window compaction.
     fn compact_sliding(&self, conversation: &mut Conversation) -> Result<()> {
+        let config = self.config()?;
         let messages = conversation.messages_mut();
         let total = messages.len();
 
-        if total <= self.preserve_recent {
+        let preserve_recent = config.preserve_recent_for(total);
+        if total <= preserve_recent {
             return Ok(());
         }
 
@@ -116,7 +389,7 @@ impl CompactionStrategy {
         }
 
         // Keep recent messages
-        let start_recent = total.saturating_sub(self.preserve_recent);
+        let start_recent = total - preserve_recent;
         for i in start_recent..total {
             if !keep_indices.contains(&i) {
                 keep_indices.push(i);
@@ -137,15 +410,17 @@ impl CompactionStrategy {
 
     /// Summarization compaction (placeholder - would need LLM call).
     fn compact_summarize(&self, conversation: &mut Conversation) -> Result<()> {
+        let config = self.config()?;
         let messages = conversation.messages_mut();
         let total = messages.len();
 
-        if total <= self.preserve_recent + 1 {
+        let preserve_recent = config.preserve_recent_for(total);
+        if total <= preserve_recent + 1 {
             return Ok(());
         }
 
         // Calculate how many messages to summarize
-        let summarize_count = total.saturating_sub(self.preserve_recent);
+        let summarize_count = total - preserve_recent;
         if summarize_count == 0 {
             return Ok(());
         }
@@ -157,19 +432,16 @@ impl CompactionStrategy {
         let summary = create_simple_summary(&to_summarize, self.max_summary_length);
 
         // Insert summary as system message at start
-        messages.insert(
-            0,
-            Message::system(format!("[Conversation summary]\n{summary}")),
-        );
+        messages.insert(0, Message::system(format!("{SUMMARY_TAG}\n{summary}")));
 
         Ok(())
     }
 
     /// Importance-based compaction.
     fn compact_importance(&self, conversation: &mut Conversation) -> Result<()> {
+        let config = self.config()?;
         let messages = conversation.messages_mut();
         let total_len = messages.len();
-        let capacity = messages.capacity();
 
         // Score each message by importance
         let mut scored: Vec<(usize, f32, Message)> = messages
@@ -185,8 +457,8 @@ impl CompactionStrategy {
         scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
         // Calculate target count
-        let target_count = (capacity as f32 * self.target_ratio) as usize;
-        let target_count = target_count.max(self.preserve_recent);
+        let target_count = (total_len as f32 * config.target_ratio()) as usize;
+        let target_count = target_count.max(config.preserve_recent_for(total_len));
 
         // Keep top N by importance, then restore order
         scored.truncate(target_count);
@@ -204,7 +476,8 @@ impl CompactionStrategy {
         self.compact_summarize(conversation)?;
 
         // Second pass: importance-based trimming if still too large
-        if conversation.len() > self.preserve_recent * 2 {
+        let preserve_recent = self.config()?.preserve_recent_for(conversation.len());
+        if conversation.len() > preserve_recent.saturating_mul(2) {
             self.compact_importance(conversation)?;
         }
 
@@ -240,6 +513,95 @@ impl CompactionStrategy {
     }
 }
 
+/// Whether `message` is a summary produced by compaction.
+pub fn is_summary_message(message: &Message) -> bool {
+    message.role == MessageRole::System
+        && message
+            .content
+            .as_text()
+            .is_some_and(|text| text.starts_with(SUMMARY_TAG))
+}
+
+/// Bounds of the longest run of non-system messages (oldest wins ties).
+fn longest_summarizable_run(messages: &[Message]) -> Option<(usize, usize)> {
+    let mut best: Option<(usize, usize)> = None;
+    let mut run_start = None;
+    for (i, msg) in messages.iter().enumerate() {
+        if msg.role != MessageRole::System {
+            run_start.get_or_insert(i);
+            continue;
+        }
+        if let Some(start) = run_start.take()
+            && best.is_none_or(|(s, e)| i - start > e - s)
+        {
+            best = Some((start, i));
+        }
+    }
+    if let Some(start) = run_start
+        && best.is_none_or(|(s, e)| messages.len() - start > e - s)
+    {
+        best = Some((start, messages.len()));
+    }
+    best
+}
+
+/// Merge runs of adjacent same-role text messages into one message.
+///
+/// System messages, tool results, messages carrying tool calls, and
+/// multi-part content are never merged, so the system prompt and summaries
+/// stay separate and the tool call/result structure is preserved.
+fn merge_same_role_messages(messages: &mut Vec<Message>) {
+    fn mergeable(msg: &Message) -> bool {
+        msg.role != MessageRole::Tool
+            && msg.role != MessageRole::System
+            && msg.tool_calls.is_none()
+            && msg.tool_call_id.is_none()
+            && matches!(msg.content, MessageContent::Text(_))
+    }
+
+    let mut merged: Vec<Message> = Vec::with_capacity(messages.len());
+    for msg in messages.drain(..) {
+        if let Some(prev) = merged.last_mut()
+            && prev.role == msg.role
+            && mergeable(prev)
+            && mergeable(&msg)
+            && let (MessageContent::Text(prev_text), MessageContent::Text(text)) =
+                (&mut prev.content, &msg.content)
+        {
+            prev_text.push('\n');
+            prev_text.push_str(text);
+            continue;
+        }
+        merged.push(msg);
+    }
+    *messages = merged;
+}
+
+/// Replace all but the `keep` most recent tool results with a short stub.
+///
+/// Conversational messages are left untouched.
+fn elide_old_tool_results(messages: &mut [Message], keep: usize) {
+    let tool_results = messages
+        .iter()
+        .filter(|m| m.role == MessageRole::Tool)
+        .count();
+    let to_elide = tool_results.saturating_sub(keep);
+
+    for msg in messages
+        .iter_mut()
+        .filter(|m| m.role == MessageRole::Tool)
+        .take(to_elide)
+    {
+        msg.content = match &msg.content {
+            MessageContent::ToolResult { tool_call_id, .. } => MessageContent::ToolResult {
+                tool_call_id: tool_call_id.clone(),
+                content: ELIDED_TOOL_OUTPUT.to_string(),
+            },
+            _ => MessageContent::Text(ELIDED_TOOL_OUTPUT.to_string()),
+        };
+    }
+}
+
 /// Message compactor for more complex compaction operations.
 #[derive(Debug)]
 pub struct MessageCompactor {
@@ -276,27 +638,64 @@ impl MessageCompactor {
     /// Compact until target is reached.
     pub fn compact(&self, conversation: &mut Conversation) -> Result<CompactionResult> {
         let initial_messages = conversation.len();
-        let initial_tokens = conversation.token_count();
+        let initial_tokens = conversation_tokens(conversation);
+        let mut tokens = initial_tokens;
 
         for strategy in &self.strategies {
-            if conversation.token_count() <= self.target_tokens {
+            if tokens <= self.target_tokens {
                 break;
             }
             if conversation.len() <= self.min_messages {
                 break;
             }
             strategy.compact(conversation)?;
+            tokens = conversation_tokens(conversation);
         }
 
         Ok(CompactionResult {
-            messages_removed: initial_messages - conversation.len(),
-            tokens_saved: initial_tokens - conversation.token_count(),
+            messages_removed: initial_messages.saturating_sub(conversation.len()),
+            tokens_saved: initial_tokens.saturating_sub(tokens),
             final_messages: conversation.len(),
-            final_tokens: conversation.token_count(),
+            final_tokens: tokens,
         })
     }
 }
 
+/// Accounting for a single [`CompactionStrategy::compact`] or
+/// [`CompactionStrategy::compact_with`] call.
+///
+/// Counts are net: a summary replacing ten messages counts as nine removed.
+/// Token figures use the conversation's ~4 chars/token estimate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CompactionReport {
+    /// Messages removed from the conversation.
+    pub removed_count: usize,
+    /// Estimated tokens removed from the conversation.
+    pub removed_token_estimate: u32,
+    /// Messages left in the conversation.
+    pub preserved_count: usize,
+    /// Strategy or policy that produced this report.
+    pub strategy: AppliedStrategy,
+}
+
+/// What a [`CompactionReport`] was produced by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppliedStrategy {
+    /// The strategy configured on the [`CompactionStrategy`].
+    Configured(StrategyType),
+    /// A policy passed to [`CompactionStrategy::compact_with`].
+    Policy(CompactionPolicy),
+}
+
+/// Estimate the tokens of every message currently in `conversation`.
+///
+/// Recomputed rather than read from the cached count, which strategies do
+/// not update when they edit messages in place.
+fn conversation_tokens(conversation: &Conversation) -> u32 {
+    conversation.messages().map(estimate_tokens).sum()
+}
+
 /// Result of compaction.
 #[derive(Debug, Clone, Serialize)]
 pub struct CompactionResult {
@@ -393,9 +792,30 @@ mod tests {
             .build();
 
         let strategy = CompactionStrategy::sliding(4);
-        strategy.compact(&mut conv).unwrap();
+        let report = strategy.compact(&mut conv).unwrap();
 
         assert_eq!(conv.len(), 4);
+        assert_eq!(report.removed_count, 2);
+        assert_eq!(report.preserved_count, 4);
+        assert_eq!(
+            report.strategy,
+            AppliedStrategy::Configured(StrategyType::Sliding)
+        );
+    }
+
+    #[test]
+    fn test_message_compactor_counts_recomputed_tokens() {
+        let mut conv = seeded_conversation();
+        let before_tokens = conversation_tokens(&conv);
+
+        let result = MessageCompactor::new(0)
+            .add_strategy(CompactionStrategy::sliding(2))
+            .compact(&mut conv)
+            .unwrap();
+
+        assert_eq!(result.final_tokens, conversation_tokens(&conv));
+        assert_eq!(result.tokens_saved, before_tokens - result.final_tokens);
+        assert!(result.tokens_saved > 0);
     }
 
     #[test]
@@ -416,6 +836,463 @@ mod tests {
         assert!(conv.len() <= 4);
     }
 
+    #[test]
+    fn test_max_tool_results_elides_older_outputs() {
+        let mut builder = ConversationBuilder::new().user("Read the files");
+        for i in 0..6 {
+            builder = builder
+                .assistant(format!("Reading file {i}"))
+                .message(Message::tool_result(
+                    format!("call_{i}"),
+                    format!("contents of file {i}"),
+                ));
+        }
+        let mut conv = builder.assistant("Done").build();
+
+        let strategy = CompactionStrategy {
+            strategy: StrategyType::Custom,
+            ..CompactionStrategy::default()
+        }
+        .with_max_tool_results(2);
+        strategy.compact(&mut conv).unwrap();
+
+        let tool_results: Vec<_> = conv
+            .messages()
+            .filter(|m| m.role == MessageRole::Tool)
+            .collect();
+        assert_eq!(tool_results.len(), 6);
+        for (i, msg) in tool_results.iter().enumerate() {
+            let text = msg.content.as_text().unwrap();
+            if i < 4 {
+                assert_eq!(text, ELIDED_TOOL_OUTPUT);
+            } else {
+                assert_eq!(text, format!("contents of file {i}"));
+            }
+            assert_eq!(
+                msg.tool_call_id.as_deref(),
+                Some(format!("call_{i}").as_str())
+            );
+        }
+
+        // Conversational messages are untouched.
+        assert_eq!(conv.len(), 14);
+        assert_eq!(conv.last_message().unwrap().content.as_text(), Some("Done"));
+    }
+
+    #[test]
+    fn test_merge_consecutive_user_messages() {
+        let mut conv = ConversationBuilder::new()
+            .user("First chunk")
+            .user("Second chunk")
+            .user("Third chunk")
+            .assistant("Reply")
+            .build();
+
+        let strategy = CompactionStrategy {
+            strategy: StrategyType::Custom,
+            ..CompactionStrategy::default()
+        };
+        strategy.compact(&mut conv).unwrap();
+
+        let messages: Vec<_> = conv.messages().collect();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, MessageRole::User);
+        assert_eq!(
+            messages[0].content.as_text(),
+            Some("First chunk\nSecond chunk\nThird chunk")
+        );
+        assert_eq!(messages[1].content.as_text(), Some("Reply"));
+    }
+
+    #[test]
+    fn test_merge_keeps_tool_results_separate() {
+        let mut conv = ConversationBuilder::new()
+            .user("Read both files")
+            .message(Message::tool_result("call_1", "a"))
+            .message(Message::tool_result("call_2", "b"))
+            .build();
+
+        let strategy = CompactionStrategy {
+            strategy: StrategyType::Custom,
+            ..CompactionStrategy::default()
+        };
+        strategy.compact(&mut conv).unwrap();
+
+        assert_eq!(conv.len(), 3);
+    }
+
+    /// One token per byte of text, so budgets are easy to reason about.
+    fn byte_tokens(msg: &Message) -> u32 {
+        msg.content.as_text().map(str::len).unwrap_or(0) as u32
+    }
+
+    #[test]
+    fn test_token_budget_respected() {
+        let mut conv = ConversationBuilder::new()
+            .system("sys")
+            .user("user message one")
+            .assistant("assistant reply one")
+            .user("user message two")
+            .assistant("assistant reply two")
+            .user("latest")
+            .build();
+
+        let strategy = CompactionStrategy::sliding(2);
+        strategy
+            .compact_to_token_budget_with(&mut conv, 50, byte_tokens)
+            .unwrap();
+
+        let used: u32 = conv.messages().map(byte_tokens).sum();
+        assert!(used <= 50, "used {used} tokens");
+        // The oldest, least important messages go first; the system prompt stays.
+        let texts: Vec<_> = conv
+            .messages()
+            .filter_map(|m| m.content.as_text())
+            .collect();
+        assert_eq!(
+            texts,
+            ["sys", "user message two", "assistant reply two", "latest"]
+        );
+    }
+
+    #[test]
+    fn test_token_budget_preserves_recent() {
+        let mut conv = ConversationBuilder::new()
+            .user("a".repeat(40))
+            .assistant("b".repeat(40))
+            .user("c".repeat(40))
+            .assistant("d".repeat(40))
+            .build();
+
+        let strategy = CompactionStrategy::sliding(2);
+        strategy
+            .compact_to_token_budget_with(&mut conv, 80, byte_tokens)
+            .unwrap();
+
+        let messages: Vec<_> = conv.messages().collect();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].content.as_text(), Some("c".repeat(40).as_str()));
+        assert_eq!(messages[1].content.as_text(), Some("d".repeat(40).as_str()));
+    }
+
+    #[test]
+    fn test_token_budget_under_limit_is_noop() {
+        let mut conv = ConversationBuilder::new()
+            .user("Hello")
+            .assistant("Hi")
+            .build();
+
+        let strategy = CompactionStrategy::sliding(1);
+        strategy.compact_to_token_budget(&mut conv, 1_000).unwrap();
+
+        assert_eq!(conv.len(), 2);
+    }
+
+    #[test]
+    fn test_token_budget_preserved_messages_too_large() {
+        let mut conv = ConversationBuilder::new()
+            .user("old")
+            .assistant("x".repeat(100))
+            .build();
+
+        let strategy = CompactionStrategy::sliding(1);
+        let err = strategy
+            .compact_to_token_budget_with(&mut conv, 50, byte_tokens)
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            CortexError::ContextWindowExceeded {
+                used: 100,
+                limit: 50
+            }
+        ));
+        assert_eq!(conv.len(), 2);
+    }
+
+    #[test]
+    fn test_summary_collapses_block() {
+        let mut conv = ConversationBuilder::new()
+            .system("You are helpful")
+            .user("Question 1")
+            .assistant("Answer 1")
+            .user("Question 2")
+            .assistant("Answer 2")
+            .user("Question 3")
+            .assistant("Answer 3")
+            .build();
+
+        let mut summarized = Vec::new();
+        let strategy = CompactionStrategy::sliding(2);
+        strategy
+            .compact_with_summary(&mut conv, |block| {
+                summarized.push(block.len());
+                "stub summary".to_string()
+            })
+            .unwrap();
+
+        assert_eq!(summarized, [4]);
+        let messages: Vec<_> = conv.messages().collect();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[0].content.as_text(), Some("You are helpful"));
+        assert!(is_summary_message(messages[1]));
+        assert_eq!(
+            messages[1].content.as_text(),
+            Some(format!("{SUMMARY_TAG}\nstub summary").as_str())
+        );
+        assert_eq!(messages[2].content.as_text(), Some("Question 3"));
+        assert_eq!(messages[3].content.as_text(), Some("Answer 3"));
+    }
+
+    #[test]
+    fn test_summary_not_resummarized() {
+        let mut conv = ConversationBuilder::new()
+            .user("Question 1")
+            .assistant("Answer 1")
+            .user("Question 2")
+            .assistant("Answer 2")
+            .build();
+
+        let strategy = CompactionStrategy::sliding(1);
+        let mut calls = 0;
+        let mut summarize = |block: &[Message]| {
+            calls += 1;
+            assert!(!block.iter().any(is_summary_message));
+            format!("{} messages", block.len())
+        };
+        strategy
+            .compact_with_summary(&mut conv, &mut summarize)
+            .unwrap();
+        assert_eq!(conv.len(), 2);
+
+        // Only the summary and the recent message are left: nothing to do.
+        strategy
+            .compact_with_summary(&mut conv, &mut summarize)
+            .unwrap();
+        assert_eq!(calls, 1);
+        assert_eq!(conv.len(), 2);
+        assert!(is_summary_message(conv.messages().next().unwrap()));
+    }
+
+    #[test]
+    fn test_summary_survives_second_compaction() {
+        let mut conv = seeded_conversation();
+        let strategy = seeded_strategy();
+
+        strategy
+            .compact_with(&mut conv, CompactionPolicy::Summary)
+            .unwrap();
+        strategy
+            .compact_with(&mut conv, CompactionPolicy::Summary)
+            .unwrap();
+
+        let messages: Vec<_> = conv.messages().collect();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[0].content.as_text(), Some("You are helpful"));
+        assert!(is_summary_message(messages[1]));
+    }
+
+    /// System prompt followed by five user/assistant turns.
+    fn seeded_conversation() -> Conversation {
+        let mut builder = ConversationBuilder::new().system("You are helpful");
+        for i in 1..=5 {
+            builder = builder
+                .user(format!("Question {i}: {}", "q".repeat(40)))
+                .assistant(format!("Answer {i}: {}", "a".repeat(40)));
+        }
+        builder.build()
+    }
+
+    fn seeded_strategy() -> CompactionStrategy {
+        CompactionStrategy {
+            preserve_recent: 2,
+            ..CompactionStrategy::default()
+        }
+    }
+
+    fn texts(conv: &Conversation) -> Vec<&str> {
+        conv.messages()
+            .filter_map(|m| m.content.as_text())
+            .collect()
+    }
+
+    #[test]
+    fn test_policy_importance() {
+        let mut conv = seeded_conversation();
+        // Spare capacity must not inflate the target count
+        conv.messages_mut().reserve(100);
+
+        let before_tokens = conversation_tokens(&conv);
+        let report = seeded_strategy()
+            .compact_with(&mut conv, CompactionPolicy::Importance)
+            .unwrap();
+
+        // 11 messages at a 0.5 ratio
+        assert_eq!(conv.len(), 5);
+        assert_eq!(
+            report,
+            CompactionReport {
+                removed_count: 6,
+                removed_token_estimate: before_tokens - conversation_tokens(&conv),
+                preserved_count: 5,
+                strategy: AppliedStrategy::Policy(CompactionPolicy::Importance),
+            }
+        );
+        assert!(report.removed_token_estimate > 0);
+        assert_eq!(conv.messages().next().unwrap().role, MessageRole::System);
+        assert!(texts(&conv).last().unwrap().starts_with("Answer 5"));
+    }
+
+    #[test]
+    fn test_policy_recency() {
+        let mut conv = seeded_conversation();
+
+        seeded_strategy()
+            .compact_with(&mut conv, CompactionPolicy::Recency)
+            .unwrap();
+
+        let texts = texts(&conv);
+        assert_eq!(texts.len(), 3);
+        assert_eq!(texts[0], "You are helpful");
+        assert!(texts[1].starts_with("Question 5"));
+        assert!(texts[2].starts_with("Answer 5"));
+    }
+
+    #[test]
+    fn test_policy_token_budget() {
+        let mut conv = seeded_conversation();
+
+        let before_count = conv.len();
+        let before_tokens = conversation_tokens(&conv);
+        let report = seeded_strategy()
+            .compact_with(&mut conv, CompactionPolicy::TokenBudget(60))
+            .unwrap();
+
+        let used: u32 = conv.messages().map(estimate_tokens).sum();
+        assert!(used <= 60, "used {used} tokens");
+        assert_eq!(
+            report.strategy,
+            AppliedStrategy::Policy(CompactionPolicy::TokenBudget(60))
+        );
+        assert_eq!(report.preserved_count, conv.len());
+        assert_eq!(report.removed_count, before_count - conv.len());
+        assert!(report.removed_count > 0);
+        assert_eq!(report.removed_token_estimate, before_tokens - used);
+        assert!(texts(&conv).ends_with(&[
+            format!("Question 5: {}", "q".repeat(40)).as_str(),
+            format!("Answer 5: {}", "a".repeat(40)).as_str(),
+        ]));
+    }
+
+    #[test]
+    fn test_policy_summary() {
+        let mut conv = seeded_conversation();
+
+        seeded_strategy()
+            .compact_with(&mut conv, CompactionPolicy::Summary)
+            .unwrap();
+
+        let messages: Vec<_> = conv.messages().collect();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[0].content.as_text(), Some("You are helpful"));
+        assert!(is_summary_message(messages[1]));
+        assert!(
+            messages[1]
+                .content
+                .as_text()
+                .unwrap()
+                .contains("User: Question 1")
+        );
+    }
+
+    #[test]
+    fn test_compaction_config_rejects_out_of_range_ratio() {
+        for ratio in [0.0, -0.5, 1.01, 2.0, f32::NAN] {
+            let err = CompactionConfig::new(ratio, 4).unwrap_err();
+            assert!(
+                matches!(&err, CortexError::InvalidConfig { field, .. } if field == "target_ratio"),
+                "{ratio} should be rejected, got {err:?}"
+            );
+        }
+
+        let config = CompactionConfig::new(1.0, 4).unwrap();
+        assert_eq!(config.target_ratio(), 1.0);
+        assert!(CompactionConfig::new(0.25, 0).is_ok());
+
+        let strategy = CompactionStrategy {
+            target_ratio: 2.0,
+            ..CompactionStrategy::default()
+        };
+        assert!(strategy.config().is_err());
+        assert!(CompactionStrategy::default().config().is_ok());
+    }
+
+    #[test]
+    fn test_compaction_rejects_invalid_public_fields() {
+        let strategy = CompactionStrategy {
+            target_ratio: 2.0,
+            ..seeded_strategy()
+        };
+        fn is_invalid(result: Result<()>) -> bool {
+            matches!(
+                result,
+                Err(CortexError::InvalidConfig { field, .. }) if field == "target_ratio"
+            )
+        }
+
+        let mut conv = seeded_conversation();
+        assert!(is_invalid(strategy.compact(&mut conv).map(drop)));
+        for policy in [
+            CompactionPolicy::Importance,
+            CompactionPolicy::Recency,
+            CompactionPolicy::TokenBudget(60),
+            CompactionPolicy::Summary,
+        ] {
+            assert!(is_invalid(
+                strategy.compact_with(&mut conv, policy).map(drop)
+            ));
+        }
+        assert!(is_invalid(strategy.compact_to_token_budget(&mut conv, 60)));
+        assert!(is_invalid(
+            strategy.compact_with_summary(&mut conv, |_| String::new())
+        ));
+        assert_eq!(conv.len(), 11);
+    }
+
+    #[test]
+    fn test_preserve_recent_clamped_to_conversation() {
+        let config = CompactionConfig::new(0.5, usize::MAX).unwrap();
+        assert_eq!(config.preserve_recent(), usize::MAX);
+        assert_eq!(config.preserve_recent_for(11), 11);
+
+        let strategy = CompactionStrategy::default().with_config(config);
+        let policies = [
+            CompactionPolicy::Importance,
+            CompactionPolicy::Recency,
+            CompactionPolicy::TokenBudget(u32::MAX),
+            CompactionPolicy::Summary,
+        ];
+        for policy in policies {
+            let mut conv = seeded_conversation();
+            strategy.compact_with(&mut conv, policy).unwrap();
+            assert_eq!(conv.len(), 11, "{policy:?} should keep every message");
+        }
+
+        for strategy_type in [StrategyType::Summarize, StrategyType::Hybrid] {
+            let mut conv = seeded_conversation();
+            let strategy = CompactionStrategy {
+                strategy: strategy_type,
+                ..strategy.clone()
+            };
+            strategy.compact(&mut conv).unwrap();
+            assert_eq!(
+                conv.len(),
+                11,
+                "{strategy_type:?} should keep every message"
+            );
+        }
+    }
+
     #[test]
     fn test_importance_calculation() {
         let msg = Message::system("Important");
@@ -360,7 +360,7 @@ impl ConversationBuilder {
 }
 
 /// Estimate token count for a message.
-fn estimate_tokens(message: &Message) -> u32 {
+pub(crate) fn estimate_tokens(message: &Message) -> u32 {
     let text = match &message.content {
         MessageContent::Text(s) => s.as_str(),
         MessageContent::Parts(parts) => {
@@ -370,7 +370,7 @@ fn estimate_tokens(message: &Message) -> u32 {
                 .filter_map(|p| {
                     match p {
                         crate::client::types::ContentPart::Text { text, .. } => {
-                            Some(text.len() as u32 / 4)
+                            Some(cortex_prompt_harness::estimate_tokens(text))
                         }
                         _ => Some(100), // Estimate for images/documents
                     }
@@ -383,8 +383,7 @@ fn estimate_tokens(message: &Message) -> u32 {
         }
     };
 
-    // Rough estimate: ~4 characters per token
-    (text.len() as u32 / 4) + 4 // +4 for role overhead
+    cortex_prompt_harness::estimate_tokens(text) + 4 // +4 for role overhead
 }
 
 #[cfg(test)]
@@ -3,6 +3,7 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+use cortex_prompt_harness::estimate_tokens;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{CortexError, Result};
@@ -124,7 +125,8 @@ impl FileContext {
         let mut current_tokens = 0u32;
 
         for line in lines {
-            let line_tokens = (line.len() as u32 / 4) + 1;
+            // +1 for the newline.
+            let line_tokens = estimate_tokens(line) + 1;
             if current_tokens + line_tokens > max_tokens {
                 new_content.push_str("... [truncated]\n");
                 break;
@@ -447,12 +449,6 @@ fn detect_language(path: &Path) -> Option<String> {
     Some(lang.to_string())
 }
 
-/// Estimate token count for content.
-fn estimate_tokens(content: &str) -> u32 {
-    // Rough estimate: ~4 characters per token
-    (content.len() as u32 / 4) + 1
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
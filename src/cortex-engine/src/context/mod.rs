@@ -14,7 +14,10 @@ pub mod file_context;
 pub mod system_prompt;
 pub mod token_budget;
 
-pub use compaction::{CompactionStrategy, MessageCompactor};
+pub use compaction::{
+    AppliedStrategy, CompactionConfig, CompactionPolicy, CompactionReport, CompactionStrategy,
+    MessageCompactor,
+};
 pub use conversation::{Conversation, ConversationBuilder};
 pub use file_context::{FileContext, FileContextBuilder};
 pub use system_prompt::{SystemPrompt, SystemPromptBuilder};
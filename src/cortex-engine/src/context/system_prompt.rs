@@ -2,6 +2,7 @@
 
 use std::collections::HashMap;
 
+use cortex_prompt_harness::estimate_tokens;
 use serde::{Deserialize, Serialize};
 
 /// System prompt configuration.
@@ -365,11 +366,6 @@ const CODE_REVIEWER_BASE: &str = r#"Review code for:
 
 Provide specific, actionable feedback with examples."#;
 
-/// Estimate token count.
-fn estimate_tokens(text: &str) -> u32 {
-    (text.len() as u32 / 4) + 1
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
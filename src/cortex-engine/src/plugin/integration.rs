@@ -8,9 +8,9 @@ use std::sync::Arc;
 
 use cortex_plugins_ext::{
     HookDispatcher as PluginsHookDispatcher, HookRegistry, HookResult as PluginsHookResult,
-    PermissionAskInput, PermissionDecision, SessionEndInput, SessionEndOutput, SessionStartInput,
-    SessionStartOutput, ToolExecuteAfterInput, ToolExecuteAfterOutput, ToolExecuteBeforeInput,
-    ToolExecuteBeforeOutput,
+    HookType, PermissionAskInput, PermissionDecision, SessionEndInput, SessionEndOutput,
+    SessionStartInput, SessionStartOutput, ToolExecuteAfterInput, ToolExecuteAfterOutput,
+    ToolExecuteBeforeInput, ToolExecuteBeforeOutput,
 };
 
 use crate::error::{CortexError, Result};
@@ -19,6 +19,9 @@ use crate::error::{CortexError, Result};
 #[derive(Debug, Clone)]
 pub struct ToolHookResult {
     /// Modified tool arguments (for before hooks).
+    ///
+    /// These are not applied automatically; use [`Self::apply_args_to`]
+    /// before running the tool.
     pub args: Option<serde_json::Value>,
     /// Modified tool output (for after hooks).
     pub output: Option<String>,
@@ -42,6 +45,49 @@ impl Default for ToolHookResult {
     }
 }
 
+/// What to do with a tool call once `tool.execute.before` hooks have run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolArgsOutcome {
+    /// Execute the tool with the (possibly updated) arguments.
+    Execute,
+    /// Skip execution and use this value as the tool result.
+    Replaced(serde_json::Value),
+    /// Skip execution; a hook aborted the call.
+    Aborted(String),
+}
+
+impl ToolHookResult {
+    /// Apply hook-modified arguments to `original` and report how to proceed.
+    ///
+    /// Object arguments are merged key by key, with hook values winning;
+    /// any other value replaces `original` outright. When a hook aborted or
+    /// replaced the result, `original` is left untouched since the tool
+    /// must not run.
+    pub fn apply_args_to(&self, original: &mut serde_json::Value) -> ToolArgsOutcome {
+        if !self.should_continue {
+            let reason = self
+                .abort_reason
+                .clone()
+                .unwrap_or_else(|| "Aborted by plugin hook".to_string());
+            return ToolArgsOutcome::Aborted(reason);
+        }
+        if let Some(replacement) = &self.replacement {
+            return ToolArgsOutcome::Replaced(replacement.clone());
+        }
+
+        match (original, &self.args) {
+            (_, None) => {}
+            (serde_json::Value::Object(original), Some(serde_json::Value::Object(args))) => {
+                for (key, value) in args {
+                    original.insert(key.clone(), value.clone());
+                }
+            }
+            (original, Some(args)) => *original = args.clone(),
+        }
+        ToolArgsOutcome::Execute
+    }
+}
+
 impl From<ToolExecuteBeforeOutput> for ToolHookResult {
     fn from(output: ToolExecuteBeforeOutput) -> Self {
         let (should_continue, abort_reason, replacement) = match output.result {
@@ -83,7 +129,8 @@ impl From<ToolExecuteAfterOutput> for ToolHookResult {
 /// Result returned from session hooks.
 #[derive(Debug, Clone)]
 pub struct SessionHookResult {
-    /// System prompt additions from the hook.
+    /// System prompt additions from every hook that ran, in dispatch order
+    /// with exact duplicates removed. Append these to the system prompt.
     pub system_prompt_additions: Vec<String>,
     /// Greeting message from the hook.
     pub greeting: Option<String>,
@@ -284,27 +331,22 @@ impl PluginIntegration {
             resumed,
         };
 
-        // The dispatcher doesn't have a direct trigger_session_start method,
-        // so we need to handle this at the registry level if hooks are registered.
-        // For now, we return a default result since the dispatcher only handles
-        // tool, chat, and permission hooks.
-        //
-        // In a full implementation, the HookDispatcher would need to be extended
-        // to support session hooks, or we'd interact directly with the registry.
-        let output = SessionStartOutput::new();
+        let output = self
+            .dispatcher
+            .trigger_session_start(input)
+            .await
+            .map_err(|e| CortexError::Internal(format!("Plugin hook error: {}", e)))?;
 
-        // Log that session start was triggered (useful for debugging)
         tracing::debug!(
             session_id = %session_id,
             cwd = %cwd.display(),
             model = ?model,
             agent = ?agent,
             resumed = resumed,
-            "Session start hook triggered (no plugins registered)"
+            additions = output.system_prompt_additions.len(),
+            "Session start hook triggered"
         );
 
-        let _ = input; // Suppress unused warning
-
         Ok(SessionHookResult::from(output))
     }
 
@@ -342,22 +384,21 @@ impl PluginIntegration {
             saved,
         };
 
-        // Similar to session start, the current dispatcher doesn't have a direct method.
-        // Return a default result for now.
-        let output = SessionEndOutput::new();
+        let output = self
+            .dispatcher
+            .trigger_session_end(input)
+            .await
+            .map_err(|e| CortexError::Internal(format!("Plugin hook error: {}", e)))?;
 
-        // Log that session end was triggered
         tracing::debug!(
             session_id = %session_id,
             duration_secs = duration_secs,
             total_messages = total_messages,
             total_tokens = ?total_tokens,
             saved = saved,
-            "Session end hook triggered (no plugins registered)"
+            "Session end hook triggered"
         );
 
-        let _ = input; // Suppress unused warning
-
         Ok(SessionHookResult::from(output))
     }
 
@@ -460,15 +501,35 @@ impl PluginIntegration {
     ///
     /// This can be used to skip hook triggering when no plugins are interested,
     /// improving performance.
-    pub fn has_tool_hooks(&self) -> bool {
-        // The dispatcher always exists, so we consider hooks available.
-        // In a more sophisticated implementation, we'd check the registry.
-        true
+    pub async fn has_tool_hooks(&self) -> bool {
+        self.has_hooks(&[HookType::ToolExecuteBefore, HookType::ToolExecuteAfter])
+            .await
     }
 
     /// Check if any hooks are registered for permission decisions.
-    pub fn has_permission_hooks(&self) -> bool {
-        true
+    pub async fn has_permission_hooks(&self) -> bool {
+        self.has_hooks(&[HookType::PermissionAsk]).await
+    }
+
+    /// Check if any hooks are registered for session start or end.
+    pub async fn has_session_hooks(&self) -> bool {
+        self.has_hooks(&[HookType::SessionStart, HookType::SessionEnd])
+            .await
+    }
+
+    /// Check if any hooks are registered for chat messages.
+    pub async fn has_chat_hooks(&self) -> bool {
+        self.has_hooks(&[HookType::ChatMessage]).await
+    }
+
+    async fn has_hooks(&self, hook_types: &[HookType]) -> bool {
+        let registry = self.dispatcher.registry();
+        for &hook_type in hook_types {
+            if registry.hook_count(hook_type).await > 0 {
+                return true;
+            }
+        }
+        false
     }
 }
 
@@ -518,6 +579,52 @@ mod tests {
         assert!(result.replacement.is_none());
     }
 
+    #[test]
+    fn test_apply_args_merges_modified_key() {
+        let mut output = ToolExecuteBeforeOutput::new(serde_json::json!({"path": "/test.txt"}));
+        output.args = serde_json::json!({"path": "/sandbox/test.txt"});
+        let result = ToolHookResult::from(output);
+
+        let mut args = serde_json::json!({"path": "/test.txt", "limit": 10});
+        assert_eq!(result.apply_args_to(&mut args), ToolArgsOutcome::Execute);
+        assert_eq!(
+            args,
+            serde_json::json!({"path": "/sandbox/test.txt", "limit": 10})
+        );
+    }
+
+    #[test]
+    fn test_apply_args_replace_skips_execution() {
+        let mut output = ToolExecuteBeforeOutput::new(serde_json::json!({"path": "/changed"}));
+        output.result = PluginsHookResult::Replace {
+            result: serde_json::json!("cached contents"),
+        };
+        let result = ToolHookResult::from(output);
+
+        let mut args = serde_json::json!({"path": "/test.txt"});
+        assert_eq!(
+            result.apply_args_to(&mut args),
+            ToolArgsOutcome::Replaced(serde_json::json!("cached contents"))
+        );
+        assert_eq!(args, serde_json::json!({"path": "/test.txt"}));
+    }
+
+    #[test]
+    fn test_apply_args_abort() {
+        let mut output = ToolExecuteBeforeOutput::new(serde_json::json!({}));
+        output.result = PluginsHookResult::Abort {
+            reason: "blocked".to_string(),
+        };
+        let result = ToolHookResult::from(output);
+
+        let mut args = serde_json::json!({"path": "/test.txt"});
+        assert_eq!(
+            result.apply_args_to(&mut args),
+            ToolArgsOutcome::Aborted("blocked".to_string())
+        );
+        assert_eq!(args, serde_json::json!({"path": "/test.txt"}));
+    }
+
     #[test]
     fn test_session_hook_result_default() {
         let result = SessionHookResult::default();
@@ -526,20 +633,86 @@ mod tests {
         assert!(result.greeting.is_none());
     }
 
-    #[test]
-    fn test_plugin_integration_builder() {
+    #[tokio::test]
+    async fn test_plugin_integration_builder() {
         let integration = PluginIntegrationBuilder::new().build();
-        assert!(integration.has_tool_hooks());
-        assert!(integration.has_permission_hooks());
+        assert!(!integration.has_tool_hooks().await);
+        assert!(!integration.has_permission_hooks().await);
+        assert!(!integration.has_session_hooks().await);
+        assert!(!integration.has_chat_hooks().await);
     }
 
-    #[test]
-    fn test_plugin_integration_with_registry() {
+    struct NoopToolHook;
+
+    #[async_trait::async_trait]
+    impl cortex_plugins_ext::ToolExecuteBeforeHook for NoopToolHook {
+        async fn execute(
+            &self,
+            _input: &ToolExecuteBeforeInput,
+            _output: &mut ToolExecuteBeforeOutput,
+        ) -> cortex_plugins_ext::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plugin_integration_with_registry() {
         let registry = Arc::new(HookRegistry::new());
         let integration = PluginIntegrationBuilder::new()
-            .with_registry(registry)
+            .with_registry(registry.clone())
             .build();
-        assert!(integration.has_tool_hooks());
+        assert!(!integration.has_tool_hooks().await);
+
+        registry
+            .register_tool_execute_before("tool-plugin", Arc::new(NoopToolHook))
+            .await;
+
+        assert!(integration.has_tool_hooks().await);
+        assert!(!integration.has_permission_hooks().await);
+        assert!(!integration.has_session_hooks().await);
+        assert!(!integration.has_chat_hooks().await);
+    }
+
+    struct PromptLineHook(&'static str);
+
+    #[async_trait::async_trait]
+    impl cortex_plugins_ext::SessionStartHook for PromptLineHook {
+        async fn execute(
+            &self,
+            _input: &SessionStartInput,
+            output: &mut SessionStartOutput,
+        ) -> cortex_plugins_ext::Result<()> {
+            output.system_prompt_additions.push(self.0.to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_start_collects_additions_from_all_hooks() {
+        let registry = Arc::new(HookRegistry::new());
+        registry
+            .register_session_start("first", Arc::new(PromptLineHook("Use British spelling.")))
+            .await;
+        registry
+            .register_session_start("second", Arc::new(PromptLineHook("Prefer short answers.")))
+            .await;
+        let integration = PluginIntegration::new(registry);
+
+        let result = integration
+            .trigger_session_start(
+                "session-1",
+                std::path::Path::new("/workspace"),
+                None,
+                None,
+                false,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.system_prompt_additions,
+            vec!["Use British spelling.", "Prefer short answers."]
+        );
     }
 
     #[tokio::test]
@@ -608,6 +781,55 @@ mod tests {
         assert!(hook_result.system_prompt_additions.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_trigger_session_start_with_registered_hook() {
+        use async_trait::async_trait;
+        use cortex_plugins_ext::SessionStartHook;
+
+        struct GreetingHook;
+
+        #[async_trait]
+        impl SessionStartHook for GreetingHook {
+            async fn execute(
+                &self,
+                _input: &SessionStartInput,
+                output: &mut SessionStartOutput,
+            ) -> cortex_plugins_ext::Result<()> {
+                output
+                    .system_prompt_additions
+                    .push("Follow the team style guide.".to_string());
+                output.greeting = Some("Welcome back".to_string());
+                Ok(())
+            }
+        }
+
+        let registry = Arc::new(HookRegistry::new());
+        registry
+            .register_session_start("greeter", Arc::new(GreetingHook))
+            .await;
+        let integration = PluginIntegrationBuilder::new()
+            .with_registry(registry)
+            .build();
+
+        let hook_result = integration
+            .trigger_session_start(
+                "session-1",
+                std::path::Path::new("/workspace"),
+                None,
+                None,
+                false,
+            )
+            .await
+            .unwrap();
+
+        assert!(hook_result.should_continue);
+        assert_eq!(
+            hook_result.system_prompt_additions,
+            vec!["Follow the team style guide."]
+        );
+        assert_eq!(hook_result.greeting.as_deref(), Some("Welcome back"));
+    }
+
     #[tokio::test]
     async fn test_trigger_session_end_default() {
         let integration = PluginIntegrationBuilder::new().build();
@@ -83,7 +83,7 @@ pub use hooks::{
     MessageHookContext, PermissionHookContext, SessionHookContext, ToolHookContext,
 };
 pub use integration::{
-    PluginIntegration, PluginIntegrationBuilder, SessionHookResult, ToolHookResult,
+    PluginIntegration, PluginIntegrationBuilder, SessionHookResult, ToolArgsOutcome, ToolHookResult,
 };
 pub use loader::{
     DiscoveredPlugin, LoadedPluginInfo, PluginFormat, PluginLoadError, PluginLoadResult,
@@ -339,10 +339,8 @@ impl PromptBuilder {
 
     /// Estimate token count.
     pub fn estimate_tokens(&self) -> u32 {
-        let messages = self.build();
-        let total_chars: usize = messages.iter().map(|m| m.content.len()).sum();
-        // Rough estimate: ~4 chars per token
-        (total_chars as f32 / 4.0).ceil() as u32
+        let text: String = self.build().into_iter().map(|m| m.content).collect();
+        cortex_prompt_harness::estimate_tokens(&text)
     }
 
     /// Check if within token limit.
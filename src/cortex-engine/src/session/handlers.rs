@@ -17,10 +17,7 @@ use crate::rollout::recorder::SessionMeta;
 use crate::summarization::SummarizationStrategy;
 
 use super::Session;
-use super::prompt::{
-    USE_SKILL_BASED_PROMPT, auto_detect_skills_from_message, build_system_prompt,
-    build_system_prompt_with_skills, inject_skills,
-};
+use super::prompt::SkillSet;
 
 impl Session {
     /// Handle an incoming submission.
@@ -125,12 +122,7 @@ impl Session {
                 // Update system prompt in existing message history
                 if let Some(msg) = self.messages.first_mut() {
                     if matches!(msg.role, crate::client::MessageRole::System) {
-                        let new_prompt = if USE_SKILL_BASED_PROMPT {
-                            build_system_prompt_with_skills(&self.config, &[])
-                        } else {
-                            build_system_prompt(&self.config)
-                        };
-                        *msg = Message::system(new_prompt);
+                        *msg = Message::system(self.skills.build_prompt(&self.config));
                     }
                 }
             }
@@ -201,25 +193,26 @@ impl Session {
 
         tracing::debug!("User message: {}", user_text);
 
-        // Auto-detect and inject skills based on the user's message (only on first message)
-        // This reduces context window usage by only loading relevant skills.
-        if USE_SKILL_BASED_PROMPT && self.turn_id == 1 {
-            let detected_skills = auto_detect_skills_from_message(&user_text);
+        // Auto-detect skills from the user's message (only on first message) and pin them
+        // for the session. This reduces context window usage by only loading relevant skills.
+        if self.turn_id == 1 && self.skills.is_empty() {
+            let detected_skills = SkillSet::resolve(&self.config, Some(&user_text));
             if !detected_skills.is_empty() {
-                tracing::info!("Auto-detected skills for task: {:?}", detected_skills);
-
-                // Update the system prompt with detected skills
-                if let Some(msg) = self.messages.first_mut() {
-                    if matches!(msg.role, MessageRole::System) {
-                        if let Some(current_content) = msg.content.as_text() {
-                            let updated_prompt = inject_skills(current_content, &detected_skills);
-                            *msg = Message::system(updated_prompt);
-                            tracing::debug!(
-                                "Injected skills into system prompt: {:?}",
-                                detected_skills
-                            );
-                        }
-                    }
+                tracing::info!(
+                    "Auto-detected skills for task: {:?}",
+                    detected_skills.names()
+                );
+                self.skills = detected_skills;
+
+                // Rebuild the system prompt with the pinned skills
+                if let Some(msg) = self.messages.first_mut()
+                    && matches!(msg.role, MessageRole::System)
+                {
+                    *msg = Message::system(self.skills.build_prompt(&self.config));
+                    tracing::debug!(
+                        "Injected skills into system prompt: {:?}",
+                        self.skills.names()
+                    );
                 }
             }
         }
@@ -21,7 +21,7 @@ use crate::rollout::{RolloutRecorder, SESSIONS_SUBDIR, get_rollout_path, read_ro
 use crate::tools::ToolRouter;
 
 use super::Session;
-use super::prompt::{USE_SKILL_BASED_PROMPT, build_system_prompt, build_system_prompt_with_skills};
+use super::prompt::SkillSet;
 use super::types::{SessionHandle, SessionInfo, TokenCounter};
 
 impl Session {
@@ -65,15 +65,10 @@ impl Session {
 
         // Initialize with system prompt
         // Use skill-based minimal prompt by default to reduce context window usage.
-        // Skills will be loaded on-demand based on task requirements.
+        // Explicit skills are pinned now; auto-detected ones on the first user message.
         let mut messages = Vec::new();
-        let initial_prompt = if USE_SKILL_BASED_PROMPT {
-            // Start with the minimal base prompt - skills will be injected on first user message
-            build_system_prompt_with_skills(&config, &[])
-        } else {
-            build_system_prompt(&config)
-        };
-        messages.push(Message::system(initial_prompt));
+        let skills = SkillSet::resolve(&config, None);
+        messages.push(Message::system(skills.build_prompt(&config)));
 
         // Initialize snapshot manager
         let snapshot_dir = config
@@ -101,6 +96,7 @@ impl Session {
             client,
             tool_router,
             messages,
+            skills,
             submission_rx,
             event_tx,
             turn_id: 0,
@@ -156,12 +152,8 @@ impl Session {
         // For resumed sessions, we still use the base prompt since skills might have been
         // loaded during the previous session and we want consistency.
         let mut messages = Vec::new();
-        let initial_prompt = if USE_SKILL_BASED_PROMPT {
-            build_system_prompt_with_skills(&config, &[])
-        } else {
-            build_system_prompt(&config)
-        };
-        messages.push(Message::system(initial_prompt));
+        let skills = SkillSet::resolve(&config, None);
+        messages.push(Message::system(skills.build_prompt(&config)));
 
         let events = get_events(&entries);
         for event_msg in events {
@@ -216,6 +208,7 @@ impl Session {
             client,
             tool_router,
             messages,
+            skills,
             submission_rx,
             event_tx,
             turn_id: 0,
@@ -272,12 +265,8 @@ impl Session {
         let entries = read_rollout(&rollout_path)?;
 
         let mut messages = Vec::new();
-        let initial_prompt = if USE_SKILL_BASED_PROMPT {
-            build_system_prompt_with_skills(&config, &[])
-        } else {
-            build_system_prompt(&config)
-        };
-        messages.push(Message::system(initial_prompt));
+        let skills = SkillSet::resolve(&config, None);
+        messages.push(Message::system(skills.build_prompt(&config)));
 
         let events = get_events(&entries);
 
@@ -384,6 +373,7 @@ impl Session {
             client,
             tool_router,
             messages,
+            skills,
             submission_rx,
             event_tx,
             turn_id: user_msg_count as u64,
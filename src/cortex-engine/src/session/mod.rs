@@ -23,8 +23,10 @@ use crate::tools::ToolRouter;
 
 pub use lifecycle::list_sessions;
 pub use prompt::{
-    USE_SKILL_BASED_PROMPT, auto_detect_skills_from_message, available_skills, build_system_prompt,
-    build_system_prompt_with_skills, inject_skills, is_valid_skill,
+    AgentsMdCache, EffectivePrompt, SkillSet, auto_detect_skills_from_message, available_skills,
+    build_session_system_prompt, build_system_prompt, build_system_prompt_with_skills,
+    inject_skills, inject_skills_dedup, inject_skills_with_registry, is_valid_skill,
+    resolve_effective_prompt,
 };
 pub use types::{SessionHandle, SessionInfo, TokenCounter};
 
@@ -40,6 +42,8 @@ pub struct Session {
     pub(crate) tool_router: ToolRouter,
     /// Conversation messages.
     pub(crate) messages: Vec<Message>,
+    /// Skills pinned for the session, injected into every system prompt.
+    pub(crate) skills: SkillSet,
     /// Submission receiver (from UI).
     pub(crate) submission_rx: Receiver<Submission>,
     /// Event sender (to UI).
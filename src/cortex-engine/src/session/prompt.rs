@@ -7,9 +7,16 @@
 //! The skill-based mode reduces token usage by only including instructions
 //! relevant to the current task.
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
 
-use crate::config::Config;
+use serde::Serialize;
+
+use cortex_prompt_harness::prompts::{SkillRegistry, skill_priority};
+
+use crate::config::{Config, PromptMode, SkillSelection};
 
 /// System prompt for the Cortex Agent - loaded from cortex-prompt-harness
 pub(crate) const SYSTEM_PROMPT: &str = cortex_prompt_harness::prompts::CORTEX_MAIN_PROMPT;
@@ -21,13 +28,6 @@ pub(crate) const BASE_PROMPT: &str = cortex_prompt_harness::prompts::CORTEX_BASE
 pub(crate) const BASE_PROMPT_WITH_SKILLS: &str =
     cortex_prompt_harness::prompts::CORTEX_BASE_PROMPT_WITH_SKILLS_PRELOADED;
 
-/// Whether to use skill-based prompts by default.
-///
-/// When `true`, the system uses a minimal base prompt with skills loaded on-demand.
-/// When `false`, the full monolithic prompt is used.
-#[allow(dead_code)]
-pub const USE_SKILL_BASED_PROMPT: bool = true;
-
 /// Build the system prompt for the agent.
 pub fn build_system_prompt(config: &Config) -> String {
     let cwd = config.cwd.display().to_string();
@@ -68,13 +68,10 @@ pub fn build_system_prompt(config: &Config) -> String {
 
         if let Some(path) = path_to_try {
             if let Ok(content) = std::fs::read_to_string(path) {
-                // If it starts with frontmatter, try to parse it
-                if content.starts_with("---") {
-                    if let Ok((_meta, agent_prompt)) = crate::agents::parse_agent_md(&content) {
-                        p = agent_prompt;
-                    }
-                } else {
-                    p = content;
+                if let Some((agent_prompt, _)) =
+                    agent_prompt_from_md(&content, SYSTEM_PROMPT, SYSTEM_PROMPT)
+                {
+                    p = agent_prompt;
                 }
             }
         }
@@ -112,26 +109,203 @@ pub fn build_system_prompt(config: &Config) -> String {
     prompt
 }
 
+/// Resolve an agent `.md` file into the prompt to use instead of `base`.
+///
+/// Content without frontmatter replaces `base`. With frontmatter, the body
+/// replaces `base` unless the `extends` key is set: `extends: base` appends
+/// the body to `base`, and `extends: cortex-main` appends it to `cortex_main`,
+/// the full Cortex prompt with any skills already injected. Any other
+/// `extends` value logs a warning and extends `base`, so a typo still keeps the
+/// base prompt rather than dropping it.
+///
+/// Returns the prompt and the prompt it builds on, or `None` if the
+/// frontmatter can't be parsed.
+fn agent_prompt_from_md(
+    content: &str,
+    base: &str,
+    cortex_main: &str,
+) -> Option<(String, AgentBase)> {
+    if !content.starts_with("---") {
+        return Some((content.to_string(), AgentBase::Replaced));
+    }
+
+    let (meta, agent_prompt) = crate::agents::parse_agent_md(content).ok()?;
+    let (prefix, extended) = match meta.extends.as_deref() {
+        None => return Some((agent_prompt, AgentBase::Replaced)),
+        Some("cortex-main") => (cortex_main, AgentBase::CortexMain),
+        Some("base") => (base, AgentBase::Base),
+        Some(other) => {
+            tracing::warn!(agent = %meta.name, extends = %other, "Unknown agent `extends` value, extending the base prompt");
+            (base, AgentBase::Base)
+        }
+    };
+    Some((format!("{}\n\n{}", prefix, agent_prompt), extended))
+}
+
+/// Prompt an agent `.md` file builds on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AgentBase {
+    /// The agent prompt replaces the base prompt.
+    Replaced,
+    /// The agent prompt is appended to the base prompt.
+    Base,
+    /// The agent prompt is appended to the full Cortex prompt.
+    CortexMain,
+}
+
+/// Process-wide AGENTS.md cache shared by all prompt builds.
+static AGENTS_MD_CACHE: LazyLock<AgentsMdCache> = LazyLock::new(AgentsMdCache::new);
+
 /// Load and merge AGENTS.md files.
 /// Order: ~/.cortex/AGENTS.md -> repo root -> directories down to CWD
 /// AGENTS.override.md replaces instead of merging.
 fn load_agents_md(config: &Config) -> String {
+    AGENTS_MD_CACHE.load(config)
+}
+
+/// In-memory cache of AGENTS.md contents keyed by path and modification time.
+///
+/// Prompts are rebuilt every turn, so unchanged files are served from memory
+/// and only files whose mtime (or size) changed are read again. Merge and
+/// override semantics are the same as an uncached load.
+#[derive(Debug, Default)]
+pub struct AgentsMdCache {
+    entries: Mutex<HashMap<PathBuf, CachedAgentsMd>>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedAgentsMd {
+    modified: SystemTime,
+    len: u64,
+    content: String,
+}
+
+impl AgentsMdCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load and merge the AGENTS.md files that apply to `config.cwd`.
+    pub fn load(&self, config: &Config) -> String {
+        join_agents_md(&self.collect(config), config.agents_md_max_bytes)
+    }
+
+    /// Drop all cached entries.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Number of files currently cached.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether the cache holds no files.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn collect(&self, config: &Config) -> Vec<(PathBuf, String)> {
+        collect_agents_md_with(config, |path| self.read(path))
+    }
+
+    /// Read a file through the cache, re-reading it if its mtime changed.
+    fn read(&self, path: &Path) -> Option<String> {
+        let Ok(metadata) = std::fs::metadata(path) else {
+            self.lock().remove(path);
+            return None;
+        };
+        let modified = metadata.modified().ok();
+
+        if let Some(modified) = modified
+            && let Some(entry) = self.lock().get(path)
+            && entry.modified == modified
+            && entry.len == metadata.len()
+        {
+            return Some(entry.content.clone());
+        }
+
+        let Ok(content) = std::fs::read_to_string(path) else {
+            self.lock().remove(path);
+            return None;
+        };
+        if let Some(modified) = modified {
+            self.lock().insert(
+                path.to_path_buf(),
+                CachedAgentsMd {
+                    modified,
+                    len: metadata.len(),
+                    content: content.clone(),
+                },
+            );
+        }
+        Some(content)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<PathBuf, CachedAgentsMd>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Join collected AGENTS.md contents in merge order, capped at `max_bytes`.
+fn join_agents_md(files: &[(PathBuf, String)], max_bytes: usize) -> String {
+    let joined = files
+        .iter()
+        .map(|(_, content)| content.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n---\n\n");
+    truncate_agents_md(joined, max_bytes)
+}
+
+/// Truncate combined instructions to `max_bytes` at a UTF-8 boundary.
+///
+/// A marker is appended when truncation happens, so the result may exceed
+/// `max_bytes` by the marker's length.
+fn truncate_agents_md(mut instructions: String, max_bytes: usize) -> String {
+    if instructions.len() <= max_bytes {
+        return instructions;
+    }
+
+    let mut cut = max_bytes;
+    while !instructions.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    instructions.truncate(cut);
+    instructions.push_str(&format!(
+        "\n\n[AGENTS.md truncated: exceeded {} bytes]",
+        max_bytes
+    ));
+    instructions
+}
+
+/// Collect the AGENTS.md files that apply to `config.cwd`, with their paths.
+fn collect_agents_md(config: &Config) -> Vec<(PathBuf, String)> {
+    AGENTS_MD_CACHE.collect(config)
+}
+
+/// Collect AGENTS.md files using `read` to load each candidate path.
+fn collect_agents_md_with(
+    config: &Config,
+    mut read: impl FnMut(&Path) -> Option<String>,
+) -> Vec<(PathBuf, String)> {
     let mut instructions = Vec::new();
 
     // 1. Global AGENTS.md from ~/.cortex/
     let global_path = config.cortex_home.join("AGENTS.md");
-    if let Ok(content) = std::fs::read_to_string(&global_path) {
-        instructions.push(content);
+    if let Some(content) = read(&global_path) {
+        instructions.push((global_path, content));
     }
 
-    // 2. Find git root or use cwd
-    let repo_root = find_git_root(&config.cwd).unwrap_or_else(|| config.cwd.clone());
+    // 2. Find git root or use cwd. The root is canonical, so compare it
+    // against the canonical cwd.
+    let cwd = &config
+        .cwd
+        .canonicalize()
+        .unwrap_or_else(|_| config.cwd.clone());
+    let repo_root = find_git_root(cwd).unwrap_or_else(|| cwd.clone());
 
     // 3. Walk from repo root to cwd, collecting AGENTS.md files
-    let _current = repo_root.clone();
-    let cwd = &config.cwd;
-
-    // Collect all directories from root to cwd
     let mut dirs_to_check = vec![repo_root.clone()];
     if let Ok(relative) = cwd.strip_prefix(&repo_root) {
         let mut path = repo_root.clone();
@@ -144,27 +318,33 @@ fn load_agents_md(config: &Config) -> String {
     for dir in dirs_to_check {
         // Check for AGENTS.override.md first (replaces all previous)
         let override_path = dir.join("AGENTS.override.md");
-        if let Ok(content) = std::fs::read_to_string(&override_path) {
+        if let Some(content) = read(&override_path) {
             instructions.clear();
-            instructions.push(content);
+            instructions.push((override_path, content));
             continue;
         }
 
         // Regular AGENTS.md (merges)
         let agents_path = dir.join("AGENTS.md");
-        if let Ok(content) = std::fs::read_to_string(&agents_path) {
-            instructions.push(content);
+        if let Some(content) = read(&agents_path) {
+            instructions.push((agents_path, content));
         }
     }
 
-    instructions.join("\n\n---\n\n")
+    instructions
 }
 
 /// Find git repository root.
+///
+/// The root is the nearest ancestor with a `.git` directory, or a `.git`
+/// file holding a `gitdir:` pointer (worktrees and submodules). The start
+/// path is canonicalized once up front so symlinks and `..` components
+/// cannot send the walk around in circles.
 pub(crate) fn find_git_root(start: &PathBuf) -> Option<PathBuf> {
-    let mut current = start.clone();
+    let mut current = start.canonicalize().unwrap_or_else(|_| start.clone());
     loop {
-        if current.join(".git").exists() {
+        let dot_git = current.join(".git");
+        if dot_git.is_dir() || (dot_git.is_file() && read_gitdir_pointer(&dot_git).is_some()) {
             return Some(current);
         }
         if !current.pop() {
@@ -173,6 +353,24 @@ pub(crate) fn find_git_root(start: &PathBuf) -> Option<PathBuf> {
     }
 }
 
+/// Read the `gitdir:` pointer of a `.git` file, resolved against its directory.
+fn read_gitdir_pointer(dot_git: &Path) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(dot_git).ok()?;
+    let target = content
+        .lines()
+        .find_map(|line| line.strip_prefix("gitdir:"))?
+        .trim();
+    if target.is_empty() {
+        return None;
+    }
+    let target = Path::new(target);
+    if target.is_absolute() {
+        Some(target.to_path_buf())
+    } else {
+        Some(dot_git.parent()?.join(target))
+    }
+}
+
 /// Get system information string.
 fn get_system_info() -> String {
     let os = std::env::consts::OS;
@@ -204,8 +402,8 @@ fn get_system_info() -> String {
 /// Build a system prompt with specific skills pre-loaded.
 ///
 /// This function constructs a prompt using the minimal base prompt and injects
-/// the requested skills. If no skills are specified and `USE_SKILL_BASED_PROMPT`
-/// is false, it falls back to the full monolithic prompt.
+/// the requested skills. If no skills are specified and `config.prompt_mode` is
+/// [`PromptMode::Monolithic`], it falls back to the full monolithic prompt.
 ///
 /// # Arguments
 ///
@@ -228,9 +426,64 @@ fn get_system_info() -> String {
 /// ```
 #[allow(dead_code)]
 pub fn build_system_prompt_with_skills(config: &Config, skills: &[&str]) -> String {
+    resolve_effective_prompt(config, skills).render()
+}
+
+/// The exact components of the system prompt sent to the model.
+///
+/// Returned by [`resolve_effective_prompt`] so sessions can log or serialize
+/// what the model actually saw on a turn.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EffectivePrompt {
+    /// Base prompt with skills injected and template variables substituted.
+    pub system: String,
+    /// Names of the skills whose content was injected, in order.
+    pub skills_injected: Vec<String>,
+    /// AGENTS.md files that contributed project instructions, in merge order.
+    pub agents_sources: Vec<PathBuf>,
+    /// Project and user instructions appended after `system`.
+    ///
+    /// Empty when there are none or when the prompt placed them inline via
+    /// `{{ADDITIONAL_CONTEXT}}`.
+    pub additional_context: String,
+}
+
+impl EffectivePrompt {
+    /// Render the final prompt string.
+    pub fn render(&self) -> String {
+        if self.additional_context.is_empty() {
+            self.system.clone()
+        } else {
+            format!("{}\n\n{}", self.system, self.additional_context)
+        }
+    }
+}
+
+/// Resolve every component of the skill-based system prompt without side effects.
+///
+/// This is what [`build_system_prompt_with_skills`] renders; use it when the
+/// individual pieces need to be recorded.
+///
+/// # Examples
+///
+/// ```ignore
+/// let effective = resolve_effective_prompt(&config, &["git"]);
+/// tracing::debug!(skills = ?effective.skills_injected, "system prompt resolved");
+/// assert_eq!(effective.render(), build_system_prompt_with_skills(&config, &["git"]));
+/// ```
+#[allow(dead_code)]
+pub fn resolve_effective_prompt(config: &Config, skills: &[&str]) -> EffectivePrompt {
     // If skills mode is disabled and no skills specified, use monolithic prompt
-    if !USE_SKILL_BASED_PROMPT && skills.is_empty() {
-        return build_system_prompt(config);
+    if !config.prompt_mode.is_skill_based() && skills.is_empty() {
+        return EffectivePrompt {
+            system: build_system_prompt(config),
+            skills_injected: Vec::new(),
+            agents_sources: collect_agents_md(config)
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect(),
+            additional_context: String::new(),
+        };
     }
 
     let cwd = config.cwd.display().to_string();
@@ -253,7 +506,12 @@ pub fn build_system_prompt_with_skills(config: &Config, skills: &[&str]) -> Stri
     };
 
     // Inject skills into the base prompt
-    let mut prompt = inject_skills(base, skills);
+    let (mut prompt, skipped) = inject_skills_with(
+        base,
+        skills,
+        cortex_prompt_harness::prompts::get_builtin_skill,
+    );
+    let mut skills_injected = injected_skill_names(skills, &skipped);
 
     // Handle agent-specific prompts
     if let Some(agent_name) = &config.current_agent {
@@ -277,13 +535,19 @@ pub fn build_system_prompt_with_skills(config: &Config, skills: &[&str]) -> Stri
 
         if let Some(path) = path_to_try {
             if let Ok(content) = std::fs::read_to_string(path) {
-                // If it starts with frontmatter, try to parse it
-                if content.starts_with("---") {
-                    if let Ok((_meta, agent_prompt)) = crate::agents::parse_agent_md(&content) {
-                        prompt = agent_prompt;
+                let (cortex_main, main_skipped) = inject_skills_dedup(SYSTEM_PROMPT, skills);
+                if let Some((agent_prompt, agent_base)) =
+                    agent_prompt_from_md(&content, &prompt, &cortex_main)
+                {
+                    prompt = agent_prompt;
+                    match agent_base {
+                        // A replacing agent prompt drops the injected skills.
+                        AgentBase::Replaced => skills_injected.clear(),
+                        AgentBase::Base => {}
+                        AgentBase::CortexMain => {
+                            skills_injected = injected_skill_names(skills, &main_skipped);
+                        }
                     }
-                } else {
-                    prompt = content;
                 }
             }
         } else {
@@ -300,7 +564,8 @@ pub fn build_system_prompt_with_skills(config: &Config, skills: &[&str]) -> Stri
     prompt = prompt.replace("{{ENVIRONMENT_CONTEXT}}", &env_context);
 
     // Load AGENTS.md instructions
-    let agents_instructions = load_agents_md(config);
+    let agents_files = collect_agents_md(config);
+    let agents_instructions = join_agents_md(&agents_files, config.agents_md_max_bytes);
 
     // Additional context (user instructions + AGENTS.md)
     let mut additional = String::new();
@@ -319,20 +584,143 @@ pub fn build_system_prompt_with_skills(config: &Config, skills: &[&str]) -> Stri
 
     prompt = prompt.replace("{{ADDITIONAL_CONTEXT}}", &additional);
 
-    // If template variable wasn't present, append additional context
-    if !additional.is_empty() && !prompt.contains(&additional) {
-        prompt.push_str("\n\n");
-        prompt.push_str(&additional);
+    // If template variable wasn't present, additional context is appended
+    if additional.is_empty() || prompt.contains(&additional) {
+        additional.clear();
     }
 
-    prompt
+    EffectivePrompt {
+        system: prompt,
+        skills_injected,
+        agents_sources: agents_files.into_iter().map(|(path, _)| path).collect(),
+        additional_context: additional,
+    }
+}
+
+/// Build the session system prompt according to `config.skill_selection`.
+///
+/// This is the single entry point callers should use so they don't have to
+/// branch on the selection mode themselves:
+///
+/// * `SkillSelection::Off` - the full monolithic prompt
+/// * `SkillSelection::Auto` - skills detected from `first_message`; with no
+///   message or no matches, the prompt for `config.prompt_mode`
+/// * `SkillSelection::Explicit` - exactly the listed skills
+///
+/// # Examples
+///
+/// ```ignore
+/// let prompt = build_session_system_prompt(&config, Some("Fix this bug"));
+/// ```
+pub fn build_session_system_prompt(config: &Config, first_message: Option<&str>) -> String {
+    SkillSet::resolve(config, first_message).build_prompt(config)
+}
+
+/// Skills pinned for a whole session.
+///
+/// Resolved once from the first task or explicit config, then reused for
+/// every turn instead of re-detecting. This keeps the injected skills, and
+/// so the system prompt prefix, stable across turns, which is what prompt
+/// caching needs. Mid-session changes go through [`SkillSet::add`] and
+/// [`SkillSet::remove`].
+///
+/// # Examples
+///
+/// ```ignore
+/// let mut skills = SkillSet::resolve(&config, Some("Fix this bug"));
+/// let first_turn = skills.build_prompt(&config);
+/// skills.add("security");
+/// let later_turn = skills.build_prompt(&config);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SkillSet {
+    /// Pinned skill names, in injection order.
+    skills: Vec<String>,
+}
+
+impl SkillSet {
+    /// Pin exactly the given skills, skipping unknown names and duplicates.
+    pub fn new<S: AsRef<str>>(skills: impl IntoIterator<Item = S>) -> Self {
+        let mut set = Self::default();
+        for skill in skills {
+            set.add(skill.as_ref());
+        }
+        set
+    }
+
+    /// Resolve the session's skills according to `config.skill_selection`.
+    ///
+    /// `Auto` detects skills from `first_message`, `Explicit` pins the listed
+    /// skills, and `Off` pins none. `Auto` only detects in the skill-based
+    /// prompt mode, so the monolithic prompt is not mixed with skills.
+    pub fn resolve(config: &Config, first_message: Option<&str>) -> Self {
+        match &config.skill_selection {
+            SkillSelection::Off => Self::default(),
+            SkillSelection::Auto if !config.prompt_mode.is_skill_based() => Self::default(),
+            SkillSelection::Auto => Self::new(
+                first_message
+                    .map(auto_detect_skills_from_message)
+                    .unwrap_or_default(),
+            ),
+            SkillSelection::Explicit(names) => Self::new(names),
+        }
+    }
+
+    /// Pin another skill. Returns `false` if it is unknown or already pinned.
+    pub fn add(&mut self, skill: &str) -> bool {
+        if !is_valid_skill(skill) || self.contains(skill) {
+            return false;
+        }
+        self.skills.push(skill.to_string());
+        true
+    }
+
+    /// Unpin a skill. Returns `false` if it was not pinned.
+    pub fn remove(&mut self, skill: &str) -> bool {
+        let before = self.skills.len();
+        self.skills.retain(|s| s != skill);
+        self.skills.len() != before
+    }
+
+    /// Whether `skill` is pinned.
+    pub fn contains(&self, skill: &str) -> bool {
+        self.skills.iter().any(|s| s == skill)
+    }
+
+    /// Pinned skill names, in injection order.
+    pub fn names(&self) -> Vec<&str> {
+        self.skills.iter().map(String::as_str).collect()
+    }
+
+    /// Number of pinned skills.
+    pub fn len(&self) -> usize {
+        self.skills.len()
+    }
+
+    /// Whether no skills are pinned.
+    pub fn is_empty(&self) -> bool {
+        self.skills.is_empty()
+    }
+
+    /// Build the system prompt for a turn.
+    ///
+    /// With `SkillSelection::Off` and nothing pinned this is the monolithic
+    /// prompt; otherwise the skill-based prompt with the pinned skills.
+    pub fn build_prompt(&self, config: &Config) -> String {
+        if self.is_empty() && config.skill_selection == SkillSelection::Off {
+            return build_system_prompt(config);
+        }
+        build_system_prompt_with_skills(config, &self.names())
+    }
 }
 
 /// Inject skill content into a base prompt.
 ///
 /// This function retrieves the content for each requested skill and appends
 /// it to the base prompt with clear section separators. Invalid or missing
-/// skills are silently skipped.
+/// skills are silently skipped. Repeated names (compared case-insensitively)
+/// are injected once, in first-seen order, and a skill whose content already
+/// appears verbatim in the base prompt is not injected again.
 ///
 /// # Arguments
 ///
@@ -341,7 +729,9 @@ pub fn build_system_prompt_with_skills(config: &Config, skills: &[&str]) -> Stri
 ///
 /// # Returns
 ///
-/// The base prompt with skill content appended.
+/// The base prompt with skill content appended. Skills are rendered in
+/// [`skill_priority`] order rather than input order, so the same set of
+/// skills always produces the same prompt.
 ///
 /// # Examples
 ///
@@ -352,19 +742,62 @@ pub fn build_system_prompt_with_skills(config: &Config, skills: &[&str]) -> Stri
 /// ```
 #[allow(dead_code)]
 pub fn inject_skills(base_prompt: &str, skills: &[&str]) -> String {
-    if skills.is_empty() {
-        return base_prompt.to_string();
-    }
+    inject_skills_with(
+        base_prompt,
+        skills,
+        cortex_prompt_harness::prompts::get_builtin_skill,
+    )
+    .0
+}
+
+/// Inject skill content looked up in a [`SkillRegistry`].
+///
+/// Like [`inject_skills`], but runtime-registered skills (e.g. project-local
+/// ones) are available too.
+///
+/// # Examples
+///
+/// ```ignore
+/// let mut registry = SkillRegistry::new();
+/// registry.register_skill("deploy", "# Deploy Skill");
+/// let prompt = inject_skills_with_registry(BASE_PROMPT, &["git", "deploy"], &registry);
+/// assert!(prompt.contains("## Skill: deploy"));
+/// ```
+#[allow(dead_code)]
+pub fn inject_skills_with_registry(
+    base_prompt: &str,
+    skills: &[&str],
+    registry: &SkillRegistry,
+) -> String {
+    inject_skills_with(base_prompt, skills, |name| registry.get(name)).0
+}
 
+/// Shared implementation of the `inject_skills*` functions.
+///
+/// Returns the prompt and the skills skipped because `base_prompt` already
+/// contains their content verbatim.
+fn inject_skills_with<'a, 'r>(
+    base_prompt: &str,
+    skills: &[&'a str],
+    lookup: impl Fn(&str) -> Option<&'r str>,
+) -> (String, Vec<&'a str>) {
     let mut result = base_prompt.to_string();
     let mut injected_skills = Vec::new();
-
-    for skill_name in skills {
-        if let Some(skill_content) = cortex_prompt_harness::prompts::get_builtin_skill(skill_name) {
+    let mut skipped = Vec::new();
+
+    for skill_name in &dedup_skill_names(skills) {
+        if let Some(skill_content) = lookup(skill_name) {
+            let body = strip_yaml_frontmatter(skill_content).trim();
+            if !body.is_empty() && base_prompt.contains(body) {
+                skipped.push(*skill_name);
+                continue;
+            }
             injected_skills.push((*skill_name, skill_content));
         }
         // Silently skip invalid/missing skills for graceful handling
     }
+    // Stable, so skills without metadata keep their relative input order.
+    injected_skills.sort_by_key(|(name, _)| skill_priority(name));
 
     if !injected_skills.is_empty() {
         result.push_str("\n\n---\n\n# Loaded Skills\n\n");
@@ -379,31 +812,133 @@ pub fn inject_skills(base_prompt: &str, skills: &[&str]) -> String {
         }
     }
 
-    result
+    (result, skipped)
+}
+
+/// Built-in skills of `skills` not in `skipped`, in [`skill_priority`] order.
+fn injected_skill_names(skills: &[&str], skipped: &[&str]) -> Vec<String> {
+    let mut injected: Vec<String> = dedup_skill_names(skills)
+        .into_iter()
+        .filter(|s| !skipped.contains(s))
+        .filter(|s| cortex_prompt_harness::prompts::get_builtin_skill(s).is_some())
+        .map(str::to_string)
+        .collect();
+    injected.sort_by_key(|s| skill_priority(s));
+    injected
+}
+
+/// Drop repeated skill names, compared case-insensitively, keeping the first
+/// spelling of each in input order.
+fn dedup_skill_names<'a>(skills: &[&'a str]) -> Vec<&'a str> {
+    let mut unique: Vec<&'a str> = Vec::with_capacity(skills.len());
+    for skill in skills {
+        if !unique.iter().any(|seen| seen.eq_ignore_ascii_case(skill)) {
+            unique.push(skill);
+        }
+    }
+    unique
+}
+
+/// Inject skills into a base prompt, skipping skills the base already covers.
+///
+/// A skill is considered already present when the base prompt contains its
+/// signature heading (case-insensitive) or the skill's content verbatim. For
+/// example, the monolithic `CORTEX_MAIN_PROMPT` already embeds the Cognitive
+/// Architecture, so injecting the `planning` skill on top of it would
+/// duplicate that content.
+///
+/// # Arguments
+///
+/// * `base_prompt` - The base prompt to build upon
+/// * `skills` - Slice of skill names to inject
+///
+/// # Returns
+///
+/// The base prompt with the non-redundant skills appended, and the list of
+/// skills that were skipped because the base already contained them.
+///
+/// # Examples
+///
+/// ```ignore
+/// let (prompt, skipped) = inject_skills_dedup(SYSTEM_PROMPT, &["planning", "git"]);
+/// assert_eq!(skipped, vec!["planning"]);
+/// assert!(prompt.contains("## Skill: git"));
+/// ```
+#[allow(dead_code)]
+pub fn inject_skills_dedup<'a>(base_prompt: &str, skills: &[&'a str]) -> (String, Vec<&'a str>) {
+    let base_lower = base_prompt.to_lowercase();
+    let (covered, to_inject): (Vec<&'a str>, Vec<&'a str>) =
+        dedup_skill_names(skills).into_iter().partition(|name| {
+            skill_signature(name)
+                .is_some_and(|signature| base_lower.contains(&signature.to_lowercase()))
+        });
+
+    let (prompt, duplicated) = inject_skills_with(
+        base_prompt,
+        &to_inject,
+        cortex_prompt_harness::prompts::get_builtin_skill,
+    );
+    // Report skips in input order.
+    let skipped = dedup_skill_names(skills)
+        .into_iter()
+        .filter(|name| covered.contains(name) || duplicated.contains(name))
+        .collect();
+    (prompt, skipped)
+}
+
+/// Signature heading identifying a skill's content inside a prompt.
+///
+/// Skills that mirror a section of the monolithic prompt use that section's
+/// title; the others use their own skill title.
+fn skill_signature(skill: &str) -> Option<&'static str> {
+    match skill.to_ascii_lowercase().as_str() {
+        "planning" => Some("Cognitive Architecture"),
+        "debugging" => Some("Failure Protocol"),
+        "code-quality" => Some("Quality Checkpoints"),
+        "git" => Some("Git Operations Skill"),
+        "file-operations" => Some("File Operations Skill"),
+        "security" => Some("Security Skill"),
+        "rust" => Some("Rust Skill"),
+        "docker" => Some("Docker Skill"),
+        "testing" => Some("Testing Skill"),
+        _ => None,
+    }
 }
 
 /// Strip YAML frontmatter from skill content.
 ///
 /// Skills include YAML frontmatter for metadata, but we don't need it
-/// in the injected prompt.
+/// in the injected prompt. Fences may use three or more dashes and either
+/// `\n` or `\r\n` line endings. If the closing fence ends the file, the
+/// result is empty. Content without a closing fence is returned unchanged.
 #[allow(dead_code)]
 fn strip_yaml_frontmatter(content: &str) -> &str {
-    if !content.starts_with("---\n") {
+    let mut lines = content.split_inclusive('\n');
+    let Some(first) = lines.next() else {
+        return content;
+    };
+    if !first.ends_with('\n') || !is_frontmatter_fence(first) {
         return content;
     }
 
-    // Find the closing ---
-    if let Some(end_pos) = content[4..].find("\n---\n") {
-        // Skip past the closing --- and newline
-        let skip_to = 4 + end_pos + 5;
-        if skip_to < content.len() {
-            return &content[skip_to..];
+    // Find the closing fence and skip past it and its line ending
+    let mut offset = first.len();
+    for line in lines {
+        offset += line.len();
+        if is_frontmatter_fence(line) {
+            return &content[offset..];
         }
     }
 
     content
 }
 
+/// Whether a line (with or without its line ending) is a `---` fence.
+fn is_frontmatter_fence(line: &str) -> bool {
+    let line = line.trim_end_matches(['\r', '\n']);
+    line.len() >= 3 && line.bytes().all(|b| b == b'-')
+}
+
 /// Auto-detect skills from a user message.
 ///
 /// This function analyzes the user's message and returns a list of skills
@@ -461,6 +996,7 @@ pub fn is_valid_skill(skill: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::DEFAULT_AGENTS_MD_MAX_BYTES;
 
     // =========================================================================
     // Skill Injection Tests
@@ -495,6 +1031,62 @@ mod tests {
         assert!(result.contains("Debugging Skill"));
     }
 
+    #[test]
+    fn test_inject_skills_deduplicates_names() {
+        let base = "Base prompt";
+        let result = inject_skills(base, &["git", "debugging", "git", "Git"]);
+
+        assert_eq!(result.matches("## Skill: ").count(), 2);
+        assert_eq!(result.matches("## Skill: git").count(), 1);
+        assert_eq!(result.matches("## Skill: debugging").count(), 1);
+
+        let (_, skipped) = inject_skills_dedup(SYSTEM_PROMPT, &["Planning", "planning"]);
+        assert_eq!(skipped, vec!["Planning"]);
+    }
+
+    #[test]
+    fn test_inject_skills_orders_by_priority() {
+        let result = inject_skills("Base prompt", &["security", "git", "planning", "nope"]);
+
+        let planning = result.find("## Skill: planning").unwrap();
+        let git = result.find("## Skill: git").unwrap();
+        let security = result.find("## Skill: security").unwrap();
+        assert!(planning < git && git < security);
+        assert_eq!(
+            result,
+            inject_skills("Base prompt", &["planning", "git", "security"])
+        );
+    }
+
+    #[test]
+    fn test_inject_skills_with_registry_orders_custom_skills_last() {
+        let mut registry = SkillRegistry::new();
+        registry.register_skill("deploy", "# Deploy Skill");
+        let result = inject_skills_with_registry("Base prompt", &["deploy", "git"], &registry);
+
+        let git = result.find("## Skill: git").unwrap();
+        let deploy = result.find("## Skill: deploy").unwrap();
+        assert!(git < deploy);
+    }
+
+    #[test]
+    fn test_inject_skills_debugging_into_base_prompt() {
+        // The base prompt's skill table mentions "Failure protocol", which
+        // must not count as the debugging skill being loaded.
+        let result = inject_skills(BASE_PROMPT, &["debugging"]);
+        assert!(result.contains("## Skill: debugging"));
+        assert!(result.starts_with(BASE_PROMPT));
+    }
+
+    #[test]
+    fn test_inject_skills_skips_content_already_in_base() {
+        let once = inject_skills("Base prompt", &["git"]);
+        let twice = inject_skills(&once, &["git", "debugging"]);
+
+        assert_eq!(twice.matches("## Skill: git").count(), 1);
+        assert!(twice.contains("## Skill: debugging"));
+    }
+
     #[test]
     fn test_inject_skills_invalid_skill_skipped() {
         let base = "Base prompt";
@@ -515,6 +1107,52 @@ mod tests {
         assert!(!result.contains("# Loaded Skills"));
     }
 
+    #[test]
+    fn test_inject_skills_with_registry() {
+        let mut registry = SkillRegistry::new();
+        registry.register_skill(
+            "deploy",
+            "---\nname: deploy\n---\n# Deploy Skill\n\nUse `make deploy`.",
+        );
+
+        let result = inject_skills_with_registry("Base prompt", &["git", "deploy"], &registry);
+
+        assert!(result.contains("## Skill: git"));
+        assert!(result.contains("## Skill: deploy"));
+        assert!(result.contains("Use `make deploy`."));
+        assert!(!result.contains("name: deploy"));
+        // The static path does not know about runtime skills.
+        assert!(!inject_skills("Base prompt", &["deploy"]).contains("## Skill: deploy"));
+    }
+
+    #[test]
+    fn test_inject_skills_dedup_skips_covered_skill() {
+        let base = "Base prompt\n\n## 02 // Cognitive Architecture\n\nRECON -> DESIGN";
+        let (result, skipped) = inject_skills_dedup(base, &["planning"]);
+
+        assert_eq!(skipped, vec!["planning"]);
+        assert_eq!(result, base);
+        assert!(!result.contains("## Skill: planning"));
+    }
+
+    #[test]
+    fn test_inject_skills_dedup_monolithic_prompt() {
+        let (result, skipped) = inject_skills_dedup(SYSTEM_PROMPT, &["planning", "git"]);
+
+        assert!(skipped.contains(&"planning"));
+        assert!(!skipped.contains(&"git"));
+        assert!(result.contains("## Skill: git"));
+        assert!(!result.contains("## Skill: planning"));
+    }
+
+    #[test]
+    fn test_inject_skills_dedup_nothing_skipped() {
+        let (result, skipped) = inject_skills_dedup("Base prompt", &["git", "debugging"]);
+
+        assert!(skipped.is_empty());
+        assert_eq!(result, inject_skills("Base prompt", &["git", "debugging"]));
+    }
+
     // =========================================================================
     // Auto-Detection Tests
     // =========================================================================
@@ -578,6 +1216,48 @@ mod tests {
     // Utility Tests
     // =========================================================================
 
+    #[test]
+    fn test_find_git_root_git_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        let nested = root.join("src").join("deep");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(find_git_root(&nested), Some(root.clone()));
+        // `..` components are resolved before walking
+        let dotted = nested.join("..").join("deep");
+        assert_eq!(find_git_root(&dotted), Some(root));
+    }
+
+    #[test]
+    fn test_find_git_root_submodule_git_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join(".git").join("modules").join("sub")).unwrap();
+        let sub = root.join("sub");
+        std::fs::create_dir_all(sub.join("src")).unwrap();
+        std::fs::write(sub.join(".git"), "gitdir: ../.git/modules/sub\n").unwrap();
+
+        assert_eq!(find_git_root(&sub.join("src")), Some(sub.clone()));
+        assert_eq!(
+            read_gitdir_pointer(&sub.join(".git")),
+            Some(sub.join("../.git/modules/sub"))
+        );
+    }
+
+    #[test]
+    fn test_find_git_root_ignores_git_file_without_pointer() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        let sub = root.join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        std::fs::write(sub.join(".git"), "not a pointer\n").unwrap();
+
+        assert_eq!(find_git_root(&sub), Some(root));
+    }
+
     #[test]
     fn test_available_skills() {
         let skills = available_skills();
@@ -587,7 +1267,10 @@ mod tests {
         assert!(skills.contains(&"debugging"));
         assert!(skills.contains(&"security"));
         assert!(skills.contains(&"planning"));
-        assert_eq!(skills.len(), 6);
+        assert!(skills.contains(&"rust"));
+        assert!(skills.contains(&"docker"));
+        assert!(skills.contains(&"testing"));
+        assert_eq!(skills.len(), 9);
     }
 
     #[test]
@@ -611,19 +1294,479 @@ mod tests {
     #[test]
     fn test_strip_yaml_frontmatter_no_content_after() {
         let content = "---\nname: test\n---\n";
-        let stripped = strip_yaml_frontmatter(content);
-        // Should return original if nothing after frontmatter
-        assert_eq!(stripped, content);
+        assert_eq!(strip_yaml_frontmatter(content), "");
+
+        // Closing fence at the very end of the file, without a newline
+        let content = "---\nname: test\n---";
+        assert_eq!(strip_yaml_frontmatter(content), "");
+    }
+
+    #[test]
+    fn test_strip_yaml_frontmatter_crlf() {
+        let content = "---\r\nname: test\r\nversion: 1.0.0\r\n---\r\n# Actual Content\r\n";
+        assert_eq!(strip_yaml_frontmatter(content), "# Actual Content\r\n");
+    }
+
+    #[test]
+    fn test_strip_yaml_frontmatter_long_fence() {
+        let content = "-----\nname: test\n-----\n# Actual Content";
+        assert_eq!(strip_yaml_frontmatter(content), "# Actual Content");
+
+        // Opening and closing fences need not match in length
+        let content = "----\nname: test\n---\n# Actual Content";
+        assert_eq!(strip_yaml_frontmatter(content), "# Actual Content");
+    }
+
+    #[test]
+    fn test_strip_yaml_frontmatter_unclosed_or_invalid() {
+        // No closing fence: leave content untouched
+        let content = "---\nname: test\n# Actual Content";
+        assert_eq!(strip_yaml_frontmatter(content), content);
+
+        // Two dashes is not a fence
+        let content = "--\nname: test\n--\n# Actual Content";
+        assert_eq!(strip_yaml_frontmatter(content), content);
+
+        // A horizontal rule later in the body is not frontmatter
+        let content = "# Title\n---\nBody";
+        assert_eq!(strip_yaml_frontmatter(content), content);
     }
 
     // =========================================================================
-    // Constant Tests
+    // Agent Prompt Tests
     // =========================================================================
 
+    /// Config rooted in a fresh temp dir.
+    ///
+    /// With `agent_md`, it is written as the project agent `reviewer`, which
+    /// becomes the current agent.
+    fn test_config(
+        agent_md: Option<&str>,
+        skill_selection: SkillSelection,
+        prompt_mode: PromptMode,
+    ) -> (tempfile::TempDir, Config) {
+        let dir = tempfile::tempdir().unwrap();
+        if let Some(agent_md) = agent_md {
+            let agents_dir = dir.path().join(".cortex").join("agents");
+            std::fs::create_dir_all(&agents_dir).unwrap();
+            std::fs::write(agents_dir.join("reviewer.md"), agent_md).unwrap();
+        }
+        let config = Config {
+            cwd: dir.path().to_path_buf(),
+            cortex_home: dir.path().join("home"),
+            user_instructions: None,
+            current_agent: agent_md.map(|_| "reviewer".to_string()),
+            skill_selection,
+            prompt_mode,
+            ..Config::default()
+        };
+        (dir, config)
+    }
+
+    #[test]
+    fn test_agent_prompt_extends_base() {
+        let (_dir, config) = test_config(
+            Some(
+                "---\nname: reviewer\ndescription: Reviews code\nextends: base\n---\nFocus on review feedback.",
+            ),
+            SkillSelection::default(),
+            PromptMode::default(),
+        );
+
+        let prompt = build_system_prompt(&config);
+        assert!(prompt.contains("PRIME DIRECTIVES"));
+        assert!(prompt.ends_with("Focus on review feedback."));
+
+        let prompt = build_system_prompt_with_skills(&config, &["git"]);
+        assert!(prompt.contains("## Skill: git"));
+        assert!(prompt.contains("Focus on review feedback."));
+    }
+
+    #[test]
+    fn test_agent_prompt_extends_cortex_main() {
+        let (_dir, config) = test_config(
+            Some(
+                "---\nname: reviewer\ndescription: Reviews code\nextends: cortex-main\n---\nFocus on review feedback.",
+            ),
+            SkillSelection::default(),
+            PromptMode::default(),
+        );
+
+        let prompt = build_system_prompt_with_skills(&config, &["git"]);
+        assert!(prompt.contains("PRIME DIRECTIVES"));
+        assert!(prompt.contains("## Skill: git"));
+        assert!(prompt.ends_with("Focus on review feedback."));
+
+        let effective = resolve_effective_prompt(&config, &["git"]);
+        assert!(effective.system.contains("## Skill: git"));
+        assert_eq!(effective.skills_injected, vec!["git".to_string()]);
+
+        // cortex-main already covers these, so they are not reported.
+        let effective =
+            resolve_effective_prompt(&config, &["planning", "debugging", "code-quality", "git"]);
+        assert!(!effective.system.contains("## Skill: planning"));
+        assert_eq!(effective.skills_injected, vec!["git".to_string()]);
+    }
+
+    #[test]
+    fn test_agent_prompt_unknown_extends_falls_back_to_base() {
+        let (_dir, config) = test_config(
+            Some(
+                "---\nname: reviewer\ndescription: Reviews code\nextends: cortex-mian\n---\nFocus on review feedback.",
+            ),
+            SkillSelection::default(),
+            PromptMode::default(),
+        );
+
+        let prompt = build_system_prompt(&config);
+        assert!(prompt.contains("PRIME DIRECTIVES"));
+        assert!(prompt.ends_with("Focus on review feedback."));
+    }
+
+    #[test]
+    fn test_agent_prompt_without_extends_replaces_base() {
+        let (_dir, config) = test_config(
+            Some("---\nname: reviewer\ndescription: Reviews code\n---\nFocus on review feedback."),
+            SkillSelection::default(),
+            PromptMode::default(),
+        );
+
+        assert_eq!(build_system_prompt(&config), "Focus on review feedback.");
+        let prompt = build_system_prompt_with_skills(&config, &["git"]);
+        assert!(!prompt.contains("## Skill: git"));
+        assert!(!prompt.contains("PRIME DIRECTIVES"));
+        assert!(prompt.contains("Focus on review feedback."));
+        assert!(
+            resolve_effective_prompt(&config, &["git"])
+                .skills_injected
+                .is_empty()
+        );
+    }
+
+    // =========================================================================
+    // Skill Selection Tests
+    // =========================================================================
+
+    #[test]
+    fn test_skill_selection_off_uses_monolithic_prompt() {
+        let (_dir, config) = test_config(None, SkillSelection::Off, PromptMode::default());
+        let prompt = build_session_system_prompt(&config, Some("Fix this bug and create a PR"));
+
+        assert!(prompt.contains("PRIME DIRECTIVES"));
+        assert!(!prompt.contains("# Loaded Skills"));
+    }
+
+    #[test]
+    fn test_skill_selection_auto_detects_from_first_message() {
+        let (_dir, config) = test_config(None, SkillSelection::Auto, PromptMode::default());
+        let prompt = build_session_system_prompt(&config, Some("Fix this bug and create a PR"));
+
+        assert!(prompt.contains("# Loaded Skills"));
+        assert!(prompt.contains("## Skill: git"));
+        assert!(prompt.contains("## Skill: debugging"));
+        assert!(!prompt.contains("load_skill"));
+    }
+
+    #[test]
+    fn test_skill_selection_auto_without_message_keeps_skill_loading() {
+        let (_dir, config) = test_config(None, SkillSelection::Auto, PromptMode::default());
+        let prompt = build_session_system_prompt(&config, None);
+
+        assert!(prompt.contains("load_skill"));
+        assert!(!prompt.contains("# Loaded Skills"));
+    }
+
+    #[test]
+    fn test_skill_selection_explicit_uses_listed_skills() {
+        let (_dir, config) = test_config(
+            None,
+            SkillSelection::Explicit(vec!["security".to_string()]),
+            PromptMode::default(),
+        );
+        let prompt = build_session_system_prompt(&config, Some("Fix this bug and create a PR"));
+
+        assert!(prompt.contains("## Skill: security"));
+        assert!(!prompt.contains("## Skill: git"));
+        assert!(!prompt.contains("## Skill: debugging"));
+    }
+
+    #[test]
+    fn test_skill_set_is_stable_across_turns() {
+        let (_dir, config) = test_config(None, SkillSelection::Auto, PromptMode::default());
+        let skills = SkillSet::resolve(&config, Some("Fix this bug and create a PR"));
+        assert!(skills.contains("git"));
+        assert!(skills.contains("debugging"));
+
+        let first = skills.build_prompt(&config);
+        for _ in 0..3 {
+            assert_eq!(skills.build_prompt(&config), first);
+        }
+        let mut expected = skills.names();
+        expected.sort_by_key(|s| skill_priority(s));
+        assert_eq!(
+            resolve_effective_prompt(&config, &skills.names()).skills_injected,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_skill_set_add_remove() {
+        let (_dir, config) = test_config(
+            None,
+            SkillSelection::Explicit(vec!["git".to_string()]),
+            PromptMode::default(),
+        );
+        let mut skills = SkillSet::resolve(&config, None);
+        assert_eq!(skills.names(), vec!["git"]);
+
+        assert!(skills.add("security"));
+        assert!(!skills.add("security"));
+        assert!(!skills.add("nonexistent-skill"));
+        assert!(skills.build_prompt(&config).contains("## Skill: security"));
+
+        assert!(skills.remove("git"));
+        assert!(!skills.remove("git"));
+        assert_eq!(skills.names(), vec!["security"]);
+        assert!(!skills.build_prompt(&config).contains("## Skill: git"));
+    }
+
+    #[test]
+    fn test_skill_set_off_uses_monolithic_prompt() {
+        let (_dir, config) = test_config(None, SkillSelection::Off, PromptMode::default());
+        let skills = SkillSet::resolve(&config, Some("Fix this bug and create a PR"));
+
+        assert!(skills.is_empty());
+        assert_eq!(skills.build_prompt(&config), build_system_prompt(&config));
+    }
+
+    #[test]
+    fn test_skill_set_auto_detects_only_in_skills_mode() {
+        let (_dir, config) = test_config(None, SkillSelection::Auto, PromptMode::Monolithic);
+        let skills = SkillSet::resolve(&config, Some("Fix this bug and create a PR"));
+
+        assert!(skills.is_empty());
+        assert_eq!(skills.build_prompt(&config), build_system_prompt(&config));
+    }
+
+    #[test]
+    fn test_skill_selection_default_is_auto() {
+        assert_eq!(SkillSelection::default(), SkillSelection::Auto);
+    }
+
+    // =========================================================================
+    // Effective Prompt Tests
+    // =========================================================================
+
+    #[test]
+    fn test_effective_prompt_reconstructs_built_prompt() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        std::fs::write(
+            dir.path().join("AGENTS.md"),
+            "Run cargo fmt before committing.",
+        )
+        .unwrap();
+        let config = Config {
+            cwd: dir.path().to_path_buf(),
+            cortex_home: dir.path().join(".cortex"),
+            user_instructions: Some("Prefer small commits.".to_string()),
+            current_agent: None,
+            ..Config::default()
+        };
+        let skills = ["git", "nonexistent-skill", "debugging"];
+
+        let effective = resolve_effective_prompt(&config, &skills);
+
+        assert_eq!(effective.skills_injected, vec!["debugging", "git"]);
+        assert_eq!(effective.agents_sources, vec![dir.path().join("AGENTS.md")]);
+        assert!(effective.system.contains("## Skill: git"));
+        assert!(
+            effective
+                .additional_context
+                .contains("Run cargo fmt before committing.")
+        );
+        assert!(
+            effective
+                .additional_context
+                .contains("Prefer small commits.")
+        );
+
+        let reconstructed = format!("{}\n\n{}", effective.system, effective.additional_context);
+        assert_eq!(
+            reconstructed,
+            build_system_prompt_with_skills(&config, &skills)
+        );
+        assert_eq!(effective.render(), reconstructed);
+    }
+
+    // =========================================================================
+    // AGENTS.md Cache Tests
+    // =========================================================================
+
+    fn set_mtime(path: &Path, time: SystemTime) {
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_agents_md_cache_reloads_on_mtime_change() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        let agents = dir.path().join("AGENTS.md");
+        std::fs::write(&agents, "Version one.").unwrap();
+        let config = Config {
+            cwd: dir.path().to_path_buf(),
+            cortex_home: dir.path().join(".cortex"),
+            ..Config::default()
+        };
+        let cache = AgentsMdCache::new();
+
+        assert_eq!(cache.load(&config), "Version one.");
+        assert_eq!(cache.len(), 1);
+
+        // Same size and mtime: served from memory
+        let original = std::fs::metadata(&agents).unwrap().modified().unwrap();
+        std::fs::write(&agents, "Version two.").unwrap();
+        set_mtime(&agents, original);
+        assert_eq!(cache.load(&config), "Version one.");
+
+        // Advancing the mtime invalidates the entry
+        set_mtime(&agents, original + std::time::Duration::from_secs(10));
+        assert_eq!(cache.load(&config), "Version two.");
+    }
+
+    #[test]
+    fn test_agents_md_truncated_at_char_boundary() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        // 'é' is two bytes, so an odd limit falls inside a character
+        std::fs::write(dir.path().join("AGENTS.md"), "é".repeat(1_000)).unwrap();
+        let config = Config {
+            cwd: dir.path().to_path_buf(),
+            cortex_home: dir.path().join(".cortex"),
+            agents_md_max_bytes: 101,
+            ..Config::default()
+        };
+
+        let loaded = AgentsMdCache::new().load(&config);
+        let marker = "\n\n[AGENTS.md truncated: exceeded 101 bytes]";
+
+        assert!(loaded.ends_with(marker));
+        let kept = loaded.strip_suffix(marker).unwrap();
+        assert_eq!(kept, "é".repeat(50));
+    }
+
+    #[test]
+    fn test_agents_md_limit_applies_to_combined_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        let sub = dir.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        std::fs::write(dir.path().join("AGENTS.md"), "a".repeat(60)).unwrap();
+        std::fs::write(sub.join("AGENTS.md"), "b".repeat(60)).unwrap();
+        let mut config = Config {
+            cwd: sub.clone(),
+            cortex_home: dir.path().join(".cortex"),
+            agents_md_max_bytes: 100,
+            ..Config::default()
+        };
+        let cache = AgentsMdCache::new();
+
+        let loaded = cache.load(&config);
+        assert!(loaded.starts_with(&"a".repeat(60)));
+        assert!(loaded.contains("[AGENTS.md truncated: exceeded 100 bytes]"));
+        assert!(!loaded.contains(&"b".repeat(60)));
+
+        // Under the default limit nothing is truncated
+        config.agents_md_max_bytes = DEFAULT_AGENTS_MD_MAX_BYTES;
+        assert!(!cache.load(&config).contains("[AGENTS.md truncated"));
+    }
+
+    #[test]
+    fn test_agents_md_cache_preserves_override_semantics() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        let sub = dir.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        std::fs::write(dir.path().join("AGENTS.md"), "Root rules.").unwrap();
+        std::fs::write(sub.join("AGENTS.md"), "Sub rules.").unwrap();
+        let config = Config {
+            cwd: sub.clone(),
+            cortex_home: dir.path().join(".cortex"),
+            ..Config::default()
+        };
+        let cache = AgentsMdCache::new();
+
+        assert_eq!(cache.load(&config), "Root rules.\n\n---\n\nSub rules.");
+
+        std::fs::write(sub.join("AGENTS.override.md"), "Override only.").unwrap();
+        assert_eq!(cache.load(&config), "Override only.");
+
+        // Removed files drop out of the cache and the merge
+        std::fs::remove_file(sub.join("AGENTS.override.md")).unwrap();
+        assert_eq!(cache.load(&config), "Root rules.\n\n---\n\nSub rules.");
+    }
+
+    #[test]
+    fn test_prompt_mode_monolithic_without_skills() {
+        let (_dir, config) = test_config(None, SkillSelection::default(), PromptMode::Monolithic);
+        let prompt = build_system_prompt_with_skills(&config, &[]);
+
+        assert!(prompt.contains("PRIME DIRECTIVES"));
+        assert!(!prompt.contains("load_skill"));
+
+        // Requested skills still use the skill-based base prompt.
+        let prompt = build_system_prompt_with_skills(&config, &["git"]);
+        assert!(prompt.contains("## Skill: git"));
+    }
+
+    #[test]
+    fn test_prompt_mode_skills_without_skills() {
+        let (_dir, config) = test_config(None, SkillSelection::default(), PromptMode::Skills);
+        let prompt = build_system_prompt_with_skills(&config, &[]);
+
+        assert!(prompt.contains("load_skill"));
+        assert!(!prompt.contains("PRIME DIRECTIVES"));
+    }
+
+    #[test]
+    fn test_prompt_mode_parse() {
+        assert_eq!(PromptMode::parse("skills"), Some(PromptMode::Skills));
+        assert_eq!(
+            PromptMode::parse(" Monolithic "),
+            Some(PromptMode::Monolithic)
+        );
+        assert_eq!(PromptMode::parse("full"), None);
+        assert_eq!(PromptMode::parse(""), None);
+    }
+
     #[test]
-    fn test_use_skill_based_prompt_default() {
-        // Verify the default value
-        assert!(USE_SKILL_BASED_PROMPT);
+    fn test_prompt_mode_env_overrides_config() {
+        use crate::config::effective_prompt_mode;
+
+        let configured = Some(PromptMode::Monolithic);
+        assert_eq!(
+            effective_prompt_mode(Some("skills"), configured),
+            PromptMode::Skills
+        );
+        assert_eq!(
+            effective_prompt_mode(Some("MONOLITHIC"), Some(PromptMode::Skills)),
+            PromptMode::Monolithic
+        );
+        // Unset or unrecognized values fall back to the config file, then the default.
+        assert_eq!(
+            effective_prompt_mode(None, configured),
+            PromptMode::Monolithic
+        );
+        assert_eq!(
+            effective_prompt_mode(Some("full"), configured),
+            PromptMode::Monolithic
+        );
+        assert_eq!(effective_prompt_mode(None, None), PromptMode::Skills);
     }
 
     #[test]
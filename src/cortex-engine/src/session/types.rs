@@ -6,6 +6,7 @@ use std::sync::atomic::AtomicBool;
 
 use async_channel::{Receiver, Sender};
 
+use cortex_prompt_harness::estimate_tokens;
 use cortex_protocol::{ConversationId, Event, Submission};
 
 use crate::client::types::MessageContent;
@@ -35,22 +36,24 @@ impl TokenCounter {
             // Base overhead per message (~4 tokens)
             total += 4;
             // Count content tokens
-            let text = match &msg.content {
-                MessageContent::Text(t) => t.len(),
+            let tokens: u32 = match &msg.content {
+                MessageContent::Text(t) => estimate_tokens(t),
                 MessageContent::Parts(parts) => parts
                     .iter()
                     .map(|p| match p {
-                        crate::client::types::ContentPart::Text { text, .. } => text.len(),
+                        crate::client::types::ContentPart::Text { text, .. } => {
+                            estimate_tokens(text)
+                        }
                         _ => 85, // Image tokens approximation
                     })
                     .sum(),
-                MessageContent::ToolResult { content, .. } => content.len(),
-                MessageContent::ToolCalls(calls) => {
-                    calls.iter().map(|c| c.name.len() + c.arguments.len()).sum()
-                }
+                MessageContent::ToolResult { content, .. } => estimate_tokens(content),
+                MessageContent::ToolCalls(calls) => calls
+                    .iter()
+                    .map(|c| estimate_tokens(&c.name) + estimate_tokens(&c.arguments))
+                    .sum(),
             };
-            // Approximate: 4 chars per token
-            total += (text as f64 / 4.0).ceil() as usize;
+            total += tokens as usize;
         }
         // Message separator overhead
         total += messages.len() * 3;
@@ -62,8 +65,8 @@ impl TokenCounter {
         let mut total = 0usize;
         for tool in tools {
             let json = serde_json::to_string(tool).unwrap_or_default();
-            // Approximate: 4 chars per token + 10 overhead
-            total += (json.len() as f64 / 4.0).ceil() as usize + 10;
+            // Approximate tokens + 10 overhead
+            total += estimate_tokens(&json) as usize + 10;
         }
         // Base overhead for having tools
         if !tools.is_empty() {
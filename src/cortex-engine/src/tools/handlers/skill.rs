@@ -1024,5 +1024,8 @@ Analyze {{target}} with verbose={{verbose}}.
         assert!(builtin_names.contains(&"debugging"));
         assert!(builtin_names.contains(&"security"));
         assert!(builtin_names.contains(&"planning"));
+        assert!(builtin_names.contains(&"rust"));
+        assert!(builtin_names.contains(&"docker"));
+        assert!(builtin_names.contains(&"testing"));
     }
 }
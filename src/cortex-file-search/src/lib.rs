@@ -47,7 +47,7 @@ pub use cache::FileCache;
 pub use config::{SearchConfig, SearchConfigBuilder};
 pub use error::{SearchError, SearchResult};
 pub use index::FileIndex;
-pub use matcher::FuzzyMatcher;
+pub use matcher::{FuzzyMatcher, MatchOptions, glob_match, glob_match_opts};
 pub use result::{SearchMatch, SearchMode};
 pub use search::FileSearch;
 
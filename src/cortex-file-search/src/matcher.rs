@@ -1,5 +1,8 @@
 //! Fuzzy matching implementation using nucleo-matcher.
 
+use std::iter::Peekable;
+use std::str::Chars;
+
 use nucleo_matcher::{
     Config, Matcher, Utf32Str,
     pattern::{AtomKind, CaseMatching, Normalization, Pattern},
@@ -206,22 +209,204 @@ impl FuzzyMatcher {
     }
 }
 
+/// Options controlling [`glob_match_opts`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MatchOptions {
+    /// Fold case on both sides before comparing characters.
+    pub case_insensitive: bool,
+}
+
+impl MatchOptions {
+    fn chars_eq(&self, a: char, b: char) -> bool {
+        a == b || (self.case_insensitive && fold_case(a) == fold_case(b))
+    }
+
+    fn in_range(&self, c: char, start: char, end: char) -> bool {
+        let in_range = |c: char| c >= start && c <= end;
+        in_range(c)
+            || (self.case_insensitive && (in_range(fold_case(c)) || c.to_uppercase().all(in_range)))
+    }
+}
+
+/// Lowercases `c` when it maps to a single character.
+fn fold_case(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        _ => c,
+    }
+}
+
 /// Matches a string against a glob pattern.
 ///
 /// Supports the following patterns:
 /// - `*` matches any sequence of characters except path separators
-/// - `**` matches any sequence including path separators
+/// - `**` matches any sequence including path separators; as a `**/`
+///   segment it matches zero or more whole path components
 /// - `?` matches a single character
 /// - `[abc]` matches any character in the set
 /// - `[!abc]` matches any character not in the set
+/// - `{a,b,c}` matches any of the comma-separated alternatives (may nest)
+///
+/// `\{`, `\}` and `\,` match the literal character.
+///
+/// Matching is case-sensitive; see [`glob_match_opts`].
 pub fn glob_match(pattern: &str, text: &str) -> bool {
-    // Normalize path separators
-    let pattern = pattern.replace('\\', "/");
+    glob_match_opts(pattern, text, MatchOptions::default())
+}
+
+/// Matches a string against a glob pattern with the given options.
+///
+/// Supports the same syntax as [`glob_match`].
+pub fn glob_match_opts(pattern: &str, text: &str, opts: MatchOptions) -> bool {
     let text = text.replace('\\', "/");
-    glob_match_recursive(&pattern, &text)
+    expand_braces(pattern).iter().any(|pattern| {
+        // Normalize path separators
+        let pattern = unescape_braces(pattern).replace('\\', "/");
+        glob_match_recursive(&pattern, &text, opts)
+    })
+}
+
+/// Expands every `{...}` group into the cartesian set of concrete patterns.
+///
+/// Escaped braces and commas are kept as-is; an unmatched `{` is literal.
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let Some((start, end)) = find_brace_group(pattern) else {
+        return vec![pattern.to_string()];
+    };
+
+    let prefix = &pattern[..start];
+    let suffix = &pattern[end + 1..];
+    split_alternatives(&pattern[start + 1..end])
+        .into_iter()
+        .flat_map(|alt| expand_braces(&format!("{prefix}{alt}{suffix}")))
+        .collect()
+}
+
+/// Byte offsets of the first unescaped `{` and its matching `}`.
+fn find_brace_group(pattern: &str) -> Option<(usize, usize)> {
+    let mut chars = pattern.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '{' => {
+                if let Some(end) = find_closing_brace(&pattern[i..]) {
+                    return Some((i, i + end));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
 }
 
-fn glob_match_recursive(pattern: &str, text: &str) -> bool {
+/// Byte offset of the `}` closing the `{` at the start of `group`.
+fn find_closing_brace(group: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut chars = group.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits the body of a brace group on its top-level commas.
+fn split_alternatives(body: &str) -> Vec<&str> {
+    let mut alternatives = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut chars = body.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                alternatives.push(&body[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    alternatives.push(&body[start..]);
+    alternatives
+}
+
+/// Matches `c` against the character class following a `[`, consuming it
+/// through the closing `]`.
+///
+/// A leading `!` or `^` negates the class, and a `]` right after the opening
+/// bracket (or the negation) is literal. Classes never match `/`. Returns
+/// `None` if the class is unterminated.
+fn match_class(pat_chars: &mut Peekable<Chars<'_>>, c: char, opts: MatchOptions) -> Option<bool> {
+    let negated = pat_chars.next_if(|&p| p == '!' || p == '^').is_some();
+
+    let mut matched = false;
+    let mut prev_char: Option<char> = None;
+    let mut first = true;
+
+    loop {
+        let p = pat_chars.next()?;
+        if p == ']' && !first {
+            break;
+        }
+        first = false;
+
+        // Range
+        if p == '-'
+            && let Some(start) = prev_char
+            && let Some(end) = pat_chars.next_if(|&end| end != ']')
+        {
+            if opts.in_range(c, start, end) {
+                matched = true;
+            }
+            prev_char = None;
+            continue;
+        }
+
+        if opts.chars_eq(c, p) {
+            matched = true;
+        }
+        prev_char = Some(p);
+    }
+
+    Some(c != '/' && matched != negated)
+}
+
+/// Drops the backslash from `\{`, `\}` and `\,` escapes.
+fn unescape_braces(pattern: &str) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\'
+            && let Some(&next @ ('{' | '}' | ',')) = chars.peek()
+        {
+            out.push(next);
+            chars.next();
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn glob_match_recursive(pattern: &str, text: &str, opts: MatchOptions) -> bool {
     let mut pat_chars = pattern.chars().peekable();
     let mut txt_chars = text.chars().peekable();
 
@@ -232,8 +417,9 @@ fn glob_match_recursive(pattern: &str, text: &str) -> bool {
                 if pat_chars.peek() == Some(&'*') {
                     pat_chars.next(); // consume second *
 
-                    // Skip any trailing slash after **
-                    if pat_chars.peek() == Some(&'/') {
+                    // A `**/` segment may only resume at a component boundary
+                    let segment = pat_chars.peek() == Some(&'/');
+                    if segment {
                         pat_chars.next();
                     }
 
@@ -248,14 +434,18 @@ fn glob_match_recursive(pattern: &str, text: &str) -> bool {
                     let remaining_text: String = txt_chars.collect();
 
                     // Try matching at every position including after path separators
-                    if glob_match_recursive(&remaining_pattern, &remaining_text) {
+                    if glob_match_recursive(&remaining_pattern, &remaining_text, opts) {
                         return true;
                     }
 
                     for (i, c) in remaining_text.char_indices() {
+                        if segment && c != '/' {
+                            continue;
+                        }
                         if glob_match_recursive(
                             &remaining_pattern,
                             &remaining_text[i + c.len_utf8()..],
+                            opts,
                         ) {
                             return true;
                         }
@@ -270,7 +460,7 @@ fn glob_match_recursive(pattern: &str, text: &str) -> bool {
                     let remaining_text: String = txt_chars.collect();
 
                     // Try matching at current position
-                    if glob_match_recursive(&remaining_pattern, &remaining_text) {
+                    if glob_match_recursive(&remaining_pattern, &remaining_text, opts) {
                         return true;
                     }
 
@@ -283,6 +473,7 @@ fn glob_match_recursive(pattern: &str, text: &str) -> bool {
                         if glob_match_recursive(
                             &remaining_pattern,
                             &remaining_text[i + c.len_utf8()..],
+                            opts,
                         ) {
                             return true;
                         }
@@ -305,53 +496,19 @@ fn glob_match_recursive(pattern: &str, text: &str) -> bool {
                     None => return false,
                 };
 
-                let negated = pat_chars.peek() == Some(&'!') || pat_chars.peek() == Some(&'^');
-                if negated {
-                    pat_chars.next();
-                }
-
-                let mut matched = false;
-                let mut prev_char: Option<char> = None;
-
-                loop {
-                    match pat_chars.next() {
-                        None => return false, // Unclosed bracket
-                        Some(']') => break,
-                        Some('-') => {
-                            // Range
-                            if let (Some(start), Some(end)) = (prev_char, pat_chars.peek().copied())
-                                && end != ']'
-                            {
-                                pat_chars.next();
-                                if txt_c >= start && txt_c <= end {
-                                    matched = true;
-                                }
-                                prev_char = None;
-                                continue;
-                            }
-                            // Literal -
-                            if txt_c == '-' {
-                                matched = true;
-                            }
-                            prev_char = Some('-');
-                        }
-                        Some(c) => {
-                            if txt_c == c {
-                                matched = true;
-                            }
-                            prev_char = Some(c);
-                        }
-                    }
-                }
-
-                if matched == negated {
-                    return false;
+                let mut class_chars = pat_chars.clone();
+                match match_class(&mut class_chars, txt_c, opts) {
+                    Some(true) => pat_chars = class_chars,
+                    Some(false) => return false,
+                    // Unterminated bracket - match `[` literally
+                    None if txt_c == '[' => continue,
+                    None => return false,
                 }
             }
             c => {
                 // Literal character
                 match txt_chars.next() {
-                    Some(tc) if tc == c => continue,
+                    Some(tc) if opts.chars_eq(tc, c) => continue,
                     _ => return false,
                 }
             }
@@ -498,4 +655,122 @@ mod tests {
         // Double ** should match path separators
         assert!(glob_match("src/**/*.rs", "src/foo/bar.rs"));
     }
+
+    #[test]
+    fn test_glob_match_globstar_segments() {
+        // Zero or more components in the middle
+        assert!(glob_match("src/**/mod.rs", "src/mod.rs"));
+        assert!(glob_match("src/**/mod.rs", "src/hooks/mod.rs"));
+        assert!(glob_match("src/**/mod.rs", "src/a/b/c/mod.rs"));
+        assert!(!glob_match("src/**/mod.rs", "src/amod.rs"));
+
+        // Leading **
+        assert!(glob_match("**/foo", "foo"));
+        assert!(glob_match("**/foo", "a/b/foo"));
+        assert!(!glob_match("**/foo", "afoo"));
+        assert!(!glob_match("**/foo", "a/foo/b"));
+
+        // Trailing **
+        assert!(glob_match("foo/**", "foo/a"));
+        assert!(glob_match("foo/**", "foo/a/b.rs"));
+        assert!(!glob_match("foo/**", "foobar/a"));
+
+        // Bare ** matches everything
+        assert!(glob_match("**", "a"));
+        assert!(glob_match("**", "a/b/c.rs"));
+        assert!(glob_match("**", ""));
+
+        // Single * still refuses to cross /
+        assert!(!glob_match("*/foo", "a/b/foo"));
+        assert!(!glob_match("*", "a/b"));
+    }
+
+    #[test]
+    fn test_glob_match_braces() {
+        assert!(glob_match("*.{rs,toml}", "main.rs"));
+        assert!(glob_match("*.{rs,toml}", "Cargo.toml"));
+        assert!(!glob_match("*.{rs,toml}", "main.go"));
+
+        assert!(glob_match("src/*.{rs,toml}", "src/lib.rs"));
+        // Expansions keep the separator rules
+        assert!(!glob_match("src/*.{rs,toml}", "src/foo/lib.rs"));
+        assert!(glob_match("src/**/*.{rs,toml}", "src/foo/lib.rs"));
+    }
+
+    #[test]
+    fn test_glob_match_nested_braces() {
+        assert!(glob_match("{a,{b,c}}.txt", "a.txt"));
+        assert!(glob_match("{a,{b,c}}.txt", "b.txt"));
+        assert!(glob_match("{a,{b,c}}.txt", "c.txt"));
+        assert!(!glob_match("{a,{b,c}}.txt", "d.txt"));
+        assert!(!glob_match("{a,{b,c}}.txt", "{b,c}.txt"));
+    }
+
+    #[test]
+    fn test_glob_match_negated_class() {
+        assert!(glob_match("[!abc].rs", "d.rs"));
+        assert!(!glob_match("[!abc].rs", "b.rs"));
+
+        assert!(glob_match("v[^0-9]", "vx"));
+        assert!(!glob_match("v[^0-9]", "v7"));
+
+        // Classes never match the path separator
+        assert!(!glob_match("a[!x]b", "a/b"));
+        assert!(!glob_match("a[/]b", "a/b"));
+    }
+
+    #[test]
+    fn test_glob_match_class_literal_bracket() {
+        assert!(glob_match("[]a]", "]"));
+        assert!(glob_match("[]a]", "a"));
+        assert!(!glob_match("[]a]", "b"));
+
+        assert!(glob_match("[!]a]", "b"));
+        assert!(!glob_match("[!]a]", "]"));
+    }
+
+    #[test]
+    fn test_glob_match_unterminated_class() {
+        assert!(glob_match("file[1", "file[1"));
+        assert!(!glob_match("file[1", "file1"));
+        assert!(glob_match("[*", "[abc"));
+    }
+
+    #[test]
+    fn test_glob_match_case_insensitive() {
+        let ci = MatchOptions {
+            case_insensitive: true,
+        };
+
+        assert!(!glob_match("*.md", "FILE.MD"));
+        assert!(glob_match_opts("*.md", "FILE.MD", ci));
+        assert!(glob_match_opts("*.MD", "readme.md", ci));
+        assert!(glob_match_opts("READ?E.md", "readme.MD", ci));
+        assert!(!glob_match_opts("*.md", "FILE.MDX", ci));
+
+        // Unicode folding
+        assert!(glob_match_opts("ÉTÉ.txt", "été.TXT", ci));
+    }
+
+    #[test]
+    fn test_glob_match_case_insensitive_bracket() {
+        let ci = MatchOptions {
+            case_insensitive: true,
+        };
+
+        assert!(!glob_match("[a-c]x.rs", "Bx.rs"));
+        assert!(glob_match_opts("[a-c]x.rs", "Bx.rs", ci));
+        assert!(glob_match_opts("[A-C]x.rs", "bx.rs", ci));
+        assert!(glob_match_opts("main.[RT]s", "main.rs", ci));
+        assert!(!glob_match_opts("[!a-c]x.rs", "Bx.rs", ci));
+    }
+
+    #[test]
+    fn test_glob_match_escaped_brace() {
+        assert!(glob_match("\\{a,b\\}.txt", "{a,b}.txt"));
+        assert!(!glob_match("\\{a,b\\}.txt", "a.txt"));
+
+        // An unmatched brace is literal
+        assert!(glob_match("{a.txt", "{a.txt"));
+    }
 }
@@ -1,25 +1,174 @@
 //! Hook dispatcher for executing hooks in priority order.
 
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::FutureExt;
 
 use super::chat_hooks::{ChatMessageInput, ChatMessageOutput};
 use super::permission_hooks::{PermissionAskInput, PermissionAskOutput, PermissionDecision};
-use super::registry::HookRegistry;
+use super::registry::{HookRegistry, RegisteredToolBeforeHook};
+use super::session_hooks::{
+    SessionEndInput, SessionEndOutput, SessionStartInput, SessionStartOutput,
+};
 use super::tool_hooks::{
     ToolExecuteAfterInput, ToolExecuteAfterOutput, ToolExecuteBeforeInput, ToolExecuteBeforeOutput,
 };
 use super::types::HookResult;
 use crate::Result;
 
+/// Timing for a single trigger call.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DispatchStats {
+    /// Hook type that was triggered (e.g. `tool.execute.before`).
+    pub hook: &'static str,
+    /// Number of hooks whose `execute` was called.
+    pub hooks_invoked: usize,
+    /// Sum of all hook execution times.
+    pub total_duration: Duration,
+    /// Longest single hook execution.
+    pub max_duration: Duration,
+}
+
+impl DispatchStats {
+    fn new(hook: &'static str) -> Self {
+        Self {
+            hook,
+            ..Self::default()
+        }
+    }
+
+    fn record(&mut self, elapsed: Duration) {
+        self.hooks_invoked += 1;
+        self.total_duration += elapsed;
+        self.max_duration = self.max_duration.max(elapsed);
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.hooks_invoked += other.hooks_invoked;
+        self.total_duration += other.total_duration;
+        self.max_duration = self.max_duration.max(other.max_duration);
+    }
+}
+
 /// Dispatcher for executing hooks.
+///
+/// A hook that panics is logged and treated as `HookResult::Continue`, like
+/// one that times out. Catching the panic is a last line of defence only:
+/// hooks must not rely on unwinding across the dispatcher, and any partial
+/// changes they made to the output before panicking are kept. Builds with
+/// `panic = "abort"` (such as the release profile) still abort.
 pub struct HookDispatcher {
     registry: Arc<HookRegistry>,
+    timeout: Option<Duration>,
+    concurrent_observers: bool,
+    last_stats: Mutex<DispatchStats>,
 }
 
 impl HookDispatcher {
     /// Create a new dispatcher.
     pub fn new(registry: Arc<HookRegistry>) -> Self {
-        Self { registry }
+        Self {
+            registry,
+            timeout: None,
+            concurrent_observers: false,
+            last_stats: Mutex::new(DispatchStats::default()),
+        }
+    }
+
+    /// Registry the dispatcher reads hooks from.
+    pub fn registry(&self) -> &Arc<HookRegistry> {
+        &self.registry
+    }
+
+    /// Bound each hook execution by `timeout`.
+    ///
+    /// A hook that runs past the timeout is abandoned, logged, and treated
+    /// as `HookResult::Continue` so the rest of the chain still runs.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Per-hook execution timeout, if any.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Run observer `tool.execute.before` hooks concurrently.
+    ///
+    /// When enabled, hooks whose `is_observer()` is true are fired together
+    /// before the other hooks, each on a copy of the output that is then
+    /// discarded; the first observer error is returned. The remaining hooks
+    /// run sequentially in priority order as usual. When disabled (the
+    /// default), observers are treated like any other hook.
+    pub fn with_concurrent_observers(mut self, enabled: bool) -> Self {
+        self.concurrent_observers = enabled;
+        self
+    }
+
+    /// Stats of the most recent trigger call.
+    pub fn last_dispatch_stats(&self) -> DispatchStats {
+        self.last_stats
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn store_stats(&self, stats: DispatchStats) {
+        *self.last_stats.lock().unwrap_or_else(|e| e.into_inner()) = stats;
+    }
+
+    /// Run a single hook, applying the timeout and recording its duration.
+    ///
+    /// Returns `Ok(false)` if the hook timed out or panicked.
+    async fn run_hook(
+        &self,
+        stats: &mut DispatchStats,
+        plugin_id: &str,
+        execution: impl Future<Output = Result<()>>,
+    ) -> Result<bool> {
+        let started = Instant::now();
+        let execution = AssertUnwindSafe(execution).catch_unwind();
+        let outcome = match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, execution).await.ok(),
+            None => Some(execution.await),
+        };
+        stats.record(started.elapsed());
+
+        match outcome {
+            Some(Ok(Ok(()))) => Ok(true),
+            Some(Ok(Err(e))) => {
+                self.store_stats(stats.clone());
+                Err(e)
+            }
+            Some(Err(panic)) => {
+                let message = panic
+                    .downcast_ref::<&str>()
+                    .copied()
+                    .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+                    .unwrap_or("unknown panic");
+                tracing::error!(
+                    plugin_id = %plugin_id,
+                    hook = %stats.hook,
+                    panic = %message,
+                    "Hook panicked, continuing with next hook"
+                );
+                Ok(false)
+            }
+            None => {
+                tracing::warn!(
+                    plugin_id = %plugin_id,
+                    hook = %stats.hook,
+                    timeout_ms = self.timeout.unwrap_or_default().as_millis() as u64,
+                    "Hook timed out, continuing with next hook"
+                );
+                Ok(false)
+            }
+        }
     }
 
     /// Trigger tool.execute.before hooks.
@@ -29,8 +178,18 @@ impl HookDispatcher {
     ) -> Result<ToolExecuteBeforeOutput> {
         let mut output = ToolExecuteBeforeOutput::new(input.args.clone());
         let hooks = self.registry.tool_execute_before.read().await;
+        let mut stats = DispatchStats::new("tool.execute.before");
+
+        if self.concurrent_observers {
+            self.run_tool_observers(&hooks, &input, &output, &mut stats)
+                .await?;
+        }
 
         for registered in hooks.iter() {
+            if self.concurrent_observers && registered.hook.is_observer() {
+                continue;
+            }
+
             // Check pattern match
             if let Some(pattern) = registered.hook.pattern() {
                 if !Self::matches_pattern(&input.tool, pattern) {
@@ -38,7 +197,17 @@ impl HookDispatcher {
                 }
             }
 
-            registered.hook.execute(&input, &mut output).await?;
+            if !self
+                .run_hook(
+                    &mut stats,
+                    &registered.plugin_id,
+                    registered.hook.execute(&input, &mut output),
+                )
+                .await?
+            {
+                output.result = HookResult::Continue;
+                continue;
+            }
 
             // Check if we should stop
             match &output.result {
@@ -47,9 +216,58 @@ impl HookDispatcher {
             }
         }
 
+        self.store_stats(stats);
         Ok(output)
     }
 
+    /// Fire the matching observer `tool.execute.before` hooks concurrently.
+    async fn run_tool_observers(
+        &self,
+        hooks: &[RegisteredToolBeforeHook],
+        input: &ToolExecuteBeforeInput,
+        output: &ToolExecuteBeforeOutput,
+        stats: &mut DispatchStats,
+    ) -> Result<()> {
+        let hook_name = stats.hook;
+        let runs = hooks
+            .iter()
+            .filter(|registered| registered.hook.is_observer())
+            .filter(|registered| {
+                registered
+                    .hook
+                    .pattern()
+                    .is_none_or(|pattern| Self::matches_pattern(&input.tool, pattern))
+            })
+            .map(|registered| async move {
+                let mut scratch = output.clone();
+                let mut run_stats = DispatchStats::new(hook_name);
+                let result = self
+                    .run_hook(
+                        &mut run_stats,
+                        &registered.plugin_id,
+                        registered.hook.execute(input, &mut scratch),
+                    )
+                    .await;
+                (run_stats, result)
+            });
+
+        let mut first_error = None;
+        for (run_stats, result) in futures::future::join_all(runs).await {
+            stats.merge(&run_stats);
+            if let Err(e) = result {
+                first_error.get_or_insert(e);
+            }
+        }
+
+        match first_error {
+            Some(e) => {
+                self.store_stats(stats.clone());
+                Err(e)
+            }
+            None => Ok(()),
+        }
+    }
+
     /// Trigger tool.execute.after hooks.
     pub async fn trigger_tool_execute_after(
         &self,
@@ -58,6 +276,7 @@ impl HookDispatcher {
     ) -> Result<ToolExecuteAfterOutput> {
         let mut output = ToolExecuteAfterOutput::new(tool_output);
         let hooks = self.registry.tool_execute_after.read().await;
+        let mut stats = DispatchStats::new("tool.execute.after");
 
         for registered in hooks.iter() {
             // Check pattern match
@@ -67,7 +286,17 @@ impl HookDispatcher {
                 }
             }
 
-            registered.hook.execute(&input, &mut output).await?;
+            if !self
+                .run_hook(
+                    &mut stats,
+                    &registered.plugin_id,
+                    registered.hook.execute(&input, &mut output),
+                )
+                .await?
+            {
+                output.result = HookResult::Continue;
+                continue;
+            }
 
             match &output.result {
                 HookResult::Skip | HookResult::Abort { .. } | HookResult::Replace { .. } => break,
@@ -75,6 +304,7 @@ impl HookDispatcher {
             }
         }
 
+        self.store_stats(stats);
         Ok(output)
     }
 
@@ -86,9 +316,20 @@ impl HookDispatcher {
     ) -> Result<ChatMessageOutput> {
         let mut output = ChatMessageOutput::new(content);
         let hooks = self.registry.chat_message.read().await;
+        let mut stats = DispatchStats::new("chat.message");
 
         for registered in hooks.iter() {
-            registered.hook.execute(&input, &mut output).await?;
+            if !self
+                .run_hook(
+                    &mut stats,
+                    &registered.plugin_id,
+                    registered.hook.execute(&input, &mut output),
+                )
+                .await?
+            {
+                output.result = HookResult::Continue;
+                continue;
+            }
 
             match &output.result {
                 HookResult::Skip | HookResult::Abort { .. } | HookResult::Replace { .. } => break,
@@ -96,6 +337,7 @@ impl HookDispatcher {
             }
         }
 
+        self.store_stats(stats);
         Ok(output)
     }
 
@@ -106,9 +348,19 @@ impl HookDispatcher {
     ) -> Result<PermissionAskOutput> {
         let mut output = PermissionAskOutput::ask();
         let hooks = self.registry.permission_ask.read().await;
+        let mut stats = DispatchStats::new("permission.ask");
 
         for registered in hooks.iter() {
-            registered.hook.execute(&input, &mut output).await?;
+            if !self
+                .run_hook(
+                    &mut stats,
+                    &registered.plugin_id,
+                    registered.hook.execute(&input, &mut output),
+                )
+                .await?
+            {
+                continue;
+            }
 
             // Stop if a decision was made
             if output.decision != PermissionDecision::Ask {
@@ -116,32 +368,612 @@ impl HookDispatcher {
             }
         }
 
+        self.store_stats(stats);
+        Ok(output)
+    }
+
+    /// Trigger session.start hooks.
+    pub async fn trigger_session_start(
+        &self,
+        input: SessionStartInput,
+    ) -> Result<SessionStartOutput> {
+        let mut output = SessionStartOutput::new();
+        let hooks = self.registry.session_start.read().await;
+        let mut stats = DispatchStats::new("session.start");
+
+        for registered in hooks.iter() {
+            let additions_before = output.system_prompt_additions.len();
+            if !self
+                .run_hook(
+                    &mut stats,
+                    &registered.plugin_id,
+                    registered.hook.execute(&input, &mut output),
+                )
+                .await?
+            {
+                // Drop whatever the failed hook had added so far
+                output.system_prompt_additions.truncate(additions_before);
+                output.result = HookResult::Continue;
+                continue;
+            }
+
+            match &output.result {
+                HookResult::Skip | HookResult::Abort { .. } | HookResult::Replace { .. } => break,
+                HookResult::Continue => {}
+            }
+        }
+
+        // Several plugins may contribute the same line; keep its first occurrence
+        let mut seen = HashSet::new();
+        output
+            .system_prompt_additions
+            .retain(|line| seen.insert(line.clone()));
+
+        self.store_stats(stats);
+        Ok(output)
+    }
+
+    /// Trigger session.end hooks.
+    pub async fn trigger_session_end(&self, input: SessionEndInput) -> Result<SessionEndOutput> {
+        let mut output = SessionEndOutput::new();
+        let hooks = self.registry.session_end.read().await;
+        let mut stats = DispatchStats::new("session.end");
+
+        for registered in hooks.iter() {
+            if !self
+                .run_hook(
+                    &mut stats,
+                    &registered.plugin_id,
+                    registered.hook.execute(&input, &mut output),
+                )
+                .await?
+            {
+                output.result = HookResult::Continue;
+                continue;
+            }
+
+            match &output.result {
+                HookResult::Skip | HookResult::Abort { .. } | HookResult::Replace { .. } => break,
+                HookResult::Continue => {}
+            }
+        }
+
+        self.store_stats(stats);
         Ok(output)
     }
 
     /// Check if a tool name matches a pattern.
+    ///
+    /// Each `*` matches any sequence of characters; without a wildcard the
+    /// match is exact.
     fn matches_pattern(tool: &str, pattern: &str) -> bool {
-        if pattern == "*" {
-            return true;
-        }
+        let segments: Vec<&str> = pattern.split('*').collect();
+        let [prefix, middle @ .., suffix] = segments.as_slice() else {
+            return tool == pattern;
+        };
 
-        if pattern.contains('*') {
-            // Simple glob matching
-            let parts: Vec<&str> = pattern.split('*').collect();
-            if parts.len() == 2 {
-                let prefix = parts[0];
-                let suffix = parts[1];
-                return tool.starts_with(prefix) && tool.ends_with(suffix);
+        let Some(mut remaining) = tool.strip_prefix(prefix) else {
+            return false;
+        };
+        // Match inner segments left to right, leaving the suffix for last
+        for segment in middle {
+            match remaining.find(segment) {
+                Some(pos) => remaining = &remaining[pos + segment.len()..],
+                None => return false,
             }
         }
 
-        tool == pattern
+        remaining.ends_with(suffix)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hooks::{HookPriority, SessionEndHook, SessionStartHook, ToolExecuteBeforeHook};
+    use async_trait::async_trait;
+
+    struct PromptLineHook {
+        line: &'static str,
+        result: HookResult,
+        priority: HookPriority,
+    }
+
+    #[async_trait]
+    impl SessionStartHook for PromptLineHook {
+        fn priority(&self) -> HookPriority {
+            self.priority
+        }
+
+        async fn execute(
+            &self,
+            _input: &SessionStartInput,
+            output: &mut SessionStartOutput,
+        ) -> Result<()> {
+            output.system_prompt_additions.push(self.line.to_string());
+            output.result = self.result.clone();
+            Ok(())
+        }
+    }
+
+    struct SummaryHook;
+
+    #[async_trait]
+    impl SessionEndHook for SummaryHook {
+        async fn execute(
+            &self,
+            _input: &SessionEndInput,
+            output: &mut SessionEndOutput,
+        ) -> Result<()> {
+            output.generate_summary = true;
+            Ok(())
+        }
+    }
+
+    fn session_start_input() -> SessionStartInput {
+        SessionStartInput {
+            session_id: "session-1".to_string(),
+            agent: None,
+            model: Some("gpt-4".to_string()),
+            cwd: std::path::PathBuf::from("/workspace"),
+            resumed: false,
+        }
+    }
+
+    fn prompt_line_hook(
+        line: &'static str,
+        result: HookResult,
+        priority: HookPriority,
+    ) -> Arc<PromptLineHook> {
+        Arc::new(PromptLineHook {
+            line,
+            result,
+            priority,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_session_start_adds_system_prompt_line() {
+        let registry = Arc::new(HookRegistry::new());
+        registry
+            .register_session_start(
+                "test-plugin",
+                prompt_line_hook(
+                    "Always answer in French.",
+                    HookResult::Continue,
+                    HookPriority::NORMAL,
+                ),
+            )
+            .await;
+
+        let dispatcher = HookDispatcher::new(registry);
+        let output = dispatcher
+            .trigger_session_start(session_start_input())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output.system_prompt_additions,
+            vec!["Always answer in French."]
+        );
+        assert!(matches!(output.result, HookResult::Continue));
+    }
+
+    #[tokio::test]
+    async fn test_session_start_accumulates_additions_in_order() {
+        let registry = Arc::new(HookRegistry::new());
+        for (plugin, line) in [
+            ("style-plugin", "Always answer in French."),
+            ("docs-plugin", "Cite the docs you used."),
+            ("style-copy", "Always answer in French."),
+        ] {
+            registry
+                .register_session_start(
+                    plugin,
+                    prompt_line_hook(line, HookResult::Continue, HookPriority::NORMAL),
+                )
+                .await;
+        }
+
+        let dispatcher = HookDispatcher::new(registry);
+        let output = dispatcher
+            .trigger_session_start(session_start_input())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output.system_prompt_additions,
+            vec!["Always answer in French.", "Cite the docs you used."]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_start_abort_stops_later_hooks() {
+        let registry = Arc::new(HookRegistry::new());
+        registry
+            .register_session_start(
+                "first",
+                prompt_line_hook(
+                    "first",
+                    HookResult::Abort {
+                        reason: "blocked".to_string(),
+                    },
+                    HookPriority::PLUGIN_HIGH,
+                ),
+            )
+            .await;
+        registry
+            .register_session_start(
+                "second",
+                prompt_line_hook("second", HookResult::Continue, HookPriority::LOW),
+            )
+            .await;
+
+        let dispatcher = HookDispatcher::new(registry);
+        let output = dispatcher
+            .trigger_session_start(session_start_input())
+            .await
+            .unwrap();
+
+        assert_eq!(output.system_prompt_additions, vec!["first"]);
+        assert!(matches!(output.result, HookResult::Abort { .. }));
+    }
+
+    struct SleepyToolHook {
+        delay: Duration,
+        key: &'static str,
+        priority: HookPriority,
+    }
+
+    #[async_trait]
+    impl ToolExecuteBeforeHook for SleepyToolHook {
+        fn priority(&self) -> HookPriority {
+            self.priority
+        }
+
+        async fn execute(
+            &self,
+            _input: &ToolExecuteBeforeInput,
+            output: &mut ToolExecuteBeforeOutput,
+        ) -> Result<()> {
+            output.result = HookResult::Abort {
+                reason: "partial".to_string(),
+            };
+            tokio::time::sleep(self.delay).await;
+            output.result = HookResult::Continue;
+            if let Some(obj) = output.args.as_object_mut() {
+                obj.insert(self.key.to_string(), serde_json::json!(true));
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hook_timeout_continues_chain() {
+        let registry = Arc::new(HookRegistry::new());
+        registry
+            .register_tool_execute_before(
+                "stuck-plugin",
+                Arc::new(SleepyToolHook {
+                    delay: Duration::from_secs(30),
+                    key: "stuck",
+                    priority: HookPriority::PLUGIN_HIGH,
+                }),
+            )
+            .await;
+        registry
+            .register_tool_execute_before(
+                "fast-plugin",
+                Arc::new(SleepyToolHook {
+                    delay: Duration::ZERO,
+                    key: "fast",
+                    priority: HookPriority::LOW,
+                }),
+            )
+            .await;
+
+        let dispatcher = HookDispatcher::new(registry).with_timeout(Duration::from_millis(50));
+        assert_eq!(dispatcher.timeout(), Some(Duration::from_millis(50)));
+
+        let input = ToolExecuteBeforeInput {
+            tool: "read".to_string(),
+            session_id: "session-1".to_string(),
+            call_id: "call-1".to_string(),
+            args: serde_json::json!({}),
+        };
+        let output = dispatcher.trigger_tool_execute_before(input).await.unwrap();
+
+        // The stuck hook is abandoned and its partial Abort is discarded
+        assert!(output.args.get("stuck").is_none());
+        assert_eq!(output.args["fast"], true);
+        assert!(matches!(output.result, HookResult::Continue));
+    }
+
+    struct PanickingToolHook;
+
+    #[async_trait]
+    impl ToolExecuteBeforeHook for PanickingToolHook {
+        fn priority(&self) -> HookPriority {
+            HookPriority::PLUGIN_HIGH
+        }
+
+        async fn execute(
+            &self,
+            _input: &ToolExecuteBeforeInput,
+            _output: &mut ToolExecuteBeforeOutput,
+        ) -> Result<()> {
+            panic!("bad plugin glue");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_panicking_hook_continues_chain() {
+        let registry = Arc::new(HookRegistry::new());
+        registry
+            .register_tool_execute_before("panicky-plugin", Arc::new(PanickingToolHook))
+            .await;
+        registry
+            .register_tool_execute_before(
+                "fast-plugin",
+                Arc::new(SleepyToolHook {
+                    delay: Duration::ZERO,
+                    key: "fast",
+                    priority: HookPriority::LOW,
+                }),
+            )
+            .await;
+
+        let dispatcher = HookDispatcher::new(registry);
+        let input = ToolExecuteBeforeInput {
+            tool: "read".to_string(),
+            session_id: "session-1".to_string(),
+            call_id: "call-1".to_string(),
+            args: serde_json::json!({}),
+        };
+        let output = dispatcher.trigger_tool_execute_before(input).await.unwrap();
+
+        assert_eq!(output.args["fast"], true);
+        assert!(matches!(output.result, HookResult::Continue));
+        assert_eq!(dispatcher.last_dispatch_stats().hooks_invoked, 2);
+    }
+
+    /// Records its name and tries to change the output; observers must not.
+    struct RecordingToolHook {
+        name: &'static str,
+        observer: bool,
+        priority: HookPriority,
+        log: Arc<Mutex<Vec<&'static str>>>,
+        barrier: Option<Arc<tokio::sync::Barrier>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl ToolExecuteBeforeHook for RecordingToolHook {
+        fn priority(&self) -> HookPriority {
+            self.priority
+        }
+
+        fn is_observer(&self) -> bool {
+            self.observer
+        }
+
+        async fn execute(
+            &self,
+            _input: &ToolExecuteBeforeInput,
+            output: &mut ToolExecuteBeforeOutput,
+        ) -> Result<()> {
+            // Observers only get past the barrier if they run concurrently
+            if let Some(barrier) = &self.barrier {
+                barrier.wait().await;
+            }
+            self.log.lock().unwrap().push(self.name);
+            if let Some(obj) = output.args.as_object_mut() {
+                obj.insert(self.name.to_string(), serde_json::json!(true));
+            }
+            if self.fail {
+                return Err(crate::PluginError::hook_error(self.name, "observer failed"));
+            }
+            if self.observer {
+                output.result = HookResult::Abort {
+                    reason: "observers cannot abort".to_string(),
+                };
+            }
+            Ok(())
+        }
+    }
+
+    fn recording_hook(
+        name: &'static str,
+        observer: bool,
+        priority: HookPriority,
+        log: &Arc<Mutex<Vec<&'static str>>>,
+        barrier: Option<&Arc<tokio::sync::Barrier>>,
+    ) -> Arc<RecordingToolHook> {
+        Arc::new(RecordingToolHook {
+            name,
+            observer,
+            priority,
+            log: log.clone(),
+            barrier: barrier.cloned(),
+            fail: false,
+        })
+    }
+
+    fn read_input() -> ToolExecuteBeforeInput {
+        ToolExecuteBeforeInput {
+            tool: "read".to_string(),
+            session_id: "session-1".to_string(),
+            call_id: "call-1".to_string(),
+            args: serde_json::json!({}),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_observers_run_before_mutating_hooks() {
+        let registry = Arc::new(HookRegistry::new());
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let barrier = Arc::new(tokio::sync::Barrier::new(2));
+        // The mutating hook has the highest priority but still runs last
+        registry
+            .register_tool_execute_before(
+                "mutator",
+                recording_hook("mutator", false, HookPriority::PLUGIN_HIGH, &log, None),
+            )
+            .await;
+        for name in ["metrics", "logger"] {
+            registry
+                .register_tool_execute_before(
+                    name,
+                    recording_hook(name, true, HookPriority::NORMAL, &log, Some(&barrier)),
+                )
+                .await;
+        }
+
+        let dispatcher = HookDispatcher::new(registry).with_concurrent_observers(true);
+        let output = tokio::time::timeout(
+            Duration::from_secs(5),
+            dispatcher.trigger_tool_execute_before(read_input()),
+        )
+        .await
+        .expect("observers should run concurrently")
+        .unwrap();
+
+        let log = log.lock().unwrap().clone();
+        assert_eq!(log.len(), 3);
+        assert_eq!(log[2], "mutator");
+        assert!(log[..2].contains(&"metrics") && log[..2].contains(&"logger"));
+
+        assert_eq!(output.args, serde_json::json!({ "mutator": true }));
+        assert!(matches!(output.result, HookResult::Continue));
+        assert_eq!(dispatcher.last_dispatch_stats().hooks_invoked, 3);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_observer_error_is_returned() {
+        let registry = Arc::new(HookRegistry::new());
+        let log = Arc::new(Mutex::new(Vec::new()));
+        registry
+            .register_tool_execute_before(
+                "broken",
+                Arc::new(RecordingToolHook {
+                    name: "broken",
+                    observer: true,
+                    priority: HookPriority::NORMAL,
+                    log: log.clone(),
+                    barrier: None,
+                    fail: true,
+                }),
+            )
+            .await;
+        registry
+            .register_tool_execute_before(
+                "mutator",
+                recording_hook("mutator", false, HookPriority::NORMAL, &log, None),
+            )
+            .await;
+
+        let dispatcher = HookDispatcher::new(registry).with_concurrent_observers(true);
+        assert!(
+            dispatcher
+                .trigger_tool_execute_before(read_input())
+                .await
+                .is_err()
+        );
+        assert_eq!(*log.lock().unwrap(), vec!["broken"]);
+    }
+
+    #[tokio::test]
+    async fn test_observers_run_in_order_without_concurrent_mode() {
+        let registry = Arc::new(HookRegistry::new());
+        let log = Arc::new(Mutex::new(Vec::new()));
+        registry
+            .register_tool_execute_before(
+                "logger",
+                recording_hook("logger", true, HookPriority::PLUGIN_HIGH, &log, None),
+            )
+            .await;
+        registry
+            .register_tool_execute_before(
+                "mutator",
+                recording_hook("mutator", false, HookPriority::NORMAL, &log, None),
+            )
+            .await;
+
+        let dispatcher = HookDispatcher::new(registry);
+        let output = dispatcher
+            .trigger_tool_execute_before(read_input())
+            .await
+            .unwrap();
+
+        // Without the mode, an observer is an ordinary hook: its Abort stops the chain
+        assert_eq!(*log.lock().unwrap(), vec!["logger"]);
+        assert!(matches!(output.result, HookResult::Abort { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_last_dispatch_stats() {
+        let registry = Arc::new(HookRegistry::new());
+        for (plugin, key) in [("plugin-a", "a"), ("plugin-b", "b")] {
+            registry
+                .register_tool_execute_before(
+                    plugin,
+                    Arc::new(SleepyToolHook {
+                        delay: Duration::from_millis(5),
+                        key,
+                        priority: HookPriority::NORMAL,
+                    }),
+                )
+                .await;
+        }
+
+        let dispatcher = HookDispatcher::new(registry);
+        assert_eq!(dispatcher.last_dispatch_stats(), DispatchStats::default());
+
+        let input = ToolExecuteBeforeInput {
+            tool: "read".to_string(),
+            session_id: "session-1".to_string(),
+            call_id: "call-1".to_string(),
+            args: serde_json::json!({}),
+        };
+        dispatcher.trigger_tool_execute_before(input).await.unwrap();
+
+        let stats = dispatcher.last_dispatch_stats();
+        assert_eq!(stats.hook, "tool.execute.before");
+        assert_eq!(stats.hooks_invoked, 2);
+        assert!(stats.max_duration >= Duration::from_millis(5));
+        assert!(stats.total_duration >= stats.max_duration);
+
+        // A trigger with no registered hooks resets the stats
+        dispatcher
+            .trigger_session_start(session_start_input())
+            .await
+            .unwrap();
+        let stats = dispatcher.last_dispatch_stats();
+        assert_eq!(stats.hook, "session.start");
+        assert_eq!(stats.hooks_invoked, 0);
+    }
+
+    #[tokio::test]
+    async fn test_session_end_runs_registered_hooks() {
+        let registry = Arc::new(HookRegistry::new());
+        registry
+            .register_session_end("test-plugin", Arc::new(SummaryHook))
+            .await;
+
+        let dispatcher = HookDispatcher::new(registry);
+        let output = dispatcher
+            .trigger_session_end(SessionEndInput {
+                session_id: "session-1".to_string(),
+                duration_secs: 60,
+                total_messages: 4,
+                total_tokens: None,
+                saved: true,
+            })
+            .await
+            .unwrap();
+
+        assert!(output.generate_summary);
+    }
 
     #[test]
     fn test_pattern_matching() {
@@ -151,4 +983,32 @@ mod tests {
         assert!(HookDispatcher::matches_pattern("async_read", "*read"));
         assert!(!HookDispatcher::matches_pattern("write", "read"));
     }
+
+    #[test]
+    fn test_pattern_matching_multiple_wildcards() {
+        assert!(HookDispatcher::matches_pattern("read_file", "*file*"));
+        assert!(HookDispatcher::matches_pattern("file", "*file*"));
+        assert!(HookDispatcher::matches_pattern("write_file_sync", "*file*"));
+        assert!(!HookDispatcher::matches_pattern("read", "*file*"));
+
+        assert!(HookDispatcher::matches_pattern(
+            "fs.local.read",
+            "fs.*.read"
+        ));
+        assert!(HookDispatcher::matches_pattern("fs..read", "fs.*.read"));
+        assert!(!HookDispatcher::matches_pattern("fs.read", "fs.*.read"));
+        assert!(!HookDispatcher::matches_pattern(
+            "fs.local.write",
+            "fs.*.read"
+        ));
+
+        assert!(HookDispatcher::matches_pattern("read_file", "read_*"));
+        assert!(HookDispatcher::matches_pattern("read_", "read_*"));
+        assert!(!HookDispatcher::matches_pattern("read", "read_*"));
+
+        // Prefix and suffix may not overlap
+        assert!(!HookDispatcher::matches_pattern("ab", "ab*b"));
+        assert!(HookDispatcher::matches_pattern("a-b-c", "a*b*c"));
+        assert!(HookDispatcher::matches_pattern("anything", "**"));
+    }
 }
@@ -120,6 +120,64 @@ pub struct FileOperationAfterInput {
     pub error: Option<String>,
 }
 
+/// Payload handed to WASM `file_operation_after` handlers through the
+/// `get_hook_payload` host function.
+///
+/// The JSON schema is stable: fields are only ever added, never renamed or
+/// removed.
+///
+/// ```json
+/// {
+///   "operation": "write",
+///   "path": "src/main.rs",
+///   "dest_path": null,
+///   "success": true,
+///   "lines_added": 12,
+///   "lines_removed": 3
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileOperationPayload {
+    /// Operation type
+    pub operation: FileOperation,
+    /// Path
+    pub path: PathBuf,
+    /// Destination path (for rename/move/copy)
+    #[serde(default)]
+    pub dest_path: Option<PathBuf>,
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Lines added by the operation
+    #[serde(default)]
+    pub lines_added: u64,
+    /// Lines removed by the operation
+    #[serde(default)]
+    pub lines_removed: u64,
+}
+
+impl FileOperationPayload {
+    /// Build the payload for a completed operation and its line counts.
+    pub fn from_input(
+        input: &FileOperationAfterInput,
+        lines_added: u64,
+        lines_removed: u64,
+    ) -> Self {
+        Self {
+            operation: input.operation,
+            path: input.path.clone(),
+            dest_path: input.dest_path.clone(),
+            success: input.success,
+            lines_added,
+            lines_removed,
+        }
+    }
+
+    /// Serialize the payload as passed to the plugin.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
 /// Output for file.operation.after hook (mutable).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileOperationAfterOutput {
@@ -63,7 +63,8 @@ pub use ai_response_hooks::{
 mod file_hooks;
 pub use file_hooks::{
     FileOperation, FileOperationAfterHook, FileOperationAfterInput, FileOperationAfterOutput,
-    FileOperationBeforeHook, FileOperationBeforeInput, FileOperationBeforeOutput, FilePostAction,
+    FileOperationBeforeHook, FileOperationBeforeInput, FileOperationBeforeOutput,
+    FileOperationPayload, FilePostAction,
 };
 
 // Command execution hooks
@@ -199,7 +200,7 @@ pub use registry::HookRegistry;
 
 // Hook dispatcher
 mod dispatcher;
-pub use dispatcher::HookDispatcher;
+pub use dispatcher::{DispatchStats, HookDispatcher};
 
 #[cfg(test)]
 mod tests {
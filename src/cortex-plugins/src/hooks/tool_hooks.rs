@@ -56,6 +56,15 @@ pub trait ToolExecuteBeforeHook: Send + Sync {
         None
     }
 
+    /// Whether this hook only observes the call (logging, metrics).
+    ///
+    /// With concurrent observers enabled on the dispatcher, observer hooks
+    /// run concurrently on a copy of the output and their changes are
+    /// discarded; only their errors are reported.
+    fn is_observer(&self) -> bool {
+        false
+    }
+
     /// Execute the hook.
     async fn execute(
         &self,
@@ -9,14 +9,56 @@
 //! We avoid using `tokio::runtime::Handle::block_on()` to prevent potential deadlocks
 //! when the tokio runtime is already blocked on the WASM call. Instead, we use
 //! `std::sync::Mutex` for state that needs synchronous access from host functions.
+//!
+//! # Cancellation
+//!
+//! Each [`PluginHostState`] carries a [`CancellationToken`]. Host functions that
+//! may block for a long time must check it periodically (see
+//! [`PluginHostState::run_cancellable`]) and return [`HostError::Cancelled`]
+//! promptly once the engine cancels the turn.
+//!
+//! # Returning Data to Plugins
+//!
+//! Host functions that hand variable-sized data back to a plugin use a
+//! two-phase protocol so the plugin never has to guess a buffer size:
+//!
+//! 1. `<name>_len() -> i64` returns the number of bytes the data needs.
+//! 2. `<name>_read(ptr, len) -> i64` copies the data into the plugin buffer at
+//!    `ptr` and returns the number of bytes written.
+//!
+//! If `len` is smaller than the data, `_read` writes nothing and returns
+//! [`HostError::MemoryOutOfBounds`]. New host functions returning data should
+//! follow this pattern via `write_buffer_response`; `get_context_len` /
+//! `get_context_read` is the reference implementation.
+//!
+//! `read_context(ptr, len) -> i64` instead sizes in a single function: it
+//! writes at most `len` bytes and always returns the full length. Calling it
+//! with `len == 0` probes the size without writing; calling it again with a
+//! buffer at least that large fills it and returns the bytes written.
+//!
+//! # Capabilities
+//!
+//! Host functions with side effects outside the plugin (UI, events, files,
+//! network) require a [`HostCapability`] derived from the plugin manifest and
+//! return [`HostError::NotSupported`] without it. Logging, context, the
+//! current hook payload, skill requests, and the plugin's own state and
+//! storage are always available.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use cortex_prompt_harness::prompts::is_builtin_skill;
+use sha2::{Digest, Sha256};
+use tokio_util::sync::CancellationToken;
 use wasmtime::{Caller, Engine, Linker};
 
 use crate::Result;
+
 use crate::api::PluginContext;
 use crate::hooks::UiRegion;
+use crate::manifest::{PluginCapability, PluginManifest, PluginPermission};
 
 /// Error codes returned by host functions.
 #[repr(i32)]
@@ -28,6 +70,9 @@ pub enum HostError {
     InvalidArgument = -3,
     InternalError = -4,
     NotSupported = -5,
+    Cancelled = -6,
+    NotFound = -7,
+    RateLimited = -8,
 }
 
 impl From<HostError> for i32 {
@@ -36,6 +81,148 @@ impl From<HostError> for i32 {
     }
 }
 
+/// Host features a plugin must be granted before it can use them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HostCapability {
+    /// `register_widget` and `widget_append`. Granted by the `hooks` capability.
+    RegisterWidgets,
+    /// `register_keybinding`. Granted by the `commands` capability.
+    RegisterKeybindings,
+    /// `register_command`. Granted by the `commands` capability.
+    RegisterCommands,
+    /// `show_toast`. Granted by the `notifications` permission.
+    ShowToast,
+    /// `emit_event`. Granted by the `events` capability.
+    EmitEvents,
+    /// `list_dir` and `report_diagnostic`. Granted by the `filesystem`
+    /// capability or a `read_file` permission.
+    ReadFiles,
+    /// `http_fetch` and `http_get`. Granted by the `network` capability or permission.
+    Network,
+}
+
+impl HostCapability {
+    /// Capabilities implied by a manifest's capabilities and permissions.
+    pub fn from_manifest(manifest: &PluginManifest) -> HashSet<Self> {
+        let mut caps = HashSet::new();
+        for capability in &manifest.capabilities {
+            match capability {
+                PluginCapability::Hooks => {
+                    caps.insert(Self::RegisterWidgets);
+                }
+                PluginCapability::Commands => {
+                    caps.insert(Self::RegisterKeybindings);
+                    caps.insert(Self::RegisterCommands);
+                }
+                PluginCapability::Events => {
+                    caps.insert(Self::EmitEvents);
+                }
+                PluginCapability::FileSystem => {
+                    caps.insert(Self::ReadFiles);
+                }
+                PluginCapability::Network => {
+                    caps.insert(Self::Network);
+                }
+                _ => {}
+            }
+        }
+        for permission in &manifest.permissions {
+            match permission {
+                PluginPermission::Notifications => {
+                    caps.insert(Self::ShowToast);
+                }
+                PluginPermission::ReadFile { .. } => {
+                    caps.insert(Self::ReadFiles);
+                }
+                PluginPermission::Network { .. } => {
+                    caps.insert(Self::Network);
+                }
+                _ => {}
+            }
+        }
+        caps
+    }
+}
+
+/// Maximum number of entries returned by a single `list_dir` call.
+pub const MAX_LIST_DIR_ENTRIES: usize = 1000;
+
+/// Default number of lines kept in a streaming widget buffer.
+pub const MAX_WIDGET_BUFFER_LINES: usize = 500;
+
+/// Maximum size of a persisted plugin state blob, excluding the version header.
+pub const MAX_PLUGIN_STATE_BYTES: usize = 1024 * 1024;
+
+/// Default maximum size of an `http_fetch` response body.
+pub const MAX_HTTP_RESPONSE_BYTES: usize = 1024 * 1024;
+
+/// Default timeout for a single `http_fetch` request.
+pub const HTTP_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default number of events or toasts kept queued; older entries are evicted.
+pub const MAX_QUEUED_NOTIFICATIONS: usize = 1000;
+
+/// Shortest toast duration a plugin may request; shorter values are raised to this.
+pub const MIN_TOAST_DURATION_MS: u32 = 500;
+
+/// Longest toast duration a plugin may request; longer values are lowered to this.
+pub const MAX_TOAST_DURATION_MS: u32 = 60_000;
+
+/// Default maximum size of an `emit_event` data payload.
+pub const MAX_EVENT_PAYLOAD_BYTES: usize = 256 * 1024;
+
+/// Event name prefix reserved for events emitted by Cortex itself.
+pub const RESERVED_EVENT_PREFIX: &str = "cortex.";
+
+/// Default number of `emit_event`/`show_toast` calls a plugin may make in a burst.
+pub const DEFAULT_RATE_LIMIT_BURST: u32 = 100;
+
+/// Default sustained rate of `emit_event`/`show_toast` calls per second.
+pub const DEFAULT_RATE_LIMIT_PER_SEC: u32 = 50;
+
+/// Token bucket limiting how often a plugin may call flooding-prone host functions.
+///
+/// The bucket starts full with `burst` tokens and refills at `per_second`
+/// tokens per second. Each call takes one token.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    burst: f64,
+    per_second: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(burst: u32, per_second: u32) -> Self {
+        Self {
+            burst: burst as f64,
+            per_second: per_second as f64,
+            tokens: burst as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Take a token, returning `false` if the bucket is empty.
+    pub fn try_acquire(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_second).min(self.burst);
+        self.last_refill = now;
+
+        if self.tokens < 1.0 {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_RATE_LIMIT_BURST, DEFAULT_RATE_LIMIT_PER_SEC)
+    }
+}
+
 /// Log levels matching the SDK's expected values.
 #[repr(i32)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -82,6 +269,9 @@ impl ToastLevel {
     }
 }
 
+/// Streaming widget line buffers keyed by region and widget type.
+pub type WidgetBuffers = HashMap<(UiRegion, String), VecDeque<String>>;
+
 /// State shared between the host and WASM plugins.
 ///
 /// Uses `std::sync::Mutex` instead of `tokio::sync::RwLock` to allow synchronous
@@ -94,12 +284,53 @@ pub struct PluginHostState {
     pub context: PluginContext,
     /// Registered widgets by UI region. Uses sync Mutex for safe access from WASM host functions.
     pub widgets: Arc<Mutex<HashMap<UiRegion, Vec<String>>>>,
+    /// Streaming widget line buffers keyed by region and widget type. Oldest
+    /// lines are dropped once `widget_buffer_cap` is reached.
+    pub widget_buffers: Arc<Mutex<WidgetBuffers>>,
+    /// Maximum lines kept per streaming widget buffer.
+    pub widget_buffer_cap: usize,
     /// Registered keybindings (key -> action). Uses sync Mutex for safe access from WASM host functions.
     pub keybindings: Arc<Mutex<HashMap<String, String>>>,
+    /// Registered slash commands as (name, description), in registration order.
+    pub commands: Arc<Mutex<Vec<(String, String)>>>,
     /// Emitted events queue. Uses sync Mutex for safe access from WASM host functions.
-    pub events: Arc<Mutex<Vec<PluginEvent>>>,
+    pub events: Arc<Mutex<VecDeque<PluginEvent>>>,
     /// Toast notifications queue. Uses sync Mutex for safe access from WASM host functions.
-    pub toasts: Arc<Mutex<Vec<ToastNotification>>>,
+    pub toasts: Arc<Mutex<VecDeque<ToastNotification>>>,
+    /// Maximum entries kept in `events` and `toasts`; the oldest are evicted first.
+    pub queue_cap: usize,
+    /// Prefix emitted event names with `"{plugin_id}."` so plugins cannot collide.
+    pub namespace_events: bool,
+    /// Maximum `emit_event` data payload size; larger payloads are rejected.
+    pub max_event_payload_bytes: usize,
+    /// Rate limiter for `emit_event` and `show_toast`. Shared across host states
+    /// of the same plugin so the budget survives between invocations.
+    pub rate_limiter: Arc<Mutex<RateLimiter>>,
+    /// Reported diagnostics queue. Uses sync Mutex for safe access from WASM host functions.
+    pub diagnostics: Arc<Mutex<Vec<Diagnostic>>>,
+    /// Directory holding persisted plugin state. `save_state`/`load_state`
+    /// return `NotSupported` when unset.
+    pub state_dir: Option<PathBuf>,
+    /// Cancellation token checked by long-running host functions.
+    pub cancellation: CancellationToken,
+    /// Client used by `http_fetch`. `http_fetch` returns `NotSupported` when unset.
+    pub http_client: Option<Arc<dyn HttpClient>>,
+    /// Hosts `http_fetch` may contact; subdomains of a listed host are allowed too.
+    pub allowed_hosts: Vec<String>,
+    /// Maximum `http_fetch` response body size.
+    pub max_http_response_bytes: usize,
+    /// Body of the last `http_fetch` response, read via `http_response_len`/`http_response_read`.
+    pub http_response: Arc<Mutex<Vec<u8>>>,
+    /// Key-value store behind `storage_set`/`storage_get`. Keys are prefixed
+    /// with the length-prefixed plugin id, so one store can be shared by
+    /// several plugins.
+    pub storage: Arc<Mutex<HashMap<String, String>>>,
+    /// Host features this plugin may use. Empty by default.
+    pub capabilities: HashSet<HostCapability>,
+    /// JSON payload of the hook being invoked, read via `get_hook_payload`.
+    pub hook_payload: Option<String>,
+    /// Built-in skills requested via `request_skill`, for the engine to load.
+    pub requested_skills: Arc<Mutex<Vec<String>>>,
 }
 
 impl PluginHostState {
@@ -108,9 +339,563 @@ impl PluginHostState {
             plugin_id: plugin_id.into(),
             context,
             widgets: Arc::new(Mutex::new(HashMap::new())),
+            widget_buffers: Arc::new(Mutex::new(HashMap::new())),
+            widget_buffer_cap: MAX_WIDGET_BUFFER_LINES,
             keybindings: Arc::new(Mutex::new(HashMap::new())),
-            events: Arc::new(Mutex::new(Vec::new())),
-            toasts: Arc::new(Mutex::new(Vec::new())),
+            commands: Arc::new(Mutex::new(Vec::new())),
+            events: Arc::new(Mutex::new(VecDeque::new())),
+            toasts: Arc::new(Mutex::new(VecDeque::new())),
+            queue_cap: MAX_QUEUED_NOTIFICATIONS,
+            namespace_events: false,
+            max_event_payload_bytes: MAX_EVENT_PAYLOAD_BYTES,
+            rate_limiter: Arc::new(Mutex::new(RateLimiter::default())),
+            diagnostics: Arc::new(Mutex::new(Vec::new())),
+            state_dir: None,
+            cancellation: CancellationToken::new(),
+            http_client: None,
+            allowed_hosts: Vec::new(),
+            max_http_response_bytes: MAX_HTTP_RESPONSE_BYTES,
+            http_response: Arc::new(Mutex::new(Vec::new())),
+            storage: Arc::new(Mutex::new(HashMap::new())),
+            capabilities: HashSet::new(),
+            hook_payload: None,
+            requested_skills: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Set the JSON payload of the hook about to be invoked.
+    pub fn with_hook_payload(mut self, payload: impl Into<String>) -> Self {
+        self.hook_payload = Some(payload.into());
+        self
+    }
+
+    /// Grant host capabilities to this plugin.
+    pub fn with_capabilities(mut self, caps: impl IntoIterator<Item = HostCapability>) -> Self {
+        self.capabilities.extend(caps);
+        self
+    }
+
+    /// Whether this plugin has been granted `capability`.
+    pub fn has_capability(&self, capability: HostCapability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+
+    /// Limit the number of lines kept per streaming widget buffer.
+    pub fn with_widget_buffer_cap(mut self, cap: usize) -> Self {
+        self.widget_buffer_cap = cap;
+        self
+    }
+
+    /// Limit the number of queued events and toasts.
+    pub fn with_queue_cap(mut self, cap: usize) -> Self {
+        self.queue_cap = cap;
+        self
+    }
+
+    /// Prefix event names emitted by this plugin with its id.
+    pub fn with_event_namespacing(mut self, enabled: bool) -> Self {
+        self.namespace_events = enabled;
+        self
+    }
+
+    /// Limit the size of `emit_event` data payloads.
+    pub fn with_max_event_payload_bytes(mut self, max: usize) -> Self {
+        self.max_event_payload_bytes = max;
+        self
+    }
+
+    /// Resolve the name an `emit_event` call is stored under.
+    ///
+    /// Names under the reserved `cortex.` prefix are rejected with
+    /// `InvalidArgument`. With namespacing enabled, names not already under
+    /// `"{plugin_id}."` are prefixed with it.
+    pub fn event_name(&self, name: &str) -> std::result::Result<String, HostError> {
+        if name.is_empty() || name.to_ascii_lowercase().starts_with(RESERVED_EVENT_PREFIX) {
+            return Err(HostError::InvalidArgument);
+        }
+        if !self.namespace_events {
+            return Ok(name.to_string());
+        }
+        let namespace = format!("{}.", self.plugin_id);
+        if name.starts_with(&namespace) {
+            Ok(name.to_string())
+        } else {
+            Ok(format!("{namespace}{name}"))
+        }
+    }
+
+    /// Use a shared rate limiter for `emit_event` and `show_toast`.
+    pub fn with_rate_limiter(mut self, limiter: Arc<Mutex<RateLimiter>>) -> Self {
+        self.rate_limiter = limiter;
+        self
+    }
+
+    /// Take a token from the rate limiter, failing with `RateLimited` when empty.
+    pub fn check_rate_limit(&self) -> std::result::Result<(), HostError> {
+        let allowed = match self.rate_limiter.lock() {
+            Ok(mut limiter) => limiter.try_acquire(),
+            Err(e) => {
+                tracing::error!(plugin = %self.plugin_id, error = %e, "Failed to acquire rate limiter lock (poisoned)");
+                return Err(HostError::InternalError);
+            }
+        };
+        if allowed {
+            Ok(())
+        } else {
+            tracing::warn!(plugin = %self.plugin_id, "Host call rate limit exceeded; dropping call");
+            Err(HostError::RateLimited)
+        }
+    }
+
+    /// Persist plugin state under the given directory.
+    pub fn with_state_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.state_dir = Some(dir.into());
+        self
+    }
+
+    /// Enable `http_fetch` through `client` for the given hosts.
+    pub fn with_http_client(
+        mut self,
+        client: Arc<dyn HttpClient>,
+        allowed_hosts: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.http_client = Some(client);
+        self.allowed_hosts = allowed_hosts.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Limit the size of `http_fetch` response bodies.
+    pub fn with_max_http_response_bytes(mut self, max: usize) -> Self {
+        self.max_http_response_bytes = max;
+        self
+    }
+
+    /// Back `storage_set`/`storage_get` with a shared store, so values
+    /// outlive this host state (e.g. across plugin reloads).
+    pub fn with_storage(mut self, storage: Arc<Mutex<HashMap<String, String>>>) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Serialize the plugin context as JSON, as returned by `get_context_read`.
+    pub fn context_json(&self) -> std::result::Result<String, HostError> {
+        serde_json::to_string(&self.context).map_err(|e| {
+            tracing::warn!(plugin = %self.plugin_id, error = %e, "Failed to serialize context");
+            HostError::InternalError
+        })
+    }
+
+    /// Use the given cancellation token (typically a child of the engine's turn token).
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// Check whether the current host call has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// Drive a chunked blocking operation, checking for cancellation between steps.
+    ///
+    /// `step` is called repeatedly until it returns `Some`. The token is checked
+    /// before every step, so an operation broken into small chunks returns
+    /// [`HostError::Cancelled`] shortly after the token is tripped.
+    pub fn run_cancellable<R>(
+        &self,
+        mut step: impl FnMut() -> Option<R>,
+    ) -> std::result::Result<R, HostError> {
+        loop {
+            if self.is_cancelled() {
+                tracing::debug!(plugin = %self.plugin_id, "Host call cancelled");
+                return Err(HostError::Cancelled);
+            }
+            if let Some(result) = step() {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Resolve a plugin-supplied path against the session cwd.
+    ///
+    /// Returns `InvalidArgument` if the path does not exist or escapes the cwd
+    /// (via `..`, an absolute path, or a symlink).
+    pub fn resolve_sandboxed_path(
+        &self,
+        relative: &str,
+    ) -> std::result::Result<PathBuf, HostError> {
+        let root = self
+            .context
+            .cwd
+            .canonicalize()
+            .map_err(|_| HostError::InternalError)?;
+        let resolved = root
+            .join(relative)
+            .canonicalize()
+            .map_err(|_| HostError::InvalidArgument)?;
+
+        if !resolved.starts_with(&root) {
+            tracing::warn!(plugin = %self.plugin_id, path = %relative, "Path escapes plugin sandbox");
+            return Err(HostError::InvalidArgument);
+        }
+        Ok(resolved)
+    }
+
+    /// List the entries of a directory under the session cwd.
+    ///
+    /// Entries are sorted by name, directories carry a trailing `/`, and at most
+    /// [`MAX_LIST_DIR_ENTRIES`] are returned. When `glob` is given, only entry
+    /// names matching it are kept. Reading stops once the cap is exceeded, so
+    /// the second value tells whether entries were left out; a truncated
+    /// listing is the first entries the file system yielded, not the first by
+    /// name.
+    pub fn list_dir(
+        &self,
+        relative: &str,
+        glob: Option<&str>,
+    ) -> std::result::Result<(Vec<String>, bool), HostError> {
+        let dir = self.resolve_sandboxed_path(relative)?;
+        let read_dir = std::fs::read_dir(&dir).map_err(|_| HostError::InvalidArgument)?;
+
+        let mut entries = Vec::new();
+        let mut truncated = false;
+        for entry in read_dir {
+            if self.is_cancelled() {
+                return Err(HostError::Cancelled);
+            }
+            let Ok(entry) = entry else { continue };
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if let Some(pattern) = glob
+                && !cortex_file_search::glob_match(pattern, &name)
+            {
+                continue;
+            }
+            if entries.len() == MAX_LIST_DIR_ENTRIES {
+                truncated = true;
+                break;
+            }
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            entries.push(if is_dir { format!("{}/", name) } else { name });
+        }
+
+        entries.sort();
+        Ok((entries, truncated))
+    }
+
+    /// Append a line to a streaming widget's buffer.
+    ///
+    /// Once the buffer holds `widget_buffer_cap` lines, the oldest line is
+    /// dropped for each new one.
+    pub fn widget_append(
+        &self,
+        region: UiRegion,
+        widget_type: &str,
+        line: impl Into<String>,
+    ) -> std::result::Result<(), HostError> {
+        match self.widget_buffers.lock() {
+            Ok(mut buffers) => {
+                let buffer = buffers
+                    .entry((region, widget_type.to_string()))
+                    .or_default();
+                buffer.push_back(line.into());
+                while buffer.len() > self.widget_buffer_cap {
+                    buffer.pop_front();
+                }
+                Ok(())
+            }
+            Err(e) => {
+                tracing::error!(plugin = %self.plugin_id, error = %e, "Failed to acquire widget buffer lock (poisoned)");
+                Err(HostError::InternalError)
+            }
+        }
+    }
+
+    /// Take all buffered lines for a streaming widget, oldest first.
+    pub fn drain_widget_lines(&self, region: UiRegion, widget_type: &str) -> Vec<String> {
+        match self.widget_buffers.lock() {
+            Ok(mut buffers) => buffers
+                .get_mut(&(region, widget_type.to_string()))
+                .map(|buffer| buffer.drain(..).collect())
+                .unwrap_or_default(),
+            Err(e) => {
+                tracing::error!(plugin = %self.plugin_id, error = %e, "Failed to acquire widget buffer lock (poisoned)");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Path of this plugin's state file.
+    ///
+    /// The id is sanitized for the file system and suffixed with a short hash
+    /// of the raw id, so ids that sanitize alike (`a/b` and `a_b`) still get
+    /// separate files.
+    fn state_file(&self) -> std::result::Result<PathBuf, HostError> {
+        let dir = self.state_dir.as_ref().ok_or(HostError::NotSupported)?;
+        let name: String = self
+            .plugin_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let digest = Sha256::digest(self.plugin_id.as_bytes());
+        Ok(dir.join(format!("{}-{}.state", name, hex::encode(&digest[..4]))))
+    }
+
+    /// Persist a versioned state blob for this plugin.
+    ///
+    /// The blob is written to a temporary file and renamed into place, so a
+    /// crash never leaves a partially written state behind. Blobs larger than
+    /// [`MAX_PLUGIN_STATE_BYTES`] are rejected with `InvalidArgument`.
+    pub fn save_state(&self, version: u32, bytes: &[u8]) -> std::result::Result<(), HostError> {
+        if bytes.len() > MAX_PLUGIN_STATE_BYTES {
+            tracing::warn!(plugin = %self.plugin_id, size = bytes.len(), "Plugin state exceeds size limit");
+            return Err(HostError::InvalidArgument);
+        }
+        let path = self.state_file()?;
+        let tmp_path = path.with_extension("state.tmp");
+
+        let mut contents = Vec::with_capacity(4 + bytes.len());
+        contents.extend_from_slice(&version.to_le_bytes());
+        contents.extend_from_slice(bytes);
+
+        let write = || -> std::io::Result<()> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&tmp_path, &contents)?;
+            std::fs::rename(&tmp_path, &path)
+        };
+        write().map_err(|e| {
+            tracing::error!(plugin = %self.plugin_id, error = %e, "Failed to persist plugin state");
+            let _ = std::fs::remove_file(&tmp_path);
+            HostError::InternalError
+        })
+    }
+
+    /// Load this plugin's persisted state as `(version, bytes)`.
+    ///
+    /// A plugin that has never saved state gets version 0 and an empty blob.
+    pub fn load_state(&self) -> std::result::Result<(u32, Vec<u8>), HostError> {
+        let path = self.state_file()?;
+        let contents = match std::fs::read(&path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((0, Vec::new())),
+            Err(e) => {
+                tracing::error!(plugin = %self.plugin_id, error = %e, "Failed to read plugin state");
+                return Err(HostError::InternalError);
+            }
+        };
+
+        if contents.len() < 4 || contents.len() - 4 > MAX_PLUGIN_STATE_BYTES {
+            tracing::warn!(plugin = %self.plugin_id, "Ignoring malformed plugin state file");
+            return Err(HostError::InternalError);
+        }
+        let (header, payload) = contents.split_at(4);
+        let version = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        Ok((version, payload.to_vec()))
+    }
+
+    /// Check whether `http_fetch` may contact `url`.
+    ///
+    /// Only `http`/`https` URLs whose host is in `allowed_hosts` (or is a
+    /// subdomain of an allowed host) pass. An empty allow-list blocks everything.
+    pub fn is_host_allowed(&self, url: &str) -> bool {
+        let Ok(parsed) = url::Url::parse(url) else {
+            return false;
+        };
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return false;
+        }
+        let Some(host) = parsed.host_str() else {
+            return false;
+        };
+        let host = host.to_ascii_lowercase();
+        self.allowed_hosts.iter().any(|allowed| {
+            let allowed = allowed.to_ascii_lowercase();
+            host == allowed || host.ends_with(&format!(".{}", allowed))
+        })
+    }
+
+    /// Perform an HTTP request on behalf of the plugin.
+    ///
+    /// Returns `NotSupported` if no client is configured or the host is not
+    /// allowed, `InvalidArgument` for an unknown method or a response body
+    /// larger than `max_http_response_bytes`.
+    pub fn http_fetch(
+        &self,
+        url: &str,
+        method: &str,
+        body: &[u8],
+    ) -> std::result::Result<HttpFetchResponse, HostError> {
+        let client = self.http_client.as_ref().ok_or(HostError::NotSupported)?;
+        if !self.is_host_allowed(url) {
+            tracing::warn!(plugin = %self.plugin_id, url, "Blocked http_fetch to host outside allow-list");
+            return Err(HostError::NotSupported);
+        }
+
+        let method = method.to_ascii_uppercase();
+        if !matches!(
+            method.as_str(),
+            "GET" | "POST" | "PUT" | "DELETE" | "PATCH" | "HEAD"
+        ) {
+            return Err(HostError::InvalidArgument);
+        }
+        if self.is_cancelled() {
+            return Err(HostError::Cancelled);
+        }
+
+        let request = HttpFetchRequest {
+            url: url.to_string(),
+            method,
+            body: body.to_vec(),
+            max_response_bytes: self.max_http_response_bytes,
+        };
+        let response = client.fetch(&request)?;
+        if response.body.len() > self.max_http_response_bytes {
+            tracing::warn!(plugin = %self.plugin_id, url, size = response.body.len(), "http_fetch response exceeds size limit");
+            return Err(HostError::InvalidArgument);
+        }
+        Ok(response)
+    }
+
+    /// Key under which this plugin's `key` is kept in `storage`.
+    ///
+    /// The id length comes first so no other `(plugin_id, key)` pair maps to
+    /// the same string, even when either part contains `:`.
+    fn storage_key(&self, key: &str) -> String {
+        format!("{}:{}:{}", self.plugin_id.len(), self.plugin_id, key)
+    }
+
+    /// Store `value` under `key` in this plugin's namespace.
+    ///
+    /// Empty keys are rejected with `InvalidArgument`.
+    pub fn storage_set(
+        &self,
+        key: &str,
+        value: impl Into<String>,
+    ) -> std::result::Result<(), HostError> {
+        if key.is_empty() {
+            return Err(HostError::InvalidArgument);
+        }
+        match self.storage.lock() {
+            Ok(mut storage) => {
+                storage.insert(self.storage_key(key), value.into());
+                Ok(())
+            }
+            Err(e) => {
+                tracing::error!(plugin = %self.plugin_id, error = %e, "Failed to acquire storage lock (poisoned)");
+                Err(HostError::InternalError)
+            }
+        }
+    }
+
+    /// Look up `key` in this plugin's namespace.
+    pub fn storage_get(&self, key: &str) -> std::result::Result<String, HostError> {
+        match self.storage.lock() {
+            Ok(storage) => storage
+                .get(&self.storage_key(key))
+                .cloned()
+                .ok_or(HostError::NotFound),
+            Err(e) => {
+                tracing::error!(plugin = %self.plugin_id, error = %e, "Failed to acquire storage lock (poisoned)");
+                Err(HostError::InternalError)
+            }
+        }
+    }
+
+    /// Record a request to load the built-in skill `name`.
+    ///
+    /// Names are case-insensitive and stored lowercased; repeated requests
+    /// are recorded once. Unknown skills are rejected with `InvalidArgument`.
+    pub fn request_skill(&self, name: &str) -> std::result::Result<(), HostError> {
+        if !is_builtin_skill(name) {
+            return Err(HostError::InvalidArgument);
+        }
+        let name = name.to_ascii_lowercase();
+        match self.requested_skills.lock() {
+            Ok(mut skills) => {
+                if !skills.contains(&name) {
+                    skills.push(name);
+                }
+                Ok(())
+            }
+            Err(e) => {
+                tracing::error!(plugin = %self.plugin_id, error = %e, "Failed to acquire requested skills lock (poisoned)");
+                Err(HostError::InternalError)
+            }
+        }
+    }
+
+    /// Take all queued toast notifications, oldest first, leaving the queue empty.
+    ///
+    /// Returns an empty vec if the lock is poisoned.
+    pub fn drain_toasts(&self) -> Vec<ToastNotification> {
+        match self.toasts.lock() {
+            Ok(mut toasts) => toasts.drain(..).collect(),
+            Err(e) => {
+                tracing::error!(plugin = %self.plugin_id, error = %e, "Failed to acquire toast lock (poisoned)");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Take all queued events, oldest first, leaving the queue empty.
+    ///
+    /// Returns an empty vec if the lock is poisoned.
+    pub fn drain_events(&self) -> Vec<PluginEvent> {
+        match self.events.lock() {
+            Ok(mut events) => events.drain(..).collect(),
+            Err(e) => {
+                tracing::error!(plugin = %self.plugin_id, error = %e, "Failed to acquire event lock (poisoned)");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Take the skills requested so far, in request order.
+    pub fn drain_requested_skills(&self) -> Vec<String> {
+        self.requested_skills
+            .lock()
+            .map(|mut skills| std::mem::take(&mut *skills))
+            .unwrap_or_default()
+    }
+
+    /// Record a diagnostic for a file under the session cwd.
+    ///
+    /// The file is resolved with [`Self::resolve_sandboxed_path`], so it must
+    /// exist inside the cwd.
+    pub fn report_diagnostic(
+        &self,
+        severity: LogLevel,
+        file: &str,
+        line: u32,
+        column: u32,
+        message: impl Into<String>,
+    ) -> std::result::Result<(), HostError> {
+        let file = self.resolve_sandboxed_path(file)?;
+        let diagnostic = Diagnostic {
+            severity,
+            file,
+            line,
+            column,
+            message: message.into(),
+            plugin_id: self.plugin_id.clone(),
+        };
+
+        match self.diagnostics.lock() {
+            Ok(mut d) => {
+                d.push(diagnostic);
+                Ok(())
+            }
+            Err(e) => {
+                tracing::error!(plugin = %self.plugin_id, error = %e, "Failed to acquire diagnostics lock (poisoned)");
+                Err(HostError::InternalError)
+            }
         }
     }
 }
@@ -133,6 +918,137 @@ pub struct ToastNotification {
     pub plugin_id: String,
 }
 
+/// A file diagnostic reported by a plugin, rendered by the UI at a location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: LogLevel,
+    /// Canonical path of the file, inside the session cwd.
+    pub file: PathBuf,
+    pub line: u32,
+    pub column: u32,
+    pub message: String,
+    pub plugin_id: String,
+}
+
+/// An HTTP request issued by a plugin through `http_fetch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpFetchRequest {
+    pub url: String,
+    /// Upper-case HTTP method.
+    pub method: String,
+    /// Request body; empty for no body.
+    pub body: Vec<u8>,
+    /// The client should stop reading and fail with `InvalidArgument` past this size.
+    pub max_response_bytes: usize,
+}
+
+/// Response to an `http_fetch` request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpFetchResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+/// Host-side HTTP client used by `http_fetch`.
+///
+/// Called synchronously from a host function, so implementations must not
+/// block on the engine's tokio runtime (see the module docs).
+pub trait HttpClient: Send + Sync + std::fmt::Debug {
+    fn fetch(
+        &self,
+        request: &HttpFetchRequest,
+    ) -> std::result::Result<HttpFetchResponse, HostError>;
+}
+
+/// [`HttpClient`] backed by reqwest.
+///
+/// Each request runs on its own thread with a current-thread runtime, so
+/// the calling host function never re-enters the engine's runtime. Redirects
+/// are not followed, since they could leave the allow-list.
+#[derive(Debug, Clone)]
+pub struct ReqwestHttpClient {
+    timeout: Duration,
+}
+
+impl Default for ReqwestHttpClient {
+    fn default() -> Self {
+        Self {
+            timeout: HTTP_FETCH_TIMEOUT,
+        }
+    }
+}
+
+impl ReqwestHttpClient {
+    /// Use a custom per-request timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl HttpClient for ReqwestHttpClient {
+    fn fetch(
+        &self,
+        request: &HttpFetchRequest,
+    ) -> std::result::Result<HttpFetchResponse, HostError> {
+        let request = request.clone();
+        let timeout = self.timeout;
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|_| HostError::InternalError)?;
+            runtime.block_on(reqwest_fetch(request, timeout))
+        })
+        .join()
+        .unwrap_or(Err(HostError::InternalError))
+    }
+}
+
+async fn reqwest_fetch(
+    request: HttpFetchRequest,
+    timeout: Duration,
+) -> std::result::Result<HttpFetchResponse, HostError> {
+    let method = reqwest::Method::from_bytes(request.method.as_bytes())
+        .map_err(|_| HostError::InvalidArgument)?;
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|_| HostError::InternalError)?;
+
+    let mut builder = client.request(method, request.url.as_str());
+    if !request.body.is_empty() {
+        builder = builder.body(request.body);
+    }
+    let mut response = builder.send().await.map_err(|e| {
+        tracing::warn!(url = %request.url, error = %e, "http_fetch request failed");
+        HostError::InternalError
+    })?;
+
+    let status = response.status().as_u16();
+    if response
+        .content_length()
+        .is_some_and(|len| len > request.max_response_bytes as u64)
+    {
+        return Err(HostError::InvalidArgument);
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|_| HostError::InternalError)?
+    {
+        if body.len() + chunk.len() > request.max_response_bytes {
+            return Err(HostError::InvalidArgument);
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(HttpFetchResponse { status, body })
+}
+
 /// Trait for types that can provide access to PluginHostState.
 pub trait HasHostState {
     fn host_state(&self) -> &PluginHostState;
@@ -148,11 +1064,11 @@ impl HasHostState for PluginHostState {
     }
 }
 
-fn read_string_from_memory<T>(
+fn read_bytes_from_memory<T>(
     mut caller: Caller<'_, T>,
     ptr: i32,
     len: i32,
-) -> (Caller<'_, T>, std::result::Result<String, HostError>) {
+) -> (Caller<'_, T>, std::result::Result<Vec<u8>, HostError>) {
     if ptr < 0 || len < 0 {
         return (caller, Err(HostError::MemoryOutOfBounds));
     }
@@ -173,41 +1089,197 @@ fn read_string_from_memory<T>(
         return (caller, Err(HostError::MemoryOutOfBounds));
     }
 
-    let result = std::str::from_utf8(&data[ptr_usize..end])
-        .map(|s| s.to_string())
-        .map_err(|_| HostError::InvalidUtf8);
+    let bytes = data[ptr_usize..end].to_vec();
+    (caller, Ok(bytes))
+}
 
+fn read_string_from_memory<T>(
+    caller: Caller<'_, T>,
+    ptr: i32,
+    len: i32,
+) -> (Caller<'_, T>, std::result::Result<String, HostError>) {
+    let (caller, result) = read_bytes_from_memory(caller, ptr, len);
+    let result =
+        result.and_then(|bytes| String::from_utf8(bytes).map_err(|_| HostError::InvalidUtf8));
     (caller, result)
 }
 
-/// Register all host functions with the wasmtime Linker.
-pub fn register_host_functions<T>(linker: &mut Linker<T>) -> Result<()>
-where
-    T: HasHostState + 'static,
-{
-    linker
-        .func_wrap(
-            "cortex",
-            "log",
-            |caller: Caller<'_, T>, level: i32, msg_ptr: i32, msg_len: i32| {
-                log_impl(caller, level, msg_ptr, msg_len)
-            },
-        )
-        .map_err(|e| {
-            crate::PluginError::execution_error("host", format!("Failed to register log: {}", e))
-        })?;
-
-    linker
-        .func_wrap("cortex", "get_context", |caller: Caller<'_, T>| {
-            get_context_impl(caller)
-        })
-        .map_err(|e| {
-            crate::PluginError::execution_error(
-                "host",
+fn write_bytes_to_memory<T>(
+    mut caller: Caller<'_, T>,
+    ptr: i32,
+    len: i32,
+    bytes: &[u8],
+) -> (Caller<'_, T>, std::result::Result<(), HostError>) {
+    if ptr < 0 || len < 0 {
+        return (caller, Err(HostError::MemoryOutOfBounds));
+    }
+    if bytes.len() > len as usize {
+        return (caller, Err(HostError::MemoryOutOfBounds));
+    }
+
+    let memory = match caller.get_export("memory").and_then(|e| e.into_memory()) {
+        Some(m) => m,
+        None => return (caller, Err(HostError::InternalError)),
+    };
+
+    let result = memory
+        .write(&mut caller, ptr as usize, bytes)
+        .map_err(|_| HostError::MemoryOutOfBounds);
+
+    (caller, result)
+}
+
+/// Complete the `_read` half of the two-phase buffer protocol.
+///
+/// Writes `bytes` into the plugin buffer and returns the number of bytes
+/// written, or a negative `HostError` if the buffer is too small.
+fn write_buffer_response<T>(caller: Caller<'_, T>, ptr: i32, len: i32, bytes: &[u8]) -> i64 {
+    let (_, result) = write_bytes_to_memory(caller, ptr, len, bytes);
+    match result {
+        Ok(()) => bytes.len() as i64,
+        Err(e) => e as i64,
+    }
+}
+
+/// Truncating variant of `write_buffer_response` for single-call sizing.
+///
+/// Writes at most `len` bytes of `bytes` and always returns the full length,
+/// so a plugin can probe with `len == 0` and retry with a big-enough buffer.
+/// The return value equals the number of bytes written only when it does not
+/// exceed `len`.
+fn write_truncated_response<T>(caller: Caller<'_, T>, ptr: i32, len: i32, bytes: &[u8]) -> i64 {
+    if ptr < 0 || len < 0 {
+        return HostError::MemoryOutOfBounds as i64;
+    }
+    let prefix = &bytes[..bytes.len().min(len as usize)];
+    if !prefix.is_empty() {
+        let (_, result) = write_bytes_to_memory(caller, ptr, len, prefix);
+        if let Err(e) = result {
+            return e as i64;
+        }
+    }
+    bytes.len() as i64
+}
+
+/// Check that the calling plugin holds `capability`.
+fn require_capability<T: HasHostState>(
+    caller: &Caller<'_, T>,
+    capability: HostCapability,
+) -> std::result::Result<(), HostError> {
+    let host_state = caller.data().host_state();
+    if host_state.has_capability(capability) {
+        return Ok(());
+    }
+    tracing::warn!(plugin = %host_state.plugin_id, capability = ?capability, "Host function denied: missing capability");
+    Err(HostError::NotSupported)
+}
+
+/// Register all host functions with the wasmtime Linker.
+pub fn register_host_functions<T>(linker: &mut Linker<T>) -> Result<()>
+where
+    T: HasHostState + 'static,
+{
+    linker
+        .func_wrap(
+            "cortex",
+            "log",
+            |caller: Caller<'_, T>, level: i32, msg_ptr: i32, msg_len: i32| {
+                log_impl(caller, level, msg_ptr, msg_len)
+            },
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error("host", format!("Failed to register log: {}", e))
+        })?;
+
+    // Legacy name for `get_context_len`, kept for existing plugins.
+    linker
+        .func_wrap("cortex", "get_context", |caller: Caller<'_, T>| {
+            get_context_len_impl(caller)
+        })
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
                 format!("Failed to register get_context: {}", e),
             )
         })?;
 
+    linker
+        .func_wrap("cortex", "get_context_len", |caller: Caller<'_, T>| {
+            get_context_len_impl(caller)
+        })
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register get_context_len: {}", e),
+            )
+        })?;
+
+    linker
+        .func_wrap(
+            "cortex",
+            "get_context_read",
+            |caller: Caller<'_, T>, ptr: i32, len: i32| get_context_read_impl(caller, ptr, len),
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register get_context_read: {}", e),
+            )
+        })?;
+
+    linker
+        .func_wrap("cortex", "get_hook_payload_len", |caller: Caller<'_, T>| {
+            get_hook_payload_len_impl(caller)
+        })
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register get_hook_payload_len: {}", e),
+            )
+        })?;
+
+    linker
+        .func_wrap(
+            "cortex",
+            "get_hook_payload",
+            |caller: Caller<'_, T>, dst_ptr: i32, dst_len: i32| {
+                get_hook_payload_impl(caller, dst_ptr, dst_len)
+            },
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register get_hook_payload: {}", e),
+            )
+        })?;
+
+    // Single-call sizing: writes at most `dst_len` bytes and always returns
+    // the full length, so `dst_len == 0` probes and a short buffer gets a
+    // truncated prefix rather than an error.
+    linker
+        .func_wrap(
+            "cortex",
+            "read_context",
+            |caller: Caller<'_, T>, dst_ptr: i32, dst_len: i32| {
+                read_context_impl(caller, dst_ptr, dst_len)
+            },
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register read_context: {}", e),
+            )
+        })?;
+
+    linker
+        .func_wrap("cortex", "ui_region_count", || UI_REGIONS.len() as i32)
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register ui_region_count: {}", e),
+            )
+        })?;
+
     linker
         .func_wrap(
             "cortex",
@@ -223,6 +1295,26 @@ where
             )
         })?;
 
+    linker
+        .func_wrap(
+            "cortex",
+            "widget_append",
+            |caller: Caller<'_, T>,
+             region: i32,
+             type_ptr: i32,
+             type_len: i32,
+             line_ptr: i32,
+             line_len: i32| {
+                widget_append_impl(caller, region, type_ptr, type_len, line_ptr, line_len)
+            },
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register widget_append: {}", e),
+            )
+        })?;
+
     linker
         .func_wrap(
             "cortex",
@@ -242,6 +1334,21 @@ where
             )
         })?;
 
+    linker
+        .func_wrap(
+            "cortex",
+            "register_command",
+            |caller: Caller<'_, T>, name_ptr: i32, name_len: i32, desc_ptr: i32, desc_len: i32| {
+                register_command_impl(caller, name_ptr, name_len, desc_ptr, desc_len)
+            },
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register register_command: {}", e),
+            )
+        })?;
+
     linker
         .func_wrap(
             "cortex",
@@ -272,90 +1379,363 @@ where
             )
         })?;
 
-    Ok(())
-}
+    linker
+        .func_wrap(
+            "cortex",
+            "report_diagnostic",
+            |caller: Caller<'_, T>,
+             severity: i32,
+             file_ptr: i32,
+             file_len: i32,
+             line: i32,
+             column: i32,
+             msg_ptr: i32,
+             msg_len: i32| {
+                report_diagnostic_impl(
+                    caller, severity, file_ptr, file_len, line, column, msg_ptr, msg_len,
+                )
+            },
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register report_diagnostic: {}", e),
+            )
+        })?;
 
-/// Create a new Linker with all host functions registered.
-pub fn create_linker<T>(engine: &Engine) -> Result<Linker<T>>
-where
-    T: HasHostState + 'static,
-{
-    let mut linker = Linker::new(engine);
-    register_host_functions(&mut linker)?;
-    Ok(linker)
-}
+    linker
+        .func_wrap(
+            "cortex",
+            "save_state",
+            |caller: Caller<'_, T>, version: i32, ptr: i32, len: i32| {
+                save_state_impl(caller, version, ptr, len)
+            },
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register save_state: {}", e),
+            )
+        })?;
 
-fn log_impl<T: HasHostState>(caller: Caller<'_, T>, level: i32, msg_ptr: i32, msg_len: i32) {
-    let plugin_id = caller.data().host_state().plugin_id.clone();
-    let (_, result) = read_string_from_memory(caller, msg_ptr, msg_len);
-    match result {
-        Ok(message) => {
-            let log_level = LogLevel::from_i32(level);
-            match log_level {
-                LogLevel::Trace => tracing::trace!(plugin = %plugin_id, "{}", message),
-                LogLevel::Debug => tracing::debug!(plugin = %plugin_id, "{}", message),
-                LogLevel::Info => tracing::info!(plugin = %plugin_id, "{}", message),
-                LogLevel::Warn => tracing::warn!(plugin = %plugin_id, "{}", message),
-                LogLevel::Error => tracing::error!(plugin = %plugin_id, "{}", message),
-            }
-        }
-        Err(e) => {
-            tracing::warn!(plugin = %plugin_id, error = ?e, "Failed to read log message from WASM memory");
-        }
-    }
-}
+    linker
+        .func_wrap("cortex", "load_state_version", |caller: Caller<'_, T>| {
+            load_state_version_impl(caller)
+        })
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register load_state_version: {}", e),
+            )
+        })?;
 
-fn get_context_impl<T: HasHostState>(caller: Caller<'_, T>) -> i64 {
-    let host_state = caller.data().host_state();
-    match serde_json::to_string(&host_state.context) {
-        Ok(json) => json.len() as i64,
-        Err(e) => {
-            tracing::warn!(plugin = %host_state.plugin_id, error = %e, "Failed to serialize context");
-            HostError::InternalError as i64
-        }
-    }
-}
+    linker
+        .func_wrap("cortex", "load_state_len", |caller: Caller<'_, T>| {
+            load_state_len_impl(caller)
+        })
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register load_state_len: {}", e),
+            )
+        })?;
 
-fn register_widget_impl<T: HasHostState>(
-    caller: Caller<'_, T>,
-    region: i32,
-    type_ptr: i32,
-    type_len: i32,
-) -> i32 {
-    let plugin_id = caller.data().host_state().plugin_id.clone();
-    let widgets = caller.data().host_state().widgets.clone();
+    linker
+        .func_wrap(
+            "cortex",
+            "load_state_read",
+            |caller: Caller<'_, T>, ptr: i32, len: i32| load_state_read_impl(caller, ptr, len),
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register load_state_read: {}", e),
+            )
+        })?;
 
-    let (_, result) = read_string_from_memory(caller, type_ptr, type_len);
-    let widget_type = match result {
-        Ok(s) => s,
-        Err(e) => return e.into(),
-    };
+    linker
+        .func_wrap(
+            "cortex",
+            "http_fetch",
+            |caller: Caller<'_, T>,
+             url_ptr: i32,
+             url_len: i32,
+             method_ptr: i32,
+             method_len: i32,
+             body_ptr: i32,
+             body_len: i32| {
+                http_fetch_impl(
+                    caller, url_ptr, url_len, method_ptr, method_len, body_ptr, body_len,
+                )
+            },
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register http_fetch: {}", e),
+            )
+        })?;
 
-    let ui_region = match region {
-        0 => UiRegion::Header,
-        1 => UiRegion::Footer,
-        2 => UiRegion::SidebarLeft,
-        3 => UiRegion::SidebarRight,
-        4 => UiRegion::MainContent,
-        5 => UiRegion::InputArea,
-        6 => UiRegion::Overlay,
-        7 => UiRegion::StatusBar,
-        8 => UiRegion::ToolOutput,
-        9 => UiRegion::MessageArea,
-        _ => {
-            tracing::warn!(plugin = %plugin_id, region = region, "Invalid UI region");
-            return HostError::InvalidArgument.into();
-        }
-    };
+    linker
+        .func_wrap("cortex", "http_response_len", |caller: Caller<'_, T>| {
+            http_response_len_impl(caller)
+        })
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register http_response_len: {}", e),
+            )
+        })?;
 
-    // Use sync Mutex instead of async RwLock to avoid deadlock risk.
-    // WASM host functions run synchronously, and using block_on() on an async lock
-    // could deadlock if the tokio runtime is already blocked on this WASM call.
-    match widgets.lock() {
-        Ok(mut w) => {
-            w.entry(ui_region).or_default().push(widget_type.clone());
-        }
-        Err(e) => {
+    linker
+        .func_wrap(
+            "cortex",
+            "http_response_read",
+            |caller: Caller<'_, T>, ptr: i32, len: i32| http_response_read_impl(caller, ptr, len),
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register http_response_read: {}", e),
+            )
+        })?;
+
+    linker
+        .func_wrap(
+            "cortex",
+            "http_get",
+            |caller: Caller<'_, T>, url_ptr: i32, url_len: i32, dst_ptr: i32, dst_len: i32| {
+                http_get_impl(caller, url_ptr, url_len, dst_ptr, dst_len)
+            },
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register http_get: {}", e),
+            )
+        })?;
+
+    linker
+        .func_wrap(
+            "cortex",
+            "storage_set",
+            |caller: Caller<'_, T>, key_ptr: i32, key_len: i32, val_ptr: i32, val_len: i32| {
+                storage_set_impl(caller, key_ptr, key_len, val_ptr, val_len)
+            },
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register storage_set: {}", e),
+            )
+        })?;
+
+    linker
+        .func_wrap(
+            "cortex",
+            "storage_get",
+            |caller: Caller<'_, T>, key_ptr: i32, key_len: i32| {
+                storage_get_impl(caller, key_ptr, key_len)
+            },
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register storage_get: {}", e),
+            )
+        })?;
+
+    linker
+        .func_wrap(
+            "cortex",
+            "storage_get_read",
+            |caller: Caller<'_, T>, key_ptr: i32, key_len: i32, ptr: i32, len: i32| {
+                storage_get_read_impl(caller, key_ptr, key_len, ptr, len)
+            },
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register storage_get_read: {}", e),
+            )
+        })?;
+
+    linker
+        .func_wrap(
+            "cortex",
+            "list_dir",
+            |caller: Caller<'_, T>,
+             path_ptr: i32,
+             path_len: i32,
+             glob_ptr: i32,
+             glob_len: i32,
+             dst_ptr: i32,
+             dst_len: i32| {
+                list_dir_impl(
+                    caller, path_ptr, path_len, glob_ptr, glob_len, dst_ptr, dst_len,
+                )
+            },
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register list_dir: {}", e),
+            )
+        })?;
+
+    linker
+        .func_wrap(
+            "cortex",
+            "request_skill",
+            |caller: Caller<'_, T>, name_ptr: i32, name_len: i32| {
+                request_skill_impl(caller, name_ptr, name_len)
+            },
+        )
+        .map_err(|e| {
+            crate::PluginError::execution_error(
+                "host",
+                format!("Failed to register request_skill: {}", e),
+            )
+        })?;
+
+    Ok(())
+}
+
+/// Create a new Linker with all host functions registered.
+pub fn create_linker<T>(engine: &Engine) -> Result<Linker<T>>
+where
+    T: HasHostState + 'static,
+{
+    let mut linker = Linker::new(engine);
+    register_host_functions(&mut linker)?;
+    Ok(linker)
+}
+
+fn log_impl<T: HasHostState>(caller: Caller<'_, T>, level: i32, msg_ptr: i32, msg_len: i32) {
+    let plugin_id = caller.data().host_state().plugin_id.clone();
+    let (_, result) = read_bytes_from_memory(caller, msg_ptr, msg_len);
+    match result.map(decode_log_message) {
+        Ok(message) => {
+            let log_level = LogLevel::from_i32(level);
+            match log_level {
+                LogLevel::Trace => tracing::trace!(plugin = %plugin_id, "{}", message),
+                LogLevel::Debug => tracing::debug!(plugin = %plugin_id, "{}", message),
+                LogLevel::Info => tracing::info!(plugin = %plugin_id, "{}", message),
+                LogLevel::Warn => tracing::warn!(plugin = %plugin_id, "{}", message),
+                LogLevel::Error => tracing::error!(plugin = %plugin_id, "{}", message),
+            }
+        }
+        Err(e) => {
+            tracing::warn!(plugin = %plugin_id, error = ?e, "Failed to read log message from WASM memory");
+        }
+    }
+}
+
+/// Decode a plugin log message, replacing invalid UTF-8 rather than dropping
+/// the message. Lossy messages are prefixed with `[lossy]`.
+fn decode_log_message(bytes: Vec<u8>) -> String {
+    match String::from_utf8(bytes) {
+        Ok(message) => message,
+        Err(e) => format!("[lossy] {}", String::from_utf8_lossy(e.as_bytes())),
+    }
+}
+
+fn get_context_len_impl<T: HasHostState>(caller: Caller<'_, T>) -> i64 {
+    match caller.data().host_state().context_json() {
+        Ok(json) => json.len() as i64,
+        Err(e) => e as i64,
+    }
+}
+
+fn get_context_read_impl<T: HasHostState>(caller: Caller<'_, T>, ptr: i32, len: i32) -> i64 {
+    let json = match caller.data().host_state().context_json() {
+        Ok(json) => json,
+        Err(e) => return e as i64,
+    };
+    write_buffer_response(caller, ptr, len, json.as_bytes())
+}
+
+/// Single-call sizing variant of `get_context_read`.
+///
+/// Call with `dst_len == 0` to get the needed length, then again with a buffer
+/// at least that large to fill it. A smaller buffer receives a truncated
+/// prefix; the full length is returned either way.
+fn read_context_impl<T: HasHostState>(caller: Caller<'_, T>, ptr: i32, len: i32) -> i64 {
+    let json = match caller.data().host_state().context_json() {
+        Ok(json) => json,
+        Err(e) => return e as i64,
+    };
+    write_truncated_response(caller, ptr, len, json.as_bytes())
+}
+
+/// Size of the current hook payload; `NotFound` outside a hook invocation.
+fn get_hook_payload_len_impl<T: HasHostState>(caller: Caller<'_, T>) -> i64 {
+    match &caller.data().host_state().hook_payload {
+        Some(payload) => payload.len() as i64,
+        None => HostError::NotFound as i64,
+    }
+}
+
+fn get_hook_payload_impl<T: HasHostState>(caller: Caller<'_, T>, ptr: i32, len: i32) -> i64 {
+    let payload = match &caller.data().host_state().hook_payload {
+        Some(payload) => payload.clone(),
+        None => return HostError::NotFound as i64,
+    };
+    write_buffer_response(caller, ptr, len, payload.as_bytes())
+}
+
+/// UI regions indexed by the SDK's numeric region code.
+const UI_REGIONS: [UiRegion; 10] = [
+    UiRegion::Header,
+    UiRegion::Footer,
+    UiRegion::SidebarLeft,
+    UiRegion::SidebarRight,
+    UiRegion::MainContent,
+    UiRegion::InputArea,
+    UiRegion::Overlay,
+    UiRegion::StatusBar,
+    UiRegion::ToolOutput,
+    UiRegion::MessageArea,
+];
+
+/// Map the SDK's numeric region code to a [`UiRegion`].
+fn ui_region_from_i32(region: i32) -> Option<UiRegion> {
+    usize::try_from(region)
+        .ok()
+        .and_then(|index| UI_REGIONS.get(index).copied())
+}
+
+fn register_widget_impl<T: HasHostState>(
+    caller: Caller<'_, T>,
+    region: i32,
+    type_ptr: i32,
+    type_len: i32,
+) -> i32 {
+    if let Err(e) = require_capability(&caller, HostCapability::RegisterWidgets) {
+        return e.into();
+    }
+    let plugin_id = caller.data().host_state().plugin_id.clone();
+    let widgets = caller.data().host_state().widgets.clone();
+
+    let (_, result) = read_string_from_memory(caller, type_ptr, type_len);
+    let widget_type = match result {
+        Ok(s) => s,
+        Err(e) => return e.into(),
+    };
+
+    let Some(ui_region) = ui_region_from_i32(region) else {
+        tracing::warn!(plugin = %plugin_id, region = region, "Invalid UI region");
+        return HostError::InvalidArgument.into();
+    };
+
+    // Use sync Mutex instead of async RwLock to avoid deadlock risk.
+    // WASM host functions run synchronously, and using block_on() on an async lock
+    // could deadlock if the tokio runtime is already blocked on this WASM call.
+    match widgets.lock() {
+        Ok(mut w) => {
+            w.entry(ui_region).or_default().push(widget_type.clone());
+        }
+        Err(e) => {
             tracing::error!(plugin = %plugin_id, error = %e, "Failed to acquire widget lock (poisoned)");
             return HostError::InternalError.into();
         }
@@ -364,6 +1744,45 @@ fn register_widget_impl<T: HasHostState>(
     HostError::Success.into()
 }
 
+fn widget_append_impl<T: HasHostState>(
+    caller: Caller<'_, T>,
+    region: i32,
+    type_ptr: i32,
+    type_len: i32,
+    line_ptr: i32,
+    line_len: i32,
+) -> i32 {
+    if let Err(e) = require_capability(&caller, HostCapability::RegisterWidgets) {
+        return e.into();
+    }
+    let Some(ui_region) = ui_region_from_i32(region) else {
+        let plugin_id = &caller.data().host_state().plugin_id;
+        tracing::warn!(plugin = %plugin_id, region = region, "Invalid UI region");
+        return HostError::InvalidArgument.into();
+    };
+
+    let (caller, type_result) = read_string_from_memory(caller, type_ptr, type_len);
+    let widget_type = match type_result {
+        Ok(s) => s,
+        Err(e) => return e.into(),
+    };
+
+    let (caller, line_result) = read_string_from_memory(caller, line_ptr, line_len);
+    let line = match line_result {
+        Ok(s) => s,
+        Err(e) => return e.into(),
+    };
+
+    match caller
+        .data()
+        .host_state()
+        .widget_append(ui_region, &widget_type, line)
+    {
+        Ok(()) => HostError::Success.into(),
+        Err(e) => e.into(),
+    }
+}
+
 fn register_keybinding_impl<T: HasHostState>(
     caller: Caller<'_, T>,
     key_ptr: i32,
@@ -371,6 +1790,9 @@ fn register_keybinding_impl<T: HasHostState>(
     action_ptr: i32,
     action_len: i32,
 ) -> i32 {
+    if let Err(e) = require_capability(&caller, HostCapability::RegisterKeybindings) {
+        return e.into();
+    }
     let plugin_id = caller.data().host_state().plugin_id.clone();
     let keybindings = caller.data().host_state().keybindings.clone();
 
@@ -406,6 +1828,57 @@ fn register_keybinding_impl<T: HasHostState>(
     HostError::Success.into()
 }
 
+/// Whether `name` is a valid slash command name: non-empty `[a-z0-9-]`.
+fn is_valid_command_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+}
+
+fn register_command_impl<T: HasHostState>(
+    caller: Caller<'_, T>,
+    name_ptr: i32,
+    name_len: i32,
+    desc_ptr: i32,
+    desc_len: i32,
+) -> i32 {
+    if let Err(e) = require_capability(&caller, HostCapability::RegisterCommands) {
+        return e.into();
+    }
+    let plugin_id = caller.data().host_state().plugin_id.clone();
+    let commands = caller.data().host_state().commands.clone();
+
+    let (caller, name_result) = read_string_from_memory(caller, name_ptr, name_len);
+    let name = match name_result {
+        Ok(s) => s,
+        Err(e) => return e.into(),
+    };
+
+    let (_, desc_result) = read_string_from_memory(caller, desc_ptr, desc_len);
+    let description = match desc_result {
+        Ok(s) => s,
+        Err(e) => return e.into(),
+    };
+
+    if !is_valid_command_name(&name) {
+        return HostError::InvalidArgument.into();
+    }
+
+    match commands.lock() {
+        Ok(mut commands) => match commands.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => *existing = description,
+            None => commands.push((name.clone(), description)),
+        },
+        Err(e) => {
+            tracing::error!(plugin = %plugin_id, error = %e, "Failed to acquire commands lock (poisoned)");
+            return HostError::InternalError.into();
+        }
+    }
+    tracing::debug!(plugin = %plugin_id, command = %name, "Command registered");
+    HostError::Success.into()
+}
+
 fn show_toast_impl<T: HasHostState>(
     caller: Caller<'_, T>,
     level: i32,
@@ -413,8 +1886,15 @@ fn show_toast_impl<T: HasHostState>(
     msg_len: i32,
     duration_ms: i32,
 ) -> i32 {
+    if let Err(e) = require_capability(&caller, HostCapability::ShowToast) {
+        return e.into();
+    }
+    if let Err(e) = caller.data().host_state().check_rate_limit() {
+        return e.into();
+    }
     let plugin_id = caller.data().host_state().plugin_id.clone();
     let toasts = caller.data().host_state().toasts.clone();
+    let queue_cap = caller.data().host_state().queue_cap;
 
     let (_, result) = read_string_from_memory(caller, msg_ptr, msg_len);
     let message = match result {
@@ -425,11 +1905,16 @@ fn show_toast_impl<T: HasHostState>(
     if duration_ms < 0 {
         return HostError::InvalidArgument.into();
     }
+    let requested = duration_ms as u32;
+    let duration_ms = requested.clamp(MIN_TOAST_DURATION_MS, MAX_TOAST_DURATION_MS);
+    if duration_ms != requested {
+        tracing::debug!(plugin = %plugin_id, requested, clamped = duration_ms, "Toast duration clamped");
+    }
 
     let toast = ToastNotification {
         level: ToastLevel::from_i32(level),
         message: message.clone(),
-        duration_ms: duration_ms as u32,
+        duration_ms,
         plugin_id: plugin_id.clone(),
     };
 
@@ -438,7 +1923,10 @@ fn show_toast_impl<T: HasHostState>(
     // could deadlock if the tokio runtime is already blocked on this WASM call.
     match toasts.lock() {
         Ok(mut t) => {
-            t.push(toast);
+            t.push_back(toast);
+            while t.len() > queue_cap {
+                t.pop_front();
+            }
         }
         Err(e) => {
             tracing::error!(plugin = %plugin_id, error = %e, "Failed to acquire toast lock (poisoned)");
@@ -456,25 +1944,35 @@ fn emit_event_impl<T: HasHostState>(
     data_ptr: i32,
     data_len: i32,
 ) -> i32 {
+    if let Err(e) = require_capability(&caller, HostCapability::EmitEvents) {
+        return e.into();
+    }
+    if let Err(e) = caller.data().host_state().check_rate_limit() {
+        return e.into();
+    }
     let plugin_id = caller.data().host_state().plugin_id.clone();
     let events = caller.data().host_state().events.clone();
+    let queue_cap = caller.data().host_state().queue_cap;
+    let max_payload = caller.data().host_state().max_event_payload_bytes;
 
     let (caller, name_result) = read_string_from_memory(caller, name_ptr, name_len);
-    let name = match name_result {
+    let name = match name_result.and_then(|name| caller.data().host_state().event_name(&name)) {
         Ok(s) => s,
         Err(e) => return e.into(),
     };
 
+    // Together with `queue_cap`, this bounds the memory held by queued events.
+    // Checked before reading so an oversized payload is never copied.
+    if data_len > 0 && data_len as usize > max_payload {
+        tracing::warn!(plugin = %plugin_id, size = data_len, max = max_payload, "Event payload too large; dropping event");
+        return HostError::InvalidArgument.into();
+    }
     let (_, data_result) = read_string_from_memory(caller, data_ptr, data_len);
     let data = match data_result {
         Ok(s) => s,
         Err(e) => return e.into(),
     };
 
-    if name.is_empty() {
-        return HostError::InvalidArgument.into();
-    }
-
     // Validate that data is valid JSON if non-empty.
     // Empty data is allowed and represents "no data" (null/empty event payload).
     // This avoids confusing behavior where `serde_json::from_str("")` would fail,
@@ -495,7 +1993,10 @@ fn emit_event_impl<T: HasHostState>(
     // could deadlock if the tokio runtime is already blocked on this WASM call.
     match events.lock() {
         Ok(mut e) => {
-            e.push(event);
+            e.push_back(event);
+            while e.len() > queue_cap {
+                e.pop_front();
+            }
         }
         Err(e) => {
             tracing::error!(plugin = %plugin_id, error = %e, "Failed to acquire event lock (poisoned)");
@@ -506,32 +2007,399 @@ fn emit_event_impl<T: HasHostState>(
     HostError::Success.into()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn save_state_impl<T: HasHostState>(
+    caller: Caller<'_, T>,
+    version: i32,
+    ptr: i32,
+    len: i32,
+) -> i32 {
+    if version < 0 {
+        return HostError::InvalidArgument.into();
+    }
 
-    #[test]
-    fn test_log_level_from_i32() {
-        assert_eq!(LogLevel::from_i32(0), LogLevel::Trace);
-        assert_eq!(LogLevel::from_i32(1), LogLevel::Debug);
-        assert_eq!(LogLevel::from_i32(2), LogLevel::Info);
-        assert_eq!(LogLevel::from_i32(3), LogLevel::Warn);
-        assert_eq!(LogLevel::from_i32(4), LogLevel::Error);
-        assert_eq!(LogLevel::from_i32(-1), LogLevel::Info);
+    let (caller, result) = read_bytes_from_memory(caller, ptr, len);
+    let bytes = match result {
+        Ok(b) => b,
+        Err(e) => return e.into(),
+    };
+
+    let host_state = caller.data().host_state();
+    match host_state.save_state(version as u32, &bytes) {
+        Ok(()) => {
+            tracing::debug!(plugin = %host_state.plugin_id, version, size = bytes.len(), "Plugin state saved");
+            HostError::Success.into()
+        }
+        Err(e) => e.into(),
     }
+}
 
-    #[test]
-    fn test_toast_level_from_i32() {
-        assert_eq!(ToastLevel::from_i32(0), ToastLevel::Info);
-        assert_eq!(ToastLevel::from_i32(1), ToastLevel::Success);
-        assert_eq!(ToastLevel::from_i32(2), ToastLevel::Warning);
-        assert_eq!(ToastLevel::from_i32(3), ToastLevel::Error);
+fn load_state_version_impl<T: HasHostState>(caller: Caller<'_, T>) -> i64 {
+    match caller.data().host_state().load_state() {
+        Ok((version, _)) => version as i64,
+        Err(e) => e as i64,
     }
+}
 
-    #[test]
-    fn test_host_error_conversion() {
-        assert_eq!(i32::from(HostError::Success), 0);
-        assert_eq!(i32::from(HostError::MemoryOutOfBounds), -1);
+fn load_state_len_impl<T: HasHostState>(caller: Caller<'_, T>) -> i64 {
+    match caller.data().host_state().load_state() {
+        Ok((_, bytes)) => bytes.len() as i64,
+        Err(e) => e as i64,
+    }
+}
+
+fn load_state_read_impl<T: HasHostState>(caller: Caller<'_, T>, ptr: i32, len: i32) -> i64 {
+    let bytes = match caller.data().host_state().load_state() {
+        Ok((_, bytes)) => bytes,
+        Err(e) => return e as i64,
+    };
+    write_buffer_response(caller, ptr, len, &bytes)
+}
+
+/// Record a diagnostic at `file:line:column`.
+///
+/// `severity` uses the same values as `log`. Lines and columns must be
+/// non-negative.
+#[allow(clippy::too_many_arguments)]
+fn report_diagnostic_impl<T: HasHostState>(
+    caller: Caller<'_, T>,
+    severity: i32,
+    file_ptr: i32,
+    file_len: i32,
+    line: i32,
+    column: i32,
+    msg_ptr: i32,
+    msg_len: i32,
+) -> i32 {
+    if let Err(e) = require_capability(&caller, HostCapability::ReadFiles) {
+        return e.into();
+    }
+    if line < 0 || column < 0 {
+        return HostError::InvalidArgument.into();
+    }
+
+    let (caller, file_result) = read_string_from_memory(caller, file_ptr, file_len);
+    let file = match file_result {
+        Ok(s) => s,
+        Err(e) => return e.into(),
+    };
+
+    let (caller, msg_result) = read_string_from_memory(caller, msg_ptr, msg_len);
+    let message = match msg_result {
+        Ok(s) => s,
+        Err(e) => return e.into(),
+    };
+
+    let host_state = caller.data().host_state();
+    match host_state.report_diagnostic(
+        LogLevel::from_i32(severity),
+        &file,
+        line as u32,
+        column as u32,
+        message,
+    ) {
+        Ok(()) => {
+            tracing::debug!(plugin = %host_state.plugin_id, file = %file, line, column, "Diagnostic reported");
+            HostError::Success.into()
+        }
+        Err(e) => e.into(),
+    }
+}
+
+/// List a directory under the session cwd into the plugin buffer.
+///
+/// Entries are newline-joined. An empty glob (`glob_len == 0`) lists every
+/// entry. Returns the number of bytes written, or a negative `HostError`.
+fn list_dir_impl<T: HasHostState>(
+    caller: Caller<'_, T>,
+    path_ptr: i32,
+    path_len: i32,
+    glob_ptr: i32,
+    glob_len: i32,
+    dst_ptr: i32,
+    dst_len: i32,
+) -> i64 {
+    if let Err(e) = require_capability(&caller, HostCapability::ReadFiles) {
+        return e as i64;
+    }
+    let (caller, path_result) = read_string_from_memory(caller, path_ptr, path_len);
+    let path = match path_result {
+        Ok(s) => s,
+        Err(e) => return e as i64,
+    };
+
+    let (caller, glob_result) = read_string_from_memory(caller, glob_ptr, glob_len);
+    let glob = match glob_result {
+        Ok(s) => s,
+        Err(e) => return e as i64,
+    };
+
+    let host_state = caller.data().host_state();
+    let glob = (!glob.is_empty()).then_some(glob.as_str());
+    let listing = match host_state.list_dir(&path, glob) {
+        Ok((entries, truncated)) => {
+            if truncated {
+                tracing::warn!(plugin = %host_state.plugin_id, path = %path, limit = MAX_LIST_DIR_ENTRIES, "list_dir listing truncated");
+            }
+            entries.join("\n")
+        }
+        Err(e) => return e as i64,
+    };
+
+    write_buffer_response(caller, dst_ptr, dst_len, listing.as_bytes())
+}
+
+/// Perform an HTTP request through the host's client.
+///
+/// Returns the response status; the body is then available through
+/// `http_response_len`/`http_response_read`. An empty method means `GET`.
+fn http_fetch_impl<T: HasHostState>(
+    caller: Caller<'_, T>,
+    url_ptr: i32,
+    url_len: i32,
+    method_ptr: i32,
+    method_len: i32,
+    body_ptr: i32,
+    body_len: i32,
+) -> i32 {
+    if let Err(e) = require_capability(&caller, HostCapability::Network) {
+        return e.into();
+    }
+    let (caller, url_result) = read_string_from_memory(caller, url_ptr, url_len);
+    let url = match url_result {
+        Ok(s) => s,
+        Err(e) => return e.into(),
+    };
+
+    let (caller, method_result) = read_string_from_memory(caller, method_ptr, method_len);
+    let method = match method_result {
+        Ok(s) if s.is_empty() => "GET".to_string(),
+        Ok(s) => s,
+        Err(e) => return e.into(),
+    };
+
+    let (caller, body_result) = read_bytes_from_memory(caller, body_ptr, body_len);
+    let body = match body_result {
+        Ok(b) => b,
+        Err(e) => return e.into(),
+    };
+
+    let host_state = caller.data().host_state();
+    let response = match host_state.http_fetch(&url, &method, &body) {
+        Ok(r) => r,
+        Err(e) => return e.into(),
+    };
+
+    match host_state.http_response.lock() {
+        Ok(mut last) => *last = response.body,
+        Err(e) => {
+            tracing::error!(plugin = %host_state.plugin_id, error = %e, "Failed to acquire http_response lock (poisoned)");
+            return HostError::InternalError.into();
+        }
+    }
+    tracing::debug!(plugin = %host_state.plugin_id, url = %url, status = response.status, "http_fetch completed");
+    response.status as i32
+}
+
+/// `GET` `url` and copy the response body into `dst`, returning its length.
+///
+/// Goes through the same allow-list and client as `http_fetch`. The body is
+/// also kept for `http_response_len`/`http_response_read`, so a plugin whose
+/// buffer was too small can still read it without fetching again.
+fn http_get_impl<T: HasHostState>(
+    caller: Caller<'_, T>,
+    url_ptr: i32,
+    url_len: i32,
+    dst_ptr: i32,
+    dst_len: i32,
+) -> i64 {
+    if let Err(e) = require_capability(&caller, HostCapability::Network) {
+        return e as i64;
+    }
+    let (caller, url_result) = read_string_from_memory(caller, url_ptr, url_len);
+    let url = match url_result {
+        Ok(s) => s,
+        Err(e) => return e as i64,
+    };
+
+    let host_state = caller.data().host_state();
+    let response = match host_state.http_fetch(&url, "GET", &[]) {
+        Ok(r) => r,
+        Err(e) => return e as i64,
+    };
+    match host_state.http_response.lock() {
+        Ok(mut last) => last.clone_from(&response.body),
+        Err(e) => {
+            tracing::error!(plugin = %host_state.plugin_id, error = %e, "Failed to acquire http_response lock (poisoned)");
+            return HostError::InternalError as i64;
+        }
+    }
+    tracing::debug!(plugin = %host_state.plugin_id, url = %url, status = response.status, "http_get completed");
+    write_buffer_response(caller, dst_ptr, dst_len, &response.body)
+}
+
+fn http_response_len_impl<T: HasHostState>(caller: Caller<'_, T>) -> i64 {
+    match caller.data().host_state().http_response.lock() {
+        Ok(body) => body.len() as i64,
+        Err(_) => HostError::InternalError as i64,
+    }
+}
+
+fn http_response_read_impl<T: HasHostState>(caller: Caller<'_, T>, ptr: i32, len: i32) -> i64 {
+    let body = match caller.data().host_state().http_response.lock() {
+        Ok(body) => body.clone(),
+        Err(_) => return HostError::InternalError as i64,
+    };
+    write_buffer_response(caller, ptr, len, &body)
+}
+
+fn storage_set_impl<T: HasHostState>(
+    caller: Caller<'_, T>,
+    key_ptr: i32,
+    key_len: i32,
+    val_ptr: i32,
+    val_len: i32,
+) -> i32 {
+    let (caller, key_result) = read_string_from_memory(caller, key_ptr, key_len);
+    let key = match key_result {
+        Ok(s) => s,
+        Err(e) => return e.into(),
+    };
+
+    let (caller, val_result) = read_string_from_memory(caller, val_ptr, val_len);
+    let value = match val_result {
+        Ok(s) => s,
+        Err(e) => return e.into(),
+    };
+
+    let host_state = caller.data().host_state();
+    match host_state.storage_set(&key, value) {
+        Ok(()) => {
+            tracing::debug!(plugin = %host_state.plugin_id, key = %key, "Plugin storage value set");
+            HostError::Success.into()
+        }
+        Err(e) => e.into(),
+    }
+}
+
+/// Returns the byte length of the value stored under the key, or
+/// `NotFound`. The value itself is copied out by `storage_get_read`.
+fn storage_get_impl<T: HasHostState>(caller: Caller<'_, T>, key_ptr: i32, key_len: i32) -> i64 {
+    let (caller, key_result) = read_string_from_memory(caller, key_ptr, key_len);
+    let key = match key_result {
+        Ok(s) => s,
+        Err(e) => return e as i64,
+    };
+
+    match caller.data().host_state().storage_get(&key) {
+        Ok(value) => value.len() as i64,
+        Err(e) => e as i64,
+    }
+}
+
+fn request_skill_impl<T: HasHostState>(caller: Caller<'_, T>, name_ptr: i32, name_len: i32) -> i32 {
+    let (caller, name_result) = read_string_from_memory(caller, name_ptr, name_len);
+    let name = match name_result {
+        Ok(s) => s,
+        Err(e) => return e.into(),
+    };
+
+    let host_state = caller.data().host_state();
+    match host_state.request_skill(&name) {
+        Ok(()) => {
+            tracing::debug!(plugin = %host_state.plugin_id, skill = %name, "Plugin requested skill");
+            HostError::Success.into()
+        }
+        Err(e) => {
+            tracing::warn!(plugin = %host_state.plugin_id, skill = %name, "Plugin requested unknown skill");
+            e.into()
+        }
+    }
+}
+
+fn storage_get_read_impl<T: HasHostState>(
+    caller: Caller<'_, T>,
+    key_ptr: i32,
+    key_len: i32,
+    ptr: i32,
+    len: i32,
+) -> i64 {
+    let (caller, key_result) = read_string_from_memory(caller, key_ptr, key_len);
+    let key = match key_result {
+        Ok(s) => s,
+        Err(e) => return e as i64,
+    };
+
+    let value = match caller.data().host_state().storage_get(&key) {
+        Ok(value) => value,
+        Err(e) => return e as i64,
+    };
+    write_buffer_response(caller, ptr, len, value.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_level_from_i32() {
+        assert_eq!(LogLevel::from_i32(0), LogLevel::Trace);
+        assert_eq!(LogLevel::from_i32(1), LogLevel::Debug);
+        assert_eq!(LogLevel::from_i32(2), LogLevel::Info);
+        assert_eq!(LogLevel::from_i32(3), LogLevel::Warn);
+        assert_eq!(LogLevel::from_i32(4), LogLevel::Error);
+        assert_eq!(LogLevel::from_i32(-1), LogLevel::Info);
+    }
+
+    #[test]
+    fn test_toast_level_from_i32() {
+        assert_eq!(ToastLevel::from_i32(0), ToastLevel::Info);
+        assert_eq!(ToastLevel::from_i32(1), ToastLevel::Success);
+        assert_eq!(ToastLevel::from_i32(2), ToastLevel::Warning);
+        assert_eq!(ToastLevel::from_i32(3), ToastLevel::Error);
+    }
+
+    #[test]
+    fn test_host_error_conversion() {
+        assert_eq!(i32::from(HostError::Success), 0);
+        assert_eq!(i32::from(HostError::MemoryOutOfBounds), -1);
+        assert_eq!(i32::from(HostError::Cancelled), -6);
+        assert_eq!(i32::from(HostError::NotFound), -7);
+        assert_eq!(i32::from(HostError::RateLimited), -8);
+    }
+
+    #[test]
+    fn test_run_cancellable_completes() {
+        let state = PluginHostState::new("test-plugin", PluginContext::new("/tmp"));
+        let mut remaining = 3;
+        let result = state.run_cancellable(|| {
+            remaining -= 1;
+            (remaining == 0).then_some("done")
+        });
+        assert_eq!(result, Ok("done"));
+    }
+
+    #[test]
+    fn test_run_cancellable_returns_cancelled_after_trip() {
+        let token = CancellationToken::new();
+        let state = PluginHostState::new("test-plugin", PluginContext::new("/tmp"))
+            .with_cancellation_token(token.clone());
+
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            token.cancel();
+        });
+
+        let start = std::time::Instant::now();
+        // Simulates a host operation that would otherwise never finish.
+        let result: std::result::Result<(), HostError> = state.run_cancellable(|| {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            None
+        });
+        canceller.join().expect("canceller thread panicked");
+
+        assert_eq!(result, Err(HostError::Cancelled));
+        assert!(start.elapsed() < std::time::Duration::from_secs(2));
+        assert!(state.is_cancelled());
     }
 
     #[test]
@@ -550,6 +2418,1441 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    fn list_dir_fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let src = dir.path().join("src");
+        std::fs::create_dir(&src).unwrap();
+        std::fs::write(src.join("lib.rs"), "").unwrap();
+        std::fs::write(src.join("main.rs"), "").unwrap();
+        std::fs::write(src.join("notes.txt"), "").unwrap();
+        std::fs::create_dir(src.join("nested")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_list_dir_in_cwd() {
+        let dir = list_dir_fixture();
+        let state = PluginHostState::new("test-plugin", PluginContext::new(dir.path()));
+
+        let (entries, truncated) = state.list_dir("src", None).expect("listing should succeed");
+        assert_eq!(entries, vec!["lib.rs", "main.rs", "nested/", "notes.txt"]);
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn test_list_dir_glob_filter() {
+        let dir = list_dir_fixture();
+        let state = PluginHostState::new("test-plugin", PluginContext::new(dir.path()));
+
+        let (entries, _) = state.list_dir("src", Some("*.rs")).unwrap();
+        assert_eq!(entries, vec!["lib.rs", "main.rs"]);
+    }
+
+    #[test]
+    fn test_list_dir_stops_at_entry_cap() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        for i in 0..MAX_LIST_DIR_ENTRIES {
+            std::fs::write(dir.path().join(format!("{:04}.txt", i)), "").unwrap();
+        }
+        let state = PluginHostState::new("test-plugin", PluginContext::new(dir.path()));
+
+        let (entries, truncated) = state.list_dir(".", None).unwrap();
+        assert_eq!(entries.len(), MAX_LIST_DIR_ENTRIES);
+        assert!(!truncated, "exactly the cap is not truncated");
+
+        std::fs::write(dir.path().join("extra.txt"), "").unwrap();
+        let (entries, truncated) = state.list_dir(".", None).unwrap();
+        assert_eq!(entries.len(), MAX_LIST_DIR_ENTRIES);
+        assert!(truncated);
+    }
+
+    #[test]
+    fn test_list_dir_rejects_outside_cwd() {
+        let dir = list_dir_fixture();
+        let cwd = dir.path().join("src");
+        let state = PluginHostState::new("test-plugin", PluginContext::new(&cwd));
+
+        assert_eq!(state.list_dir("..", None), Err(HostError::InvalidArgument));
+        assert_eq!(
+            state.list_dir(dir.path().to_str().unwrap(), None),
+            Err(HostError::InvalidArgument)
+        );
+        assert_eq!(
+            state.list_dir("does-not-exist", None),
+            Err(HostError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_list_dir_host_function_writes_buffer() {
+        let dir = list_dir_fixture();
+        let state = PluginHostState::new("test-plugin", PluginContext::new(dir.path()))
+            .with_capabilities([HostCapability::ReadFiles]);
+
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "list_dir"
+                    (func $list_dir (param i32 i32 i32 i32 i32 i32) (result i64)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "src")
+                (data (i32.const 16) "*.rs")
+                (func (export "run") (result i64)
+                    (call $list_dir
+                        (i32.const 0) (i32.const 3)
+                        (i32.const 16) (i32.const 4)
+                        (i32.const 1024) (i32.const 4096))))"#,
+        )
+        .unwrap();
+        let mut store = wasmtime::Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        let run = instance
+            .get_typed_func::<(), i64>(&mut store, "run")
+            .unwrap();
+
+        let written = run.call(&mut store, ()).unwrap();
+        let expected = "lib.rs\nmain.rs";
+        assert_eq!(written, expected.len() as i64);
+
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        let data = &memory.data(&store)[1024..1024 + expected.len()];
+        assert_eq!(data, expected.as_bytes());
+    }
+
+    #[test]
+    fn test_report_diagnostic_stores_fields() {
+        let dir = list_dir_fixture();
+        let state = PluginHostState::new("linter", PluginContext::new(dir.path()));
+
+        state
+            .report_diagnostic(LogLevel::Warn, "src/lib.rs", 12, 4, "unused import")
+            .unwrap();
+
+        let diagnostics = state.diagnostics.lock().unwrap();
+        assert_eq!(
+            *diagnostics,
+            vec![Diagnostic {
+                severity: LogLevel::Warn,
+                file: dir.path().canonicalize().unwrap().join("src/lib.rs"),
+                line: 12,
+                column: 4,
+                message: "unused import".to_string(),
+                plugin_id: "linter".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_report_diagnostic_rejects_outside_cwd() {
+        let dir = list_dir_fixture();
+        std::fs::write(dir.path().join("outside.rs"), "").unwrap();
+        let state = PluginHostState::new("linter", PluginContext::new(dir.path().join("src")));
+
+        assert_eq!(
+            state.report_diagnostic(LogLevel::Error, "../outside.rs", 1, 1, "escape"),
+            Err(HostError::InvalidArgument)
+        );
+        assert!(state.diagnostics.lock().unwrap().is_empty());
+    }
+
+    /// Serves canned responses and records the URLs it was asked for.
+    #[derive(Debug, Default)]
+    struct MockHttpClient {
+        body: Vec<u8>,
+        requests: Mutex<Vec<String>>,
+    }
+
+    impl HttpClient for MockHttpClient {
+        fn fetch(
+            &self,
+            request: &HttpFetchRequest,
+        ) -> std::result::Result<HttpFetchResponse, HostError> {
+            self.requests.lock().unwrap().push(request.url.clone());
+            Ok(HttpFetchResponse {
+                status: 200,
+                body: self.body.clone(),
+            })
+        }
+    }
+
+    fn http_state(body: &[u8]) -> (PluginHostState, Arc<MockHttpClient>) {
+        let client = Arc::new(MockHttpClient {
+            body: body.to_vec(),
+            ..Default::default()
+        });
+        let state = PluginHostState::new("fetcher", PluginContext::new("/tmp"))
+            .with_http_client(client.clone(), ["api.example.com"])
+            .with_capabilities([HostCapability::Network]);
+        (state, client)
+    }
+
+    #[test]
+    fn test_http_fetch_allowed_host() {
+        let (state, client) = http_state(b"{\"ok\":true}");
+
+        let response = state
+            .http_fetch("https://api.example.com/v1/stats", "get", b"")
+            .unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"{\"ok\":true}");
+
+        // Subdomains of an allowed host are allowed too.
+        assert!(state.is_host_allowed("https://eu.api.example.com/"));
+        assert_eq!(client.requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_http_fetch_blocked_host() {
+        let (state, client) = http_state(b"secret");
+
+        for url in [
+            "https://evil.example.org/",
+            "https://notapi.example.com/",
+            "file:///etc/passwd",
+            "not a url",
+        ] {
+            assert_eq!(
+                state.http_fetch(url, "GET", b""),
+                Err(HostError::NotSupported),
+                "{url} should be blocked"
+            );
+        }
+        assert!(client.requests.lock().unwrap().is_empty());
+
+        let no_client = PluginHostState::new("fetcher", PluginContext::new("/tmp"));
+        assert_eq!(
+            no_client.http_fetch("https://api.example.com/", "GET", b""),
+            Err(HostError::NotSupported)
+        );
+    }
+
+    #[test]
+    fn test_http_fetch_response_size_cap() {
+        let (state, _) = http_state(&[b'x'; 64]);
+        let state = state.with_max_http_response_bytes(16);
+
+        assert_eq!(
+            state.http_fetch("https://api.example.com/big", "GET", b""),
+            Err(HostError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_http_fetch_status_and_body_through_linker() {
+        let (state, _) = http_state(b"hello");
+
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "http_fetch"
+                    (func $fetch (param i32 i32 i32 i32 i32 i32) (result i32)))
+                (import "cortex" "http_response_len" (func $len (result i64)))
+                (import "cortex" "http_response_read" (func $read (param i32 i32) (result i64)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "https://api.example.com/hello")
+                (func (export "fetch") (result i32)
+                    (call $fetch (i32.const 0) (i32.const 29) (i32.const 0) (i32.const 0)
+                        (i32.const 0) (i32.const 0)))
+                (func (export "len") (result i64) (call $len))
+                (func (export "read") (param i32) (result i64)
+                    (call $read (i32.const 1024) (local.get 0))))"#,
+        )
+        .unwrap();
+        let mut store = wasmtime::Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        let fetch = instance
+            .get_typed_func::<(), i32>(&mut store, "fetch")
+            .unwrap();
+        let len = instance
+            .get_typed_func::<(), i64>(&mut store, "len")
+            .unwrap();
+        let read = instance
+            .get_typed_func::<i32, i64>(&mut store, "read")
+            .unwrap();
+
+        assert_eq!(fetch.call(&mut store, ()).unwrap(), 200);
+        assert_eq!(len.call(&mut store, ()).unwrap(), 5);
+        assert_eq!(read.call(&mut store, 5).unwrap(), 5);
+
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        assert_eq!(&memory.data(&store)[1024..1029], b"hello");
+    }
+
+    /// Serve one canned HTTP response on a local port, returning its base URL
+    /// and the number of connections it accepted.
+    fn mock_http_server(body: &'static str) -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = hits.clone();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+            }
+        });
+        (url, hits)
+    }
+
+    /// Call `http_get` through the linker with `url` written at offset 0 and
+    /// a 64-byte destination buffer at 1024.
+    fn http_get_through_linker(state: PluginHostState, url: &str) -> (i64, Vec<u8>) {
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "http_get" (func $get (param i32 i32 i32 i32) (result i64)))
+                (memory (export "memory") 1)
+                (func (export "get") (param i32) (result i64)
+                    (call $get (i32.const 0) (local.get 0) (i32.const 1024) (i32.const 64))))"#,
+        )
+        .unwrap();
+        let mut store = wasmtime::Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        memory.write(&mut store, 0, url.as_bytes()).unwrap();
+        let get = instance
+            .get_typed_func::<i32, i64>(&mut store, "get")
+            .unwrap();
+
+        let result = get.call(&mut store, url.len() as i32).unwrap();
+        let written = memory.data(&store)[1024..1024 + result.max(0) as usize].to_vec();
+        (result, written)
+    }
+
+    #[test]
+    fn test_http_get_allowed_host_against_local_server() {
+        let (base, hits) = mock_http_server("pong");
+        let state = PluginHostState::new("fetcher", PluginContext::new("/tmp"))
+            .with_http_client(Arc::new(ReqwestHttpClient::default()), ["127.0.0.1"])
+            .with_capabilities([HostCapability::Network]);
+
+        let (result, body) = http_get_through_linker(state, &format!("{}/ping", base));
+
+        assert_eq!(result, 4);
+        assert_eq!(body, b"pong");
+        assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_http_get_denied_host_never_connects() {
+        let (base, hits) = mock_http_server("pong");
+        let state = PluginHostState::new("fetcher", PluginContext::new("/tmp"))
+            .with_http_client(Arc::new(ReqwestHttpClient::default()), ["api.example.com"])
+            .with_capabilities([HostCapability::Network]);
+
+        let (result, _) = http_get_through_linker(state, &format!("{}/ping", base));
+
+        assert_eq!(result, HostError::NotSupported as i64);
+        assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    /// Instantiate a module exercising the storage host functions.
+    ///
+    /// Memory holds the key "count" at 0, the value "42" at 16 and invalid
+    /// UTF-8 at 32.
+    fn storage_instance(
+        state: PluginHostState,
+    ) -> (wasmtime::Store<PluginHostState>, wasmtime::Instance) {
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "storage_set" (func $set (param i32 i32 i32 i32) (result i32)))
+                (import "cortex" "storage_get" (func $get (param i32 i32) (result i64)))
+                (import "cortex" "storage_get_read"
+                    (func $read (param i32 i32 i32 i32) (result i64)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "count")
+                (data (i32.const 16) "42")
+                (data (i32.const 32) "\ff\fe")
+                (func (export "set") (result i32)
+                    (call $set (i32.const 0) (i32.const 5) (i32.const 16) (i32.const 2)))
+                (func (export "set_bad_utf8") (result i32)
+                    (call $set (i32.const 0) (i32.const 5) (i32.const 32) (i32.const 2)))
+                (func (export "set_oob") (result i32)
+                    (call $set (i32.const 65535) (i32.const 5) (i32.const 16) (i32.const 2)))
+                (func (export "get") (result i64) (call $get (i32.const 0) (i32.const 5)))
+                (func (export "read") (param i32) (result i64)
+                    (call $read (i32.const 0) (i32.const 5) (i32.const 1024) (local.get 0))))"#,
+        )
+        .unwrap();
+        let mut store = wasmtime::Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        (store, instance)
+    }
+
+    #[test]
+    fn test_storage_set_then_get_through_linker() {
+        let state = PluginHostState::new("code-stats", PluginContext::new("/tmp"));
+        let storage = state.storage.clone();
+        let (mut store, instance) = storage_instance(state);
+        let set = instance
+            .get_typed_func::<(), i32>(&mut store, "set")
+            .unwrap();
+        let get = instance
+            .get_typed_func::<(), i64>(&mut store, "get")
+            .unwrap();
+        let read = instance
+            .get_typed_func::<i32, i64>(&mut store, "read")
+            .unwrap();
+
+        assert_eq!(
+            get.call(&mut store, ()).unwrap(),
+            HostError::NotFound as i64
+        );
+        assert_eq!(set.call(&mut store, ()).unwrap(), HostError::Success as i32);
+        assert_eq!(get.call(&mut store, ()).unwrap(), 2);
+        assert_eq!(
+            read.call(&mut store, 1).unwrap(),
+            HostError::MemoryOutOfBounds as i64
+        );
+        assert_eq!(read.call(&mut store, 2).unwrap(), 2);
+
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        assert_eq!(&memory.data(&store)[1024..1026], b"42");
+        assert_eq!(store.data().storage_get("count"), Ok("42".to_string()));
+        let key = store.data().storage_key("count");
+        assert_eq!(
+            storage.lock().unwrap().get(&key).map(String::as_str),
+            Some("42")
+        );
+    }
+
+    #[test]
+    fn test_storage_set_rejects_bad_input() {
+        let state = PluginHostState::new("code-stats", PluginContext::new("/tmp"));
+        let (mut store, instance) = storage_instance(state);
+        let set_bad_utf8 = instance
+            .get_typed_func::<(), i32>(&mut store, "set_bad_utf8")
+            .unwrap();
+        let set_oob = instance
+            .get_typed_func::<(), i32>(&mut store, "set_oob")
+            .unwrap();
+
+        assert_eq!(
+            set_bad_utf8.call(&mut store, ()).unwrap(),
+            HostError::InvalidUtf8 as i32
+        );
+        assert_eq!(
+            set_oob.call(&mut store, ()).unwrap(),
+            HostError::MemoryOutOfBounds as i32
+        );
+        assert!(store.data().storage.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_storage_is_namespaced_by_plugin() {
+        let shared = Arc::new(Mutex::new(HashMap::new()));
+        let stats = PluginHostState::new("code-stats", PluginContext::new("/tmp"))
+            .with_storage(shared.clone());
+        let other =
+            PluginHostState::new("other", PluginContext::new("/tmp")).with_storage(shared.clone());
+
+        stats.storage_set("count", "42").unwrap();
+        other.storage_set("count", "7").unwrap();
+
+        assert_eq!(stats.storage_get("count"), Ok("42".to_string()));
+        assert_eq!(other.storage_get("count"), Ok("7".to_string()));
+        assert_eq!(stats.storage_set("", "x"), Err(HostError::InvalidArgument));
+
+        // A fresh host state on the same store sees earlier values.
+        let reloaded =
+            PluginHostState::new("code-stats", PluginContext::new("/tmp")).with_storage(shared);
+        assert_eq!(reloaded.storage_get("count"), Ok("42".to_string()));
+        assert_eq!(reloaded.storage_get("missing"), Err(HostError::NotFound));
+    }
+
+    #[test]
+    fn test_storage_keys_do_not_collide_across_separators() {
+        let shared = Arc::new(Mutex::new(HashMap::new()));
+        let first =
+            PluginHostState::new("a:b", PluginContext::new("/tmp")).with_storage(shared.clone());
+        let second =
+            PluginHostState::new("a", PluginContext::new("/tmp")).with_storage(shared.clone());
+
+        first.storage_set("c", "first").unwrap();
+        second.storage_set("b:c", "second").unwrap();
+
+        assert_eq!(first.storage_get("c"), Ok("first".to_string()));
+        assert_eq!(second.storage_get("b:c"), Ok("second".to_string()));
+        assert_eq!(shared.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_plugin_state_survives_reload() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let state = PluginHostState::new("code-stats", PluginContext::new("/tmp"))
+            .with_state_dir(dir.path());
+        assert_eq!(state.load_state(), Ok((0, Vec::new())));
+
+        state.save_state(1, b"{\"lines\":42}").unwrap();
+
+        // A fresh host state, as after a restart, sees the saved blob.
+        let reloaded = PluginHostState::new("code-stats", PluginContext::new("/tmp"))
+            .with_state_dir(dir.path());
+        assert_eq!(reloaded.load_state(), Ok((1, b"{\"lines\":42}".to_vec())));
+        let files: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(files.len(), 1, "no temporary file is left behind");
+    }
+
+    #[test]
+    fn test_plugin_state_ids_that_sanitize_alike_do_not_share_state() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let slashed =
+            PluginHostState::new("a/b", PluginContext::new("/tmp")).with_state_dir(dir.path());
+        let underscored =
+            PluginHostState::new("a_b", PluginContext::new("/tmp")).with_state_dir(dir.path());
+
+        slashed.save_state(1, b"slashed").unwrap();
+        underscored.save_state(2, b"underscored").unwrap();
+
+        assert_eq!(slashed.load_state(), Ok((1, b"slashed".to_vec())));
+        assert_eq!(underscored.load_state(), Ok((2, b"underscored".to_vec())));
+    }
+
+    #[test]
+    fn test_plugin_state_size_cap_and_unconfigured_dir() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let state = PluginHostState::new("code-stats", PluginContext::new("/tmp"))
+            .with_state_dir(dir.path());
+        let too_big = vec![0u8; MAX_PLUGIN_STATE_BYTES + 1];
+        assert_eq!(
+            state.save_state(1, &too_big),
+            Err(HostError::InvalidArgument)
+        );
+
+        let no_dir = PluginHostState::new("code-stats", PluginContext::new("/tmp"));
+        assert_eq!(no_dir.save_state(1, b"x"), Err(HostError::NotSupported));
+        assert_eq!(no_dir.load_state(), Err(HostError::NotSupported));
+    }
+
+    #[test]
+    fn test_get_context_len_then_read() {
+        let state = PluginHostState::new("test-plugin", PluginContext::new("/tmp/project"));
+        let expected = state.context_json().unwrap();
+
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "get_context_len" (func $len (result i64)))
+                (import "cortex" "get_context_read" (func $read (param i32 i32) (result i64)))
+                (memory (export "memory") 1)
+                (func (export "len") (result i64) (call $len))
+                (func (export "read") (param i32) (result i64)
+                    (call $read (i32.const 1024) (local.get 0))))"#,
+        )
+        .unwrap();
+        let mut store = wasmtime::Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        let len = instance
+            .get_typed_func::<(), i64>(&mut store, "len")
+            .unwrap();
+        let read = instance
+            .get_typed_func::<i32, i64>(&mut store, "read")
+            .unwrap();
+
+        let needed = len.call(&mut store, ()).unwrap();
+        assert_eq!(needed, expected.len() as i64);
+
+        // A buffer one byte too small is rejected without writing.
+        let too_small = read.call(&mut store, needed as i32 - 1).unwrap();
+        assert_eq!(too_small, HostError::MemoryOutOfBounds as i64);
+
+        let written = read.call(&mut store, needed as i32).unwrap();
+        assert_eq!(written, needed);
+
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        let data = &memory.data(&store)[1024..1024 + expected.len()];
+        assert_eq!(data, expected.as_bytes());
+    }
+
+    #[test]
+    fn test_read_context_round_trip() {
+        let mut context = PluginContext::new("/tmp/project");
+        context.session_id = Some("session-42".to_string());
+        let state = PluginHostState::new("test-plugin", context.clone());
+
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "get_context" (func $len (result i64)))
+                (import "cortex" "read_context" (func $read (param i32 i32) (result i64)))
+                (memory (export "memory") 1)
+                (func (export "len") (result i64) (call $len))
+                (func (export "read") (param i32 i32) (result i64)
+                    (call $read (local.get 0) (local.get 1))))"#,
+        )
+        .unwrap();
+        let mut store = wasmtime::Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        let len = instance
+            .get_typed_func::<(), i64>(&mut store, "len")
+            .unwrap();
+        let read = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, "read")
+            .unwrap();
+
+        let needed = len.call(&mut store, ()).unwrap() as i32;
+        // The destination must lie inside linear memory.
+        assert_eq!(
+            read.call(&mut store, (65536, needed)).unwrap(),
+            HostError::MemoryOutOfBounds as i64
+        );
+        // A short buffer is not an error: it reports the full length.
+        assert_eq!(read.call(&mut store, (512, 4)).unwrap(), needed as i64);
+        assert_eq!(read.call(&mut store, (512, needed)).unwrap(), needed as i64);
+
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        let bytes = &memory.data(&store)[512..512 + needed as usize];
+        let decoded: PluginContext = serde_json::from_slice(bytes).unwrap();
+        assert_eq!(decoded.session_id.as_deref(), Some("session-42"));
+        assert_eq!(decoded.cwd, context.cwd);
+    }
+
+    fn read_context_instance(
+        context: PluginContext,
+    ) -> (wasmtime::Store<PluginHostState>, wasmtime::Instance) {
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "read_context" (func $read (param i32 i32) (result i64)))
+                (memory (export "memory") 1)
+                (func (export "read") (param i32 i32) (result i64)
+                    (call $read (local.get 0) (local.get 1))))"#,
+        )
+        .unwrap();
+        let mut store = wasmtime::Store::new(&engine, PluginHostState::new("test-plugin", context));
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        (store, instance)
+    }
+
+    #[test]
+    fn test_read_context_size_probe() {
+        let context = PluginContext::new("/tmp/project");
+        let expected = serde_json::to_vec(&context).unwrap();
+        let (mut store, instance) = read_context_instance(context);
+        let read = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, "read")
+            .unwrap();
+
+        assert_eq!(
+            read.call(&mut store, (0, 0)).unwrap(),
+            expected.len() as i64
+        );
+        // A probe writes nothing.
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        assert!(
+            memory.data(&store)[..expected.len()]
+                .iter()
+                .all(|&b| b == 0)
+        );
+
+        // A short buffer gets a truncated prefix but still reports the full length.
+        assert_eq!(
+            read.call(&mut store, (256, 8)).unwrap(),
+            expected.len() as i64
+        );
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        assert_eq!(&memory.data(&store)[256..264], &expected[..8]);
+        assert_eq!(memory.data(&store)[264], 0);
+
+        assert_eq!(
+            read.call(&mut store, (-1, 0)).unwrap(),
+            HostError::MemoryOutOfBounds as i64
+        );
+    }
+
+    #[test]
+    fn test_read_context_fill_after_probe() {
+        let mut context = PluginContext::new("/tmp/project");
+        context.session_id = Some("session-7".to_string());
+        let (mut store, instance) = read_context_instance(context);
+        let read = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, "read")
+            .unwrap();
+
+        let needed = read.call(&mut store, (0, 0)).unwrap() as i32;
+        assert!(needed > 0);
+        assert_eq!(
+            read.call(&mut store, (1024, needed)).unwrap(),
+            needed as i64
+        );
+        // A larger buffer than needed still returns only the bytes written.
+        assert_eq!(
+            read.call(&mut store, (4096, needed + 64)).unwrap(),
+            needed as i64
+        );
+
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        let bytes = &memory.data(&store)[1024..1024 + needed as usize];
+        let decoded: PluginContext = serde_json::from_slice(bytes).unwrap();
+        assert_eq!(decoded.session_id.as_deref(), Some("session-7"));
+    }
+
+    #[test]
+    fn test_decode_log_message() {
+        assert_eq!(decode_log_message(b"hello".to_vec()), "hello");
+        assert_eq!(
+            decode_log_message(vec![b'h', b'i', 0xff, b'!']),
+            "[lossy] hi\u{FFFD}!"
+        );
+    }
+
+    #[test]
+    fn test_log_accepts_invalid_utf8() {
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "log" (func $log (param i32 i32 i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "bad \ff\fe bytes")
+                (func (export "run")
+                    (call $log (i32.const 2) (i32.const 0) (i32.const 12))))"#,
+        )
+        .unwrap();
+        let state = PluginHostState::new("logger", PluginContext::new("/tmp"));
+        let mut store = wasmtime::Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        let run = instance
+            .get_typed_func::<(), ()>(&mut store, "run")
+            .unwrap();
+
+        #[derive(Clone, Default)]
+        struct Captured(Arc<Mutex<Vec<u8>>>);
+        impl std::io::Write for Captured {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        tracing::subscriber::with_default(subscriber, || run.call(&mut store, ()).unwrap());
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("INFO"), "{output}");
+        assert!(
+            output.contains("[lossy] bad \u{fffd}\u{fffd} bytes"),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn test_request_skill_through_linker() {
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "request_skill" (func $request (param i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "Rust")
+                (data (i32.const 16) "git")
+                (data (i32.const 32) "not-a-skill")
+                (func (export "request") (param i32 i32) (result i32)
+                    (call $request (local.get 0) (local.get 1))))"#,
+        )
+        .unwrap();
+        let state = PluginHostState::new("skills", PluginContext::new("/tmp"));
+        let mut store = wasmtime::Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        let request = instance
+            .get_typed_func::<(i32, i32), i32>(&mut store, "request")
+            .unwrap();
+
+        assert_eq!(request.call(&mut store, (0, 4)).unwrap(), 0);
+        assert_eq!(request.call(&mut store, (16, 3)).unwrap(), 0);
+        assert_eq!(request.call(&mut store, (0, 4)).unwrap(), 0);
+        assert_eq!(
+            request.call(&mut store, (32, 11)).unwrap(),
+            HostError::InvalidArgument as i32
+        );
+        assert_eq!(
+            request.call(&mut store, (65_530, 16)).unwrap(),
+            HostError::MemoryOutOfBounds as i32
+        );
+
+        let state = store.data();
+        assert_eq!(state.drain_requested_skills(), vec!["rust", "git"]);
+        assert!(state.drain_requested_skills().is_empty());
+    }
+
+    /// Instantiate a module exporting `len`/`read` wrappers around the hook
+    /// payload host functions.
+    fn hook_payload_instance(
+        state: PluginHostState,
+    ) -> (wasmtime::Store<PluginHostState>, wasmtime::Instance) {
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "get_hook_payload_len" (func $len (result i64)))
+                (import "cortex" "get_hook_payload" (func $read (param i32 i32) (result i64)))
+                (memory (export "memory") 1)
+                (func (export "len") (result i64) (call $len))
+                (func (export "read") (param i32 i32) (result i64)
+                    (call $read (local.get 0) (local.get 1))))"#,
+        )
+        .unwrap();
+        let mut store = wasmtime::Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        (store, instance)
+    }
+
+    #[test]
+    fn test_get_hook_payload_reads_file_operation() {
+        use crate::hooks::{FileOperation, FileOperationAfterInput, FileOperationPayload};
+
+        let input = FileOperationAfterInput {
+            session_id: "s1".to_string(),
+            operation: FileOperation::Write,
+            path: PathBuf::from("src/main.rs"),
+            dest_path: None,
+            success: true,
+            error: None,
+        };
+        let payload = FileOperationPayload::from_input(&input, 12, 3);
+        let state = PluginHostState::new("code-stats", PluginContext::new("/tmp"))
+            .with_hook_payload(payload.to_json());
+        let (mut store, instance) = hook_payload_instance(state);
+        let len = instance
+            .get_typed_func::<(), i64>(&mut store, "len")
+            .unwrap();
+        let read = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, "read")
+            .unwrap();
+
+        let needed = len.call(&mut store, ()).unwrap();
+        assert_eq!(read.call(&mut store, (256, 1024)).unwrap(), needed);
+
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        let bytes = &memory.data(&store)[256..256 + needed as usize];
+        let json: serde_json::Value = serde_json::from_slice(bytes).unwrap();
+        assert_eq!(json["operation"], "write");
+        assert_eq!(json["path"], "src/main.rs");
+        assert_eq!(json["lines_added"], 12);
+        assert_eq!(json["lines_removed"], 3);
+        assert_eq!(json["success"], true);
+        assert_eq!(
+            serde_json::from_slice::<FileOperationPayload>(bytes).unwrap(),
+            payload
+        );
+    }
+
+    #[test]
+    fn test_get_hook_payload_bounds_checks_destination() {
+        let state = PluginHostState::new("code-stats", PluginContext::new("/tmp"))
+            .with_hook_payload(r#"{"operation":"delete","path":"a.txt","success":true}"#);
+        let (mut store, instance) = hook_payload_instance(state);
+        let read = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, "read")
+            .unwrap();
+
+        // Destination runs past the end of linear memory.
+        assert_eq!(
+            read.call(&mut store, (65_520, 4096)).unwrap(),
+            HostError::MemoryOutOfBounds as i64
+        );
+        // Destination too small for the payload.
+        assert_eq!(
+            read.call(&mut store, (0, 4)).unwrap(),
+            HostError::MemoryOutOfBounds as i64
+        );
+    }
+
+    #[test]
+    fn test_get_hook_payload_outside_hook() {
+        let state = PluginHostState::new("code-stats", PluginContext::new("/tmp"));
+        let (mut store, instance) = hook_payload_instance(state);
+        let len = instance
+            .get_typed_func::<(), i64>(&mut store, "len")
+            .unwrap();
+        let read = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, "read")
+            .unwrap();
+
+        assert_eq!(
+            len.call(&mut store, ()).unwrap(),
+            HostError::NotFound as i64
+        );
+        assert_eq!(
+            read.call(&mut store, (0, 1024)).unwrap(),
+            HostError::NotFound as i64
+        );
+    }
+
+    /// Instantiate a module whose `toast` export calls `show_toast`.
+    fn toast_instance(
+        state: PluginHostState,
+    ) -> (
+        wasmtime::Store<PluginHostState>,
+        wasmtime::TypedFunc<(), i32>,
+    ) {
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "show_toast" (func $toast (param i32 i32 i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "hello")
+                (func (export "toast") (result i32)
+                    (call $toast (i32.const 2) (i32.const 0) (i32.const 5) (i32.const 1000))))"#,
+        )
+        .unwrap();
+        let mut store = wasmtime::Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        let toast = instance
+            .get_typed_func::<(), i32>(&mut store, "toast")
+            .unwrap();
+        (store, toast)
+    }
+
+    #[test]
+    fn test_show_toast_requires_capability() {
+        let state = PluginHostState::new("quiet", PluginContext::new("/tmp"));
+        let (mut store, toast) = toast_instance(state);
+
+        assert_eq!(
+            toast.call(&mut store, ()).unwrap(),
+            HostError::NotSupported as i32
+        );
+        assert!(store.data().toasts.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_show_toast_with_capability() {
+        let state = PluginHostState::new("loud", PluginContext::new("/tmp"))
+            .with_capabilities([HostCapability::ShowToast]);
+        let (mut store, toast) = toast_instance(state);
+
+        assert_eq!(
+            toast.call(&mut store, ()).unwrap(),
+            HostError::Success as i32
+        );
+        assert_eq!(store.data().toasts.lock().unwrap()[0].message, "hello");
+        assert_eq!(store.data().toasts.lock().unwrap()[0].duration_ms, 1000);
+    }
+
+    #[test]
+    fn test_show_toast_clamps_duration() {
+        let state = PluginHostState::new("loud", PluginContext::new("/tmp"))
+            .with_capabilities([HostCapability::ShowToast]);
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "show_toast" (func $toast (param i32 i32 i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "hello")
+                (func (export "toast") (param i32) (result i32)
+                    (call $toast (i32.const 0) (i32.const 0) (i32.const 5) (local.get 0))))"#,
+        )
+        .unwrap();
+        let mut store = wasmtime::Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        let toast = instance
+            .get_typed_func::<i32, i32>(&mut store, "toast")
+            .unwrap();
+
+        for duration in [i32::MAX, 0, 1] {
+            assert_eq!(
+                toast.call(&mut store, duration).unwrap(),
+                HostError::Success as i32
+            );
+        }
+        assert_eq!(
+            toast.call(&mut store, -1).unwrap(),
+            HostError::InvalidArgument as i32
+        );
+
+        let durations: Vec<u32> = store
+            .data()
+            .drain_toasts()
+            .into_iter()
+            .map(|t| t.duration_ms)
+            .collect();
+        assert_eq!(
+            durations,
+            vec![
+                MAX_TOAST_DURATION_MS,
+                MIN_TOAST_DURATION_MS,
+                MIN_TOAST_DURATION_MS
+            ]
+        );
+    }
+
+    /// Instantiate a module whose `emit` export emits a `tick` event.
+    fn emit_instance(
+        state: PluginHostState,
+    ) -> (
+        wasmtime::Store<PluginHostState>,
+        wasmtime::TypedFunc<(), i32>,
+    ) {
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "emit_event" (func $emit (param i32 i32 i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "tick")
+                (func (export "emit") (result i32)
+                    (call $emit (i32.const 0) (i32.const 4) (i32.const 0) (i32.const 0))))"#,
+        )
+        .unwrap();
+        let mut store = wasmtime::Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        let emit = instance
+            .get_typed_func::<(), i32>(&mut store, "emit")
+            .unwrap();
+        (store, emit)
+    }
+
+    #[test]
+    fn test_emit_event_flood_is_rate_limited() {
+        let state = PluginHostState::new("flooder", PluginContext::new("/tmp"))
+            .with_capabilities([HostCapability::EmitEvents])
+            .with_rate_limiter(Arc::new(Mutex::new(RateLimiter::new(100, 0))));
+        let (mut store, emit) = emit_instance(state);
+
+        let mut accepted = 0;
+        let mut rejected = 0;
+        for _ in 0..10_000 {
+            match emit.call(&mut store, ()).unwrap() {
+                0 => accepted += 1,
+                code => {
+                    assert_eq!(code, HostError::RateLimited as i32);
+                    rejected += 1;
+                }
+            }
+        }
+
+        assert_eq!(accepted, 100);
+        assert_eq!(rejected, 9_900);
+        assert_eq!(store.data().events.lock().unwrap().len(), 100);
+    }
+
+    #[test]
+    fn test_emit_event_queue_evicts_oldest() {
+        let state = PluginHostState::new("chatty", PluginContext::new("/tmp"))
+            .with_capabilities([HostCapability::EmitEvents])
+            .with_rate_limiter(Arc::new(Mutex::new(RateLimiter::new(u32::MAX, 0))))
+            .with_queue_cap(50);
+        let (mut store, emit) = emit_instance(state);
+
+        for _ in 0..10_000 {
+            assert_eq!(
+                emit.call(&mut store, ()).unwrap(),
+                HostError::Success as i32
+            );
+        }
+
+        assert_eq!(store.data().events.lock().unwrap().len(), 50);
+    }
+
+    #[test]
+    fn test_drain_events_empties_queue() {
+        let state = PluginHostState::new("chatty", PluginContext::new("/tmp"))
+            .with_capabilities([HostCapability::EmitEvents]);
+        let (mut store, emit) = emit_instance(state);
+
+        for _ in 0..3 {
+            assert_eq!(
+                emit.call(&mut store, ()).unwrap(),
+                HostError::Success as i32
+            );
+        }
+
+        let events = store.data().drain_events();
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().all(|event| event.name == "tick"));
+        assert!(store.data().events.lock().unwrap().is_empty());
+        assert!(store.data().drain_events().is_empty());
+    }
+
+    #[test]
+    fn test_emit_event_rejects_oversized_payload() {
+        let state = PluginHostState::new("chatty", PluginContext::new("/tmp"))
+            .with_capabilities([HostCapability::EmitEvents])
+            .with_max_event_payload_bytes(8);
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "emit_event" (func $emit (param i32 i32 i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "tick")
+                (data (i32.const 16) "\"0123456789\"")
+                (func (export "emit") (param i32) (result i32)
+                    (call $emit (i32.const 0) (i32.const 4) (i32.const 16) (local.get 0))))"#,
+        )
+        .unwrap();
+        let mut store = wasmtime::Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        let emit = instance
+            .get_typed_func::<i32, i32>(&mut store, "emit")
+            .unwrap();
+
+        // `"0123456789"` is valid JSON but 12 bytes long.
+        assert_eq!(
+            emit.call(&mut store, 12).unwrap(),
+            HostError::InvalidArgument as i32
+        );
+        assert!(store.data().events.lock().unwrap().is_empty());
+
+        // A payload at the limit is still accepted: `"012345"` is 8 bytes.
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        memory.write(&mut store, 23, b"\"").unwrap();
+        assert_eq!(emit.call(&mut store, 8).unwrap(), HostError::Success as i32);
+        assert_eq!(store.data().drain_events()[0].data, "\"012345\"");
+    }
+
+    #[test]
+    fn test_emit_event_namespacing() {
+        let state = PluginHostState::new("chatty", PluginContext::new("/tmp"))
+            .with_capabilities([HostCapability::EmitEvents])
+            .with_event_namespacing(true);
+        let (mut store, emit) = emit_instance(state);
+
+        assert_eq!(
+            emit.call(&mut store, ()).unwrap(),
+            HostError::Success as i32
+        );
+        assert_eq!(store.data().drain_events()[0].name, "chatty.tick");
+
+        let host_state = store.data();
+        assert_eq!(host_state.event_name("chatty.done").unwrap(), "chatty.done");
+        assert_eq!(
+            host_state.event_name("other.done").unwrap(),
+            "chatty.other.done"
+        );
+
+        let plain = PluginHostState::new("chatty", PluginContext::new("/tmp"));
+        assert_eq!(plain.event_name("session_end").unwrap(), "session_end");
+    }
+
+    #[test]
+    fn test_emit_event_rejects_reserved_prefix() {
+        let state = PluginHostState::new("sneaky", PluginContext::new("/tmp"))
+            .with_capabilities([HostCapability::EmitEvents]);
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "emit_event" (func $emit (param i32 i32 i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "cortex.session_end")
+                (func (export "emit") (result i32)
+                    (call $emit (i32.const 0) (i32.const 18) (i32.const 0) (i32.const 0))))"#,
+        )
+        .unwrap();
+        let mut store = wasmtime::Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        let emit = instance
+            .get_typed_func::<(), i32>(&mut store, "emit")
+            .unwrap();
+
+        assert_eq!(
+            emit.call(&mut store, ()).unwrap(),
+            HostError::InvalidArgument as i32
+        );
+        assert!(store.data().events.lock().unwrap().is_empty());
+
+        let namespaced =
+            PluginHostState::new("sneaky", PluginContext::new("/tmp")).with_event_namespacing(true);
+        assert_eq!(
+            namespaced.event_name("Cortex.shutdown"),
+            Err(HostError::InvalidArgument)
+        );
+        assert_eq!(namespaced.event_name(""), Err(HostError::InvalidArgument));
+    }
+
+    #[test]
+    fn test_drain_toasts_empties_queue() {
+        let state = PluginHostState::new("loud", PluginContext::new("/tmp"))
+            .with_capabilities([HostCapability::ShowToast]);
+        let (mut store, toast) = toast_instance(state);
+
+        for _ in 0..3 {
+            assert_eq!(
+                toast.call(&mut store, ()).unwrap(),
+                HostError::Success as i32
+            );
+        }
+
+        let toasts = store.data().drain_toasts();
+        assert_eq!(toasts.len(), 3);
+        assert!(toasts.iter().all(|toast| toast.message == "hello"));
+        assert!(store.data().toasts.lock().unwrap().is_empty());
+        assert!(store.data().drain_toasts().is_empty());
+    }
+
+    #[test]
+    fn test_show_toast_flood_is_rate_limited() {
+        let limiter = Arc::new(Mutex::new(RateLimiter::new(3, 0)));
+        let state = PluginHostState::new("noisy", PluginContext::new("/tmp"))
+            .with_capabilities([HostCapability::ShowToast])
+            .with_rate_limiter(limiter.clone())
+            .with_queue_cap(2);
+        let (mut store, toast) = toast_instance(state);
+
+        let codes: Vec<i32> = (0..5)
+            .map(|_| toast.call(&mut store, ()).unwrap())
+            .collect();
+        assert_eq!(codes, vec![0, 0, 0, -8, -8]);
+        assert_eq!(store.data().toasts.lock().unwrap().len(), 2);
+
+        // The budget is shared with later host states for the same plugin.
+        let next =
+            PluginHostState::new("noisy", PluginContext::new("/tmp")).with_rate_limiter(limiter);
+        assert_eq!(next.check_rate_limit(), Err(HostError::RateLimited));
+    }
+
+    #[test]
+    fn test_rate_limiter_refills_over_time() {
+        let mut limiter = RateLimiter::new(1, 1000);
+        assert!(limiter.try_acquire());
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(limiter.try_acquire());
+    }
+
+    /// Instantiate a module whose `register(name_ptr, name_len)` export
+    /// registers a command described as "Show stats".
+    fn command_instance(
+        state: PluginHostState,
+    ) -> (
+        wasmtime::Store<PluginHostState>,
+        wasmtime::TypedFunc<(i32, i32), i32>,
+    ) {
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "register_command" (func $register (param i32 i32 i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "Show stats")
+                (data (i32.const 16) "stats")
+                (data (i32.const 32) "git-log2")
+                (data (i32.const 48) "Stats")
+                (data (i32.const 64) "my cmd")
+                (data (i32.const 80) "/stats")
+                (func (export "register") (param i32 i32) (result i32)
+                    (call $register (local.get 0) (local.get 1) (i32.const 0) (i32.const 10))))"#,
+        )
+        .unwrap();
+        let mut store = wasmtime::Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        let register = instance
+            .get_typed_func::<(i32, i32), i32>(&mut store, "register")
+            .unwrap();
+        (store, register)
+    }
+
+    #[test]
+    fn test_register_command_valid_names() {
+        let state = PluginHostState::new("stats", PluginContext::new("/tmp"))
+            .with_capabilities([HostCapability::RegisterCommands]);
+        let (mut store, register) = command_instance(state);
+
+        assert_eq!(register.call(&mut store, (16, 5)).unwrap(), 0);
+        assert_eq!(register.call(&mut store, (32, 8)).unwrap(), 0);
+        assert_eq!(register.call(&mut store, (16, 5)).unwrap(), 0);
+
+        assert_eq!(
+            *store.data().commands.lock().unwrap(),
+            vec![
+                ("stats".to_string(), "Show stats".to_string()),
+                ("git-log2".to_string(), "Show stats".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_register_command_invalid_names() {
+        let state = PluginHostState::new("stats", PluginContext::new("/tmp"))
+            .with_capabilities([HostCapability::RegisterCommands]);
+        let (mut store, register) = command_instance(state);
+
+        for (ptr, len) in [(16, 0), (48, 5), (64, 6), (80, 6)] {
+            assert_eq!(
+                register.call(&mut store, (ptr, len)).unwrap(),
+                HostError::InvalidArgument as i32
+            );
+        }
+        assert!(store.data().commands.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_register_command_requires_capability() {
+        let state = PluginHostState::new("stats", PluginContext::new("/tmp"));
+        let (mut store, register) = command_instance(state);
+
+        assert_eq!(
+            register.call(&mut store, (16, 5)).unwrap(),
+            HostError::NotSupported as i32
+        );
+        assert!(store.data().commands.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_capabilities_from_manifest() {
+        let manifest = PluginManifest::parse(
+            r#"
+capabilities = ["hooks", "events"]
+permissions = ["notifications", { network = { domains = ["api.example.com"] } }]
+
+[plugin]
+id = "caps"
+name = "Caps"
+version = "1.0.0"
+"#,
+        )
+        .unwrap();
+
+        let caps = HostCapability::from_manifest(&manifest);
+        assert_eq!(
+            caps,
+            HashSet::from([
+                HostCapability::RegisterWidgets,
+                HostCapability::EmitEvents,
+                HostCapability::ShowToast,
+                HostCapability::Network,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_ui_region_count_matches_regions() {
+        // Exhaustive, so adding a `UiRegion` variant fails to compile until
+        // `UI_REGIONS` is updated.
+        fn code(region: UiRegion) -> usize {
+            match region {
+                UiRegion::Header => 0,
+                UiRegion::Footer => 1,
+                UiRegion::SidebarLeft => 2,
+                UiRegion::SidebarRight => 3,
+                UiRegion::MainContent => 4,
+                UiRegion::InputArea => 5,
+                UiRegion::Overlay => 6,
+                UiRegion::StatusBar => 7,
+                UiRegion::ToolOutput => 8,
+                UiRegion::MessageArea => 9,
+            }
+        }
+        for (index, region) in UI_REGIONS.iter().enumerate() {
+            assert_eq!(code(*region), index);
+        }
+
+        let engine = Engine::default();
+        let linker = create_linker::<PluginHostState>(&engine).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"(module
+                (import "cortex" "ui_region_count" (func $count (result i32)))
+                (import "cortex" "register_widget" (func $register (param i32 i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "clock")
+                (func (export "count") (result i32) (call $count))
+                (func (export "register") (param i32) (result i32)
+                    (call $register (local.get 0) (i32.const 0) (i32.const 5))))"#,
+        )
+        .unwrap();
+        let state = PluginHostState::new("clock", PluginContext::new("/tmp"))
+            .with_capabilities([HostCapability::RegisterWidgets]);
+        let mut store = wasmtime::Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        let count = instance
+            .get_typed_func::<(), i32>(&mut store, "count")
+            .unwrap();
+        let register = instance
+            .get_typed_func::<i32, i32>(&mut store, "register")
+            .unwrap();
+
+        let count = count.call(&mut store, ()).unwrap();
+        assert_eq!(count, 10);
+        assert_eq!(register.call(&mut store, count - 1).unwrap(), 0);
+        for region in [count, -1] {
+            assert_eq!(
+                register.call(&mut store, region).unwrap(),
+                HostError::InvalidArgument as i32
+            );
+        }
+    }
+
+    #[test]
+    fn test_widget_append_keeps_order() {
+        let state = PluginHostState::new("build-log", PluginContext::new("/tmp"));
+        for line in ["compiling", "linking", "done"] {
+            state
+                .widget_append(UiRegion::ToolOutput, "build_log", line)
+                .unwrap();
+        }
+
+        assert_eq!(
+            state.drain_widget_lines(UiRegion::ToolOutput, "build_log"),
+            vec!["compiling", "linking", "done"]
+        );
+        assert!(
+            state
+                .drain_widget_lines(UiRegion::ToolOutput, "build_log")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_widget_append_respects_cap() {
+        let state =
+            PluginHostState::new("build-log", PluginContext::new("/tmp")).with_widget_buffer_cap(2);
+        for line in ["compiling", "linking", "done"] {
+            state
+                .widget_append(UiRegion::ToolOutput, "build_log", line)
+                .unwrap();
+        }
+
+        assert_eq!(
+            state.drain_widget_lines(UiRegion::ToolOutput, "build_log"),
+            vec!["linking", "done"]
+        );
+    }
+
     #[test]
     fn test_plugin_host_state_widgets() {
         let context = PluginContext::new("/tmp");
@@ -134,6 +134,7 @@ pub use hooks::{
     CustomEventEmitHook,
     CustomEventEmitInput,
     CustomEventEmitOutput,
+    DispatchStats,
     // Error hooks
     ErrorHandleHook,
     ErrorHandleInput,
@@ -151,6 +152,7 @@ pub use hooks::{
     FileOperationBeforeHook,
     FileOperationBeforeInput,
     FileOperationBeforeOutput,
+    FileOperationPayload,
     FilePostAction,
     // Focus hooks
     FocusAction,
@@ -275,13 +277,15 @@ pub use manifest::{
 };
 pub use plugin::{Plugin, PluginInfo, PluginState, PluginStatus};
 pub use registry::{PluginIndex, PluginIndexEntry, PluginRegistry, RemoteRegistry};
-pub use runtime::{PluginStoreState, WasmPlugin, WasmRuntime};
-pub use signing::PluginSigner;
+pub use runtime::{ExecutionLimits, PluginStoreState, WasmPlugin, WasmRuntime};
+pub use signing::{DetachedSignature, PluginKeypair, PluginSigner};
 
 // Host function re-exports
 pub use host::{
-    HasHostState, HostError, LogLevel as HostLogLevel, PluginEvent, PluginHostState,
-    ToastLevel as HostToastLevel, ToastNotification, create_linker, register_host_functions,
+    Diagnostic, HasHostState, HostCapability, HostError, HttpClient, HttpFetchRequest,
+    HttpFetchResponse, LogLevel as HostLogLevel, PluginEvent, PluginHostState, RateLimiter,
+    ReqwestHttpClient, ToastLevel as HostToastLevel, ToastNotification, create_linker,
+    register_host_functions,
 };
 
 /// Plugin system version
@@ -538,6 +538,10 @@ pub struct WasmSettings {
     #[serde(default = "default_timeout_ms")]
     pub timeout_ms: u64,
 
+    /// Fuel (roughly, WASM instructions) available to each call
+    #[serde(default = "default_fuel")]
+    pub fuel: u64,
+
     /// Enable WASI preview1 compatibility
     #[serde(default = "default_wasi_enabled")]
     pub wasi_enabled: bool,
@@ -552,6 +556,7 @@ impl Default for WasmSettings {
         Self {
             memory_pages: default_memory_pages(),
             timeout_ms: default_timeout_ms(),
+            fuel: default_fuel(),
             wasi_enabled: default_wasi_enabled(),
             wasi_caps: Vec::new(),
         }
@@ -566,6 +571,10 @@ fn default_timeout_ms() -> u64 {
     30000 // 30 seconds
 }
 
+fn default_fuel() -> u64 {
+    crate::runtime::DEFAULT_FUEL_LIMIT
+}
+
 fn default_wasi_enabled() -> bool {
     true
 }
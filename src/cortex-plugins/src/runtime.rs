@@ -8,22 +8,35 @@
 //! The runtime includes resource limits to prevent DoS attacks:
 //! - CPU: Fuel-based limiting and epoch interruption
 //! - Memory: Maximum 16MB per plugin instance
+//!
+//! Fuel and wall-clock limits are set per plugin from the manifest's `[wasm]`
+//! section (see [`ExecutionLimits`]). A plugin that exceeds either limit is
+//! trapped and the call fails with `PluginError::ExecutionError`.
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use wasmtime::*;
 
 use crate::api::{PluginContext, PluginHostFunctions};
+use crate::hooks::{FileOperationAfterInput, FileOperationPayload};
 use crate::host::{self, HasHostState, PluginHostState};
-use crate::manifest::PluginManifest;
+use crate::manifest::{HookType, PluginManifest, PluginPermission, WasmSettings};
 use crate::plugin::{Plugin, PluginInfo, PluginState};
 use crate::{PluginError, Result};
 
 /// Default fuel limit for WASM execution (CPU operations limit).
 /// This value allows approximately 10 million operations before exhaustion.
-const DEFAULT_FUEL_LIMIT: u64 = 10_000_000;
+pub const DEFAULT_FUEL_LIMIT: u64 = 10_000_000;
+
+/// Interval at which the runtime advances the engine epoch.
+///
+/// Wall-clock limits are rounded up to a whole number of ticks.
+pub const EPOCH_TICK: Duration = Duration::from_millis(10);
 
 /// Maximum memory size for a plugin instance (16MB).
 const MAX_MEMORY_SIZE: usize = 16 * 1024 * 1024;
@@ -44,10 +57,76 @@ const MAX_TABLES: u32 = 10;
 /// Maximum number of memories per instance.
 const MAX_MEMORIES: u32 = 1;
 
+/// CPU and wall-clock budget for a single plugin call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionLimits {
+    /// Fuel available to each call.
+    pub fuel: u64,
+    /// Wall-clock time after which a call is interrupted.
+    pub timeout: Duration,
+}
+
+impl ExecutionLimits {
+    /// Limits declared in a manifest's `[wasm]` section.
+    pub fn from_settings(settings: &WasmSettings) -> Self {
+        Self {
+            fuel: settings.fuel,
+            timeout: Duration::from_millis(settings.timeout_ms),
+        }
+    }
+
+    /// Number of epoch ticks covering `timeout` (at least one).
+    fn epoch_ticks(&self) -> u64 {
+        let ticks = self.timeout.as_millis().div_ceil(EPOCH_TICK.as_millis());
+        u64::try_from(ticks).unwrap_or(u64::MAX).max(1)
+    }
+}
+
+impl Default for ExecutionLimits {
+    fn default() -> Self {
+        Self::from_settings(&WasmSettings::default())
+    }
+}
+
+/// Background thread advancing the engine epoch every [`EPOCH_TICK`].
+struct EpochTicker {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl EpochTicker {
+    fn start(engine: Engine) -> Result<Self> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let handle = std::thread::Builder::new()
+            .name("cortex-plugin-epoch".to_string())
+            .spawn(move || {
+                while !thread_stop.load(Ordering::Relaxed) {
+                    std::thread::sleep(EPOCH_TICK);
+                    engine.increment_epoch();
+                }
+            })?;
+        Ok(Self {
+            stop,
+            handle: Some(handle),
+        })
+    }
+}
+
+impl Drop for EpochTicker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
 /// WASM runtime for executing plugins.
 pub struct WasmRuntime {
     engine: Engine,
     linker: Linker<PluginStoreState>,
+    _epoch_ticker: EpochTicker,
 }
 
 impl WasmRuntime {
@@ -57,7 +136,8 @@ impl WasmRuntime {
     ///
     /// The runtime is configured with:
     /// - Fuel consumption for CPU limiting
-    /// - Epoch-based interruption for timeout handling
+    /// - Epoch-based interruption for timeout handling, driven by a
+    ///   background thread that ticks every [`EPOCH_TICK`]
     ///
     /// # Note on async_support
     ///
@@ -83,8 +163,13 @@ impl WasmRuntime {
 
         // Create linker with host functions registered
         let linker = host::create_linker::<PluginStoreState>(&engine)?;
+        let epoch_ticker = EpochTicker::start(engine.clone())?;
 
-        Ok(Self { engine, linker })
+        Ok(Self {
+            engine,
+            linker,
+            _epoch_ticker: epoch_ticker,
+        })
     }
 
     /// Get the linker reference.
@@ -125,6 +210,9 @@ pub struct WasmPlugin {
     host: Arc<PluginHostFunctions>,
     config: RwLock<HashMap<String, serde_json::Value>>,
     runtime: Arc<WasmRuntime>,
+    limits: ExecutionLimits,
+    /// Rate limiter shared by every invocation of this plugin.
+    rate_limiter: Arc<Mutex<host::RateLimiter>>,
 }
 
 impl WasmPlugin {
@@ -134,6 +222,7 @@ impl WasmPlugin {
         let wasm_path = path.join(crate::WASM_FILE);
 
         let host = Arc::new(PluginHostFunctions::new(&info.id, path.clone()));
+        let limits = ExecutionLimits::from_settings(&manifest.wasm);
 
         Ok(Self {
             info,
@@ -144,9 +233,22 @@ impl WasmPlugin {
             host,
             config: RwLock::new(HashMap::new()),
             runtime,
+            limits,
+            rate_limiter: Arc::new(Mutex::new(host::RateLimiter::default())),
         })
     }
 
+    /// Override the limits taken from the manifest.
+    pub fn with_limits(mut self, limits: ExecutionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Fuel and time budget applied to each call.
+    pub fn limits(&self) -> ExecutionLimits {
+        self.limits
+    }
+
     /// Load and compile the WASM module.
     pub fn load(&mut self) -> Result<()> {
         self.state = PluginState::Loading;
@@ -183,6 +285,7 @@ impl WasmPlugin {
     ///
     /// The store is configured with resource limits:
     /// - Fuel limit: Prevents excessive CPU usage (default: 10M operations)
+    /// - Time limit: Interrupts calls running past the manifest's `timeout_ms`
     /// - Memory limit: Maximum 16MB per instance
     /// - Table/instance limits for additional sandboxing
     pub async fn call_function(&self, name: &str) -> Result<i32> {
@@ -190,6 +293,68 @@ impl WasmPlugin {
         self.call_function_with_context(name, context).await
     }
 
+    /// Build the host state for a single invocation.
+    ///
+    /// Host capabilities come from the manifest, and `http_fetch` is only
+    /// enabled for the domains listed in the manifest's `network` permissions.
+    fn new_host_state(&self, context: PluginContext) -> PluginHostState {
+        let state = PluginHostState::new(&self.info.id, context)
+            .with_state_dir(self.wasm_path.parent().unwrap_or(Path::new(".")))
+            .with_capabilities(host::HostCapability::from_manifest(&self.manifest))
+            .with_rate_limiter(self.rate_limiter.clone());
+
+        let hosts: Vec<String> = self
+            .manifest
+            .permissions
+            .iter()
+            .filter_map(|perm| match perm {
+                PluginPermission::Network { domains } => domains.clone(),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        if hosts.is_empty() {
+            return state;
+        }
+        state.with_http_client(Arc::new(host::ReqwestHttpClient::default()), hosts)
+    }
+
+    /// Create a store with fuel, epoch deadline and resource limits applied.
+    fn new_store(&self, host_state: PluginHostState) -> Result<Store<PluginStoreState>> {
+        let store_state = PluginStoreState::new(host_state);
+        let mut store = Store::new(self.runtime.engine(), store_state);
+
+        // SECURITY: Set fuel limit to prevent infinite loops and excessive CPU usage
+        store.set_fuel(self.limits.fuel).map_err(|e| {
+            PluginError::execution_error(&self.info.id, format!("Failed to set fuel: {}", e))
+        })?;
+
+        // SECURITY: Trap once the wall-clock budget has elapsed
+        store.epoch_deadline_trap();
+        store.set_epoch_deadline(self.limits.epoch_ticks());
+
+        // SECURITY: Configure the store's resource limiter
+        store.limiter(|state| state);
+
+        Ok(store)
+    }
+
+    /// Map a wasmtime error, naming the exceeded limit for budget traps.
+    fn execution_error(&self, error: wasmtime::Error) -> PluginError {
+        let message = match error.downcast_ref::<Trap>() {
+            Some(Trap::OutOfFuel) => format!(
+                "Plugin exceeded its fuel budget of {} units",
+                self.limits.fuel
+            ),
+            Some(Trap::Interrupt) => format!(
+                "Plugin exceeded its time limit of {} ms",
+                self.limits.timeout.as_millis()
+            ),
+            _ => error.to_string(),
+        };
+        PluginError::execution_error(&self.info.id, message)
+    }
+
     /// Call a WASM function with execution context.
     ///
     /// This method uses the linker with host functions, allowing the WASM
@@ -199,30 +364,69 @@ impl WasmPlugin {
         name: &str,
         context: PluginContext,
     ) -> Result<i32> {
+        self.call_with_host_state(name, self.new_host_state(context))
+    }
+
+    /// Call a hook handler with its JSON payload.
+    ///
+    /// The payload is readable from the plugin through `get_hook_payload`
+    /// for the duration of the call.
+    pub async fn call_hook(
+        &self,
+        name: &str,
+        context: PluginContext,
+        payload: impl Into<String>,
+    ) -> Result<i32> {
+        let host_state = self.new_host_state(context).with_hook_payload(payload);
+        self.call_with_host_state(name, host_state)
+    }
+
+    /// Run the plugin's `file_operation_after` hook for a completed operation.
+    ///
+    /// The operation reaches the plugin as a [`FileOperationPayload`] through
+    /// `get_hook_payload`. The handler is the `function` of the manifest's
+    /// `file_operation_after` hook, defaulting to `hook_file_operation_after`.
+    /// Returns `Ok(None)` if the manifest declares no such hook.
+    pub async fn call_file_operation_after(
+        &self,
+        context: PluginContext,
+        input: &FileOperationAfterInput,
+        lines_added: u64,
+        lines_removed: u64,
+    ) -> Result<Option<i32>> {
+        let Some(hook) = self
+            .manifest
+            .hooks
+            .iter()
+            .find(|h| h.hook_type == HookType::FileOperationAfter)
+        else {
+            return Ok(None);
+        };
+        let function = hook
+            .function
+            .as_deref()
+            .unwrap_or("hook_file_operation_after");
+        let payload = FileOperationPayload::from_input(input, lines_added, lines_removed);
+        self.call_hook(function, context, payload.to_json())
+            .await
+            .map(Some)
+    }
+
+    fn call_with_host_state(&self, name: &str, host_state: PluginHostState) -> Result<i32> {
         let module = self
             .module
             .as_ref()
             .ok_or_else(|| PluginError::execution_error(&self.info.id, "Plugin not loaded"))?;
 
-        // Create host state for this invocation
-        let host_state = PluginHostState::new(&self.info.id, context);
-        let store_state = PluginStoreState::new(host_state);
-        let mut store = Store::new(self.runtime.engine(), store_state);
-
-        // SECURITY: Set fuel limit to prevent infinite loops and excessive CPU usage
-        store.set_fuel(DEFAULT_FUEL_LIMIT).map_err(|e| {
-            PluginError::execution_error(&self.info.id, format!("Failed to set fuel: {}", e))
-        })?;
-
-        // SECURITY: Configure the store's resource limiter
-        store.limiter(|state| state);
+        // Create a limited store for this invocation
+        let mut store = self.new_store(host_state)?;
 
         // Use the linker to instantiate the module with host functions
         let instance = self
             .runtime
             .linker()
             .instantiate(&mut store, module)
-            .map_err(|e| PluginError::execution_error(&self.info.id, e.to_string()))?;
+            .map_err(|e| self.execution_error(e))?;
 
         let func = instance
             .get_typed_func::<(), i32>(&mut store, name)
@@ -234,7 +438,7 @@ impl WasmPlugin {
             })?;
 
         func.call(&mut store, ())
-            .map_err(|e| PluginError::execution_error(&self.info.id, e.to_string()))
+            .map_err(|e| self.execution_error(e))
     }
 
     /// Call a WASM function and retrieve the host state after execution.
@@ -248,21 +452,13 @@ impl WasmPlugin {
             .as_ref()
             .ok_or_else(|| PluginError::execution_error(&self.info.id, "Plugin not loaded"))?;
 
-        let host_state = PluginHostState::new(&self.info.id, context);
-        let store_state = PluginStoreState::new(host_state);
-        let mut store = Store::new(self.runtime.engine(), store_state);
-
-        store.set_fuel(DEFAULT_FUEL_LIMIT).map_err(|e| {
-            PluginError::execution_error(&self.info.id, format!("Failed to set fuel: {}", e))
-        })?;
-
-        store.limiter(|state| state);
+        let mut store = self.new_store(self.new_host_state(context))?;
 
         let instance = self
             .runtime
             .linker()
             .instantiate(&mut store, module)
-            .map_err(|e| PluginError::execution_error(&self.info.id, e.to_string()))?;
+            .map_err(|e| self.execution_error(e))?;
 
         let func = instance
             .get_typed_func::<(), i32>(&mut store, name)
@@ -275,7 +471,7 @@ impl WasmPlugin {
 
         let result = func
             .call(&mut store, ())
-            .map_err(|e| PluginError::execution_error(&self.info.id, e.to_string()))?;
+            .map_err(|e| self.execution_error(e))?;
 
         let host_state = store.into_data().host_state;
         Ok((result, host_state))
@@ -517,4 +713,149 @@ mod tests {
         let runtime = WasmRuntime::new();
         assert!(runtime.is_ok());
     }
+
+    /// Load a plugin whose `spin` export never returns.
+    fn spinning_plugin(dir: &Path) -> WasmPlugin {
+        std::fs::write(
+            dir.join(crate::WASM_FILE),
+            r#"(module
+                (func (export "spin") (result i32)
+                    (loop $forever (br $forever))
+                    (i32.const 0)))"#,
+        )
+        .unwrap();
+        let manifest = PluginManifest::parse(
+            r#"
+[plugin]
+id = "spinner"
+name = "Spinner"
+version = "1.0.0"
+
+[wasm]
+timeout_ms = 2000
+"#,
+        )
+        .unwrap();
+        let runtime = Arc::new(WasmRuntime::new().unwrap());
+        let mut plugin = WasmPlugin::new(manifest, dir.to_path_buf(), runtime).unwrap();
+        plugin.load().unwrap();
+        plugin
+    }
+
+    #[test]
+    fn test_limits_from_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = spinning_plugin(dir.path());
+
+        assert_eq!(plugin.limits().fuel, DEFAULT_FUEL_LIMIT);
+        assert_eq!(plugin.limits().timeout, Duration::from_millis(2000));
+        assert_eq!(plugin.limits().epoch_ticks(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_tight_loop_runs_out_of_fuel() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = spinning_plugin(dir.path()).with_limits(ExecutionLimits {
+            fuel: 100_000,
+            timeout: Duration::from_secs(60),
+        });
+
+        let err = plugin.call_function("spin").await.unwrap_err();
+        assert!(err.to_string().contains("fuel budget"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_tight_loop_interrupted_by_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = spinning_plugin(dir.path()).with_limits(ExecutionLimits {
+            fuel: u64::MAX,
+            timeout: Duration::from_millis(50),
+        });
+
+        let started = std::time::Instant::now();
+        let err = plugin.call_function("spin").await.unwrap_err();
+        assert!(err.to_string().contains("time limit"), "{err}");
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    /// Load a plugin whose `file_operation_after` hook copies the payload to
+    /// offset 0 of its memory and returns the payload length.
+    fn file_hook_plugin(dir: &Path, hooks: &str) -> WasmPlugin {
+        std::fs::write(
+            dir.join(crate::WASM_FILE),
+            r#"(module
+                (import "cortex" "get_hook_payload_len" (func $len (result i64)))
+                (import "cortex" "get_hook_payload" (func $read (param i32 i32) (result i64)))
+                (memory (export "memory") 1)
+                (func (export "on_file_op") (result i32)
+                    (i32.wrap_i64 (call $read (i32.const 0) (i32.wrap_i64 (call $len))))))"#,
+        )
+        .unwrap();
+        let manifest = PluginManifest::parse(&format!(
+            r#"
+[plugin]
+id = "file-stats"
+name = "File Stats"
+version = "1.0.0"
+{hooks}
+"#
+        ))
+        .unwrap();
+        let runtime = Arc::new(WasmRuntime::new().unwrap());
+        let mut plugin = WasmPlugin::new(manifest, dir.to_path_buf(), runtime).unwrap();
+        plugin.load().unwrap();
+        plugin
+    }
+
+    #[tokio::test]
+    async fn test_file_operation_after_passes_payload() {
+        use crate::hooks::FileOperation;
+
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = file_hook_plugin(
+            dir.path(),
+            r#"
+[[hooks]]
+hook_type = "file_operation_after"
+function = "on_file_op"
+"#,
+        );
+        let input = FileOperationAfterInput {
+            session_id: "s1".to_string(),
+            operation: FileOperation::Write,
+            path: PathBuf::from("src/main.rs"),
+            dest_path: None,
+            success: true,
+            error: None,
+        };
+
+        let context = PluginContext::new(dir.path());
+        let written = plugin
+            .call_file_operation_after(context, &input, 12, 3)
+            .await
+            .unwrap();
+
+        let expected = FileOperationPayload::from_input(&input, 12, 3).to_json();
+        assert_eq!(written, Some(expected.len() as i32));
+    }
+
+    #[tokio::test]
+    async fn test_file_operation_after_without_hook() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = file_hook_plugin(dir.path(), "");
+        let input = FileOperationAfterInput {
+            session_id: "s1".to_string(),
+            operation: crate::hooks::FileOperation::Delete,
+            path: PathBuf::from("a.txt"),
+            dest_path: None,
+            success: true,
+            error: None,
+        };
+
+        let context = PluginContext::new(dir.path());
+        let result = plugin
+            .call_file_operation_after(context, &input, 0, 0)
+            .await;
+        assert_eq!(result.unwrap(), None);
+    }
 }
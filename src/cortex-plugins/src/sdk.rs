@@ -168,8 +168,12 @@ extern "C" {
     /// level: 0=trace, 1=debug, 2=info, 3=warn, 4=error
     fn log(level: i32, msg_ptr: i32, msg_len: i32);
 
-    /// Get context JSON (returns length)
-    fn get_context() -> i64;
+    /// Get the size of the context JSON in bytes.
+    fn get_context_len() -> i64;
+
+    /// Copy the context JSON into a buffer (returns bytes written, or a
+    /// negative error if the buffer is smaller than `get_context_len()`).
+    fn get_context_read(ptr: i32, len: i32) -> i64;
 }
 
 // ============================================================================
@@ -683,7 +687,8 @@ use alloc::vec;
 #[link(wasm_import_module = "cortex")]
 extern "C" {
     fn log(level: i32, msg_ptr: i32, msg_len: i32);
-    fn get_context() -> i64;
+    fn get_context_len() -> i64;
+    fn get_context_read(ptr: i32, len: i32) -> i64;
     fn register_widget(region: i32, widget_type_ptr: i32, widget_type_len: i32) -> i32;
     fn register_keybinding(key_ptr: i32, key_len: i32, action_ptr: i32, action_len: i32) -> i32;
     fn show_toast(level: i32, msg_ptr: i32, msg_len: i32, duration_ms: i32) -> i32;
@@ -771,7 +776,7 @@ fn show_notification(level: ToastLevel, message: &str, duration_ms: i32) {
     // 1. `show_toast` is a valid function pointer provided by the WASM runtime
     // 2. The level is passed by value and validated by the host (invalid = Info)
     // 3. The message string is passed as (ptr, len) and copied by the host
-    // 4. duration_ms is passed by value; invalid values are clamped by host
+    // 4. duration_ms is passed by value; the host clamps it to 500..=60000 ms
     // 5. The host does not retain the message pointer past this call
     // 6. The function has no return value; failures are logged on the host side
     unsafe {
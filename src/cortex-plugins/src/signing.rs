@@ -1,21 +1,88 @@
 //! Plugin signing and verification.
 //!
 //! Provides ed25519-based signature verification for plugin authenticity
-//! and SHA256 checksum computation for integrity verification.
+//! and SHA256 checksum computation for integrity verification. Signing is
+//! kept in [`PluginKeypair`], so [`PluginSigner`] only ever holds public keys.
 
-use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use std::collections::HashSet;
+use std::io::{self, Read};
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
 use crate::{PluginError, Result};
 
+/// Chunk size used when hashing a reader.
+const CHECKSUM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Algorithm name used in detached signatures produced by [`PluginKeypair`].
+pub const ED25519_ALGORITHM: &str = "ed25519";
+
+/// Identifier of a public key: the first 16 hex characters of its SHA256 hash.
+pub fn key_id(public_key: &[u8; 32]) -> String {
+    PluginSigner::compute_checksum(public_key)[..16].to_string()
+}
+
+/// A `.sig` sidecar bundling a plugin signature with its metadata.
+///
+/// Stored as JSON, with the signature hex-encoded:
+///
+/// ```json
+/// { "algorithm": "ed25519", "key_id": "3f2a…", "signature": "9a0c…" }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetachedSignature {
+    /// Signature algorithm; only `ed25519` is supported
+    pub algorithm: String,
+    /// [`key_id`] of the signing key
+    pub key_id: String,
+    /// Raw signature bytes
+    #[serde(with = "hex_bytes")]
+    pub signature: Vec<u8>,
+}
+
+impl DetachedSignature {
+    /// Parse a sidecar from its JSON bytes.
+    ///
+    /// # Errors
+    /// Returns an error if the bytes are not a valid sidecar.
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        serde_json::from_slice(bytes)
+            .map_err(|e| PluginError::SignatureError(format!("Invalid signature file: {}", e)))
+    }
+
+    /// Serialize the sidecar as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// Serde helpers encoding byte vectors as hex strings.
+mod hex_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        hex::decode(s).map_err(serde::de::Error::custom)
+    }
+}
+
 /// Plugin signature verification using ed25519.
 ///
 /// The signer maintains a list of trusted public keys and can verify
-/// plugin signatures against them.
+/// plugin signatures against them. Revoked keys never verify, even if they
+/// are added as trusted again, until they are explicitly un-revoked.
 #[derive(Debug, Default)]
 pub struct PluginSigner {
     /// Trusted public keys for signature verification
     trusted_keys: Vec<VerifyingKey>,
+    /// Public keys that must not verify, regardless of `trusted_keys`
+    revoked_keys: HashSet<[u8; 32]>,
 }
 
 impl PluginSigner {
@@ -25,6 +92,7 @@ impl PluginSigner {
     pub fn new() -> Self {
         Self {
             trusted_keys: Vec::new(),
+            revoked_keys: HashSet::new(),
         }
     }
 
@@ -36,6 +104,19 @@ impl PluginSigner {
     /// # Errors
     /// Returns an error if the key bytes are invalid.
     pub fn add_trusted_key(&mut self, key_bytes: &[u8]) -> Result<()> {
+        let verifying_key = Self::parse_key(key_bytes)?;
+        if self.is_revoked(&verifying_key) {
+            tracing::warn!("Added trusted signing key is revoked and will not verify");
+        }
+
+        self.trusted_keys.push(verifying_key);
+        tracing::debug!("Added trusted signing key");
+
+        Ok(())
+    }
+
+    /// Parse a 32-byte ed25519 public key.
+    fn parse_key(key_bytes: &[u8]) -> Result<VerifyingKey> {
         if key_bytes.len() != 32 {
             return Err(PluginError::SignatureError(format!(
                 "Invalid public key length: expected 32 bytes, got {}",
@@ -47,14 +128,8 @@ impl PluginSigner {
             PluginError::SignatureError("Failed to convert key bytes to array".to_string())
         })?;
 
-        let verifying_key = VerifyingKey::from_bytes(&key_array).map_err(|e| {
-            PluginError::SignatureError(format!("Invalid ed25519 public key: {}", e))
-        })?;
-
-        self.trusted_keys.push(verifying_key);
-        tracing::debug!("Added trusted signing key");
-
-        Ok(())
+        VerifyingKey::from_bytes(&key_array)
+            .map_err(|e| PluginError::SignatureError(format!("Invalid ed25519 public key: {}", e)))
     }
 
     /// Add a trusted key from a hex-encoded string.
@@ -71,6 +146,67 @@ impl PluginSigner {
         self.add_trusted_key(&key_bytes)
     }
 
+    /// Revoke a public key.
+    ///
+    /// Removes the key from the trusted keys and records it as revoked, so
+    /// it fails verification even if it is added again later.
+    ///
+    /// # Arguments
+    /// * `key_bytes` - 32-byte ed25519 public key
+    ///
+    /// # Returns
+    /// `true` if the key was trusted and has been removed.
+    ///
+    /// # Errors
+    /// Returns an error if the key bytes are invalid.
+    pub fn revoke_key(&mut self, key_bytes: &[u8]) -> Result<bool> {
+        let verifying_key = Self::parse_key(key_bytes)?;
+
+        let before = self.trusted_keys.len();
+        self.trusted_keys.retain(|key| key != &verifying_key);
+        let removed = self.trusted_keys.len() != before;
+
+        self.revoked_keys.insert(verifying_key.to_bytes());
+        tracing::debug!(removed, "Revoked signing key");
+
+        Ok(removed)
+    }
+
+    /// Revoke a public key from a hex-encoded string.
+    ///
+    /// # Arguments
+    /// * `hex_key` - Hex-encoded 32-byte ed25519 public key (64 hex characters)
+    ///
+    /// # Errors
+    /// Returns an error if the hex string is invalid or the key is invalid.
+    pub fn revoke_key_hex(&mut self, hex_key: &str) -> Result<bool> {
+        let key_bytes = hex::decode(hex_key)
+            .map_err(|e| PluginError::SignatureError(format!("Invalid hex-encoded key: {}", e)))?;
+
+        self.revoke_key(&key_bytes)
+    }
+
+    /// Lift a revocation, letting the key verify again once it is trusted.
+    ///
+    /// Returns `true` if the key was revoked.
+    ///
+    /// # Errors
+    /// Returns an error if the key bytes are invalid.
+    pub fn unrevoke_key(&mut self, key_bytes: &[u8]) -> Result<bool> {
+        let verifying_key = Self::parse_key(key_bytes)?;
+        Ok(self.revoked_keys.remove(&verifying_key.to_bytes()))
+    }
+
+    /// Check whether a key has been revoked.
+    fn is_revoked(&self, key: &VerifyingKey) -> bool {
+        self.revoked_keys.contains(&key.to_bytes())
+    }
+
+    /// Get the number of revoked keys.
+    pub fn revoked_key_count(&self) -> usize {
+        self.revoked_keys.len()
+    }
+
     /// Get the number of trusted keys.
     pub fn trusted_key_count(&self) -> usize {
         self.trusted_keys.len()
@@ -98,6 +234,58 @@ impl PluginSigner {
             return Ok(false);
         }
 
+        let sig = Self::parse_signature(signature)?;
+
+        // Try each trusted key that has not been revoked
+        for key in self.trusted_keys.iter().filter(|key| !self.is_revoked(key)) {
+            if key.verify(wasm_bytes, &sig).is_ok() {
+                tracing::debug!("Plugin signature verified successfully");
+                return Ok(true);
+            }
+        }
+
+        tracing::warn!("Plugin signature verification failed - no trusted key matched");
+        Ok(false)
+    }
+
+    /// Verify a plugin against a detached signature.
+    ///
+    /// Only the trusted, non-revoked key whose [`key_id`] matches the
+    /// sidecar's `key_id` is tried.
+    ///
+    /// # Errors
+    /// Returns an error if the algorithm is not `ed25519` or the signature
+    /// format is invalid.
+    pub fn verify_detached(&self, wasm_bytes: &[u8], sig: &DetachedSignature) -> Result<bool> {
+        if !sig.algorithm.eq_ignore_ascii_case(ED25519_ALGORITHM) {
+            return Err(PluginError::SignatureError(format!(
+                "Unsupported signature algorithm: {}",
+                sig.algorithm
+            )));
+        }
+        let signature = Self::parse_signature(&sig.signature)?;
+
+        let Some(key) = self
+            .trusted_keys
+            .iter()
+            .filter(|key| !self.is_revoked(key))
+            .find(|key| key_id(key.as_bytes()).eq_ignore_ascii_case(&sig.key_id))
+        else {
+            tracing::warn!(key_id = %sig.key_id, "No trusted key matches detached signature key id");
+            return Ok(false);
+        };
+
+        let verified = key.verify(wasm_bytes, &signature).is_ok();
+        if verified {
+            tracing::debug!(key_id = %sig.key_id, "Detached plugin signature verified successfully");
+        } else {
+            tracing::warn!(key_id = %sig.key_id, "Detached plugin signature verification failed");
+        }
+        Ok(verified)
+    }
+
+    /// Parse a 64-byte ed25519 signature.
+    fn parse_signature(signature: &[u8]) -> Result<Signature> {
         if signature.len() != 64 {
             return Err(PluginError::SignatureError(format!(
                 "Invalid signature length: expected 64 bytes, got {}",
@@ -109,18 +297,7 @@ impl PluginSigner {
             PluginError::SignatureError("Failed to convert signature bytes to array".to_string())
         })?;
 
-        let sig = Signature::from_bytes(&sig_array);
-
-        // Try each trusted key
-        for key in &self.trusted_keys {
-            if key.verify(wasm_bytes, &sig).is_ok() {
-                tracing::debug!("Plugin signature verified successfully");
-                return Ok(true);
-            }
-        }
-
-        tracing::warn!("Plugin signature verification failed - no trusted key matched");
-        Ok(false)
+        Ok(Signature::from_bytes(&sig_array))
     }
 
     /// Verify a plugin signature from hex-encoded signature string.
@@ -155,6 +332,29 @@ impl PluginSigner {
         hex::encode(result)
     }
 
+    /// Compute SHA256 checksum of a reader's contents and return as hex string.
+    ///
+    /// Reads in 64 KiB chunks, so large plugin files can be hashed without
+    /// loading them into memory. Produces the same output as
+    /// [`compute_checksum`](Self::compute_checksum).
+    ///
+    /// # Errors
+    /// Returns any I/O error from the reader.
+    pub fn compute_checksum_reader<R: Read>(reader: &mut R) -> io::Result<String> {
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; CHECKSUM_CHUNK_SIZE];
+        loop {
+            let read = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            hasher.update(&buffer[..read]);
+        }
+        Ok(hex::encode(hasher.finalize()))
+    }
+
     /// Verify that data matches an expected checksum.
     ///
     /// # Arguments
@@ -169,6 +369,60 @@ impl PluginSigner {
     }
 }
 
+/// An ed25519 keypair for publishing signed plugins.
+///
+/// Signatures produced by [`sign`](Self::sign) verify with
+/// [`PluginSigner::verify_plugin`] once the public key is trusted.
+pub struct PluginKeypair {
+    signing_key: SigningKey,
+}
+
+impl PluginKeypair {
+    /// Generate a new random keypair.
+    pub fn generate() -> Self {
+        Self::from_bytes(&rand::random())
+    }
+
+    /// Restore a keypair from its 32-byte secret key.
+    pub fn from_bytes(secret_key: &[u8; 32]) -> Self {
+        Self {
+            signing_key: SigningKey::from_bytes(secret_key),
+        }
+    }
+
+    /// Sign a WASM module, returning the 64-byte ed25519 signature.
+    pub fn sign(&self, wasm_bytes: &[u8]) -> [u8; 64] {
+        self.signing_key.sign(wasm_bytes).to_bytes()
+    }
+
+    /// Hex-encoded public key, as accepted by [`PluginSigner::add_trusted_key_hex`].
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.signing_key.verifying_key().to_bytes())
+    }
+
+    /// [`key_id`] of this keypair's public key.
+    pub fn key_id(&self) -> String {
+        key_id(self.signing_key.verifying_key().as_bytes())
+    }
+
+    /// Sign a WASM module, producing a `.sig` sidecar.
+    pub fn sign_detached(&self, wasm_bytes: &[u8]) -> DetachedSignature {
+        DetachedSignature {
+            algorithm: ED25519_ALGORITHM.to_string(),
+            key_id: self.key_id(),
+            signature: self.sign(wasm_bytes).to_vec(),
+        }
+    }
+}
+
+impl std::fmt::Debug for PluginKeypair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginKeypair")
+            .field("public_key", &self.public_key_hex())
+            .finish_non_exhaustive()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -313,4 +567,174 @@ mod tests {
                 .contains("Invalid hex-encoded signature")
         );
     }
+
+    #[test]
+    fn test_keypair_sign_verify_round_trip() {
+        let keypair = PluginKeypair::generate();
+        let wasm = b"\0asm\x01\0\0\0";
+        let signature = keypair.sign(wasm);
+
+        let mut signer = PluginSigner::new();
+        signer
+            .add_trusted_key_hex(&keypair.public_key_hex())
+            .unwrap();
+
+        assert!(signer.verify_plugin(wasm, &signature).unwrap());
+        assert!(!signer.verify_plugin(b"tampered", &signature).unwrap());
+    }
+
+    #[test]
+    fn test_keypair_from_bytes_is_deterministic() {
+        // RFC 8032 test vector 1
+        let secret: [u8; 32] =
+            hex::decode("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let keypair = PluginKeypair::from_bytes(&secret);
+
+        assert_eq!(keypair.public_key_hex(), TEST_PUBLIC_KEY_HEX);
+        assert_eq!(
+            keypair.sign(b"data"),
+            PluginKeypair::from_bytes(&secret).sign(b"data")
+        );
+        assert!(!format!("{:?}", keypair).contains("9d61b19d"));
+    }
+
+    #[test]
+    fn test_revoke_key_then_verify_fails() {
+        let keypair = PluginKeypair::generate();
+        let wasm = b"plugin";
+        let signature = keypair.sign(wasm);
+
+        let mut signer = PluginSigner::new();
+        signer
+            .add_trusted_key_hex(&keypair.public_key_hex())
+            .unwrap();
+        assert!(signer.verify_plugin(wasm, &signature).unwrap());
+
+        assert!(signer.revoke_key_hex(&keypair.public_key_hex()).unwrap());
+        assert_eq!(signer.trusted_key_count(), 0);
+        assert_eq!(signer.revoked_key_count(), 1);
+        assert!(!signer.verify_plugin(wasm, &signature).unwrap());
+    }
+
+    #[test]
+    fn test_revoked_key_stays_revoked_when_readded() {
+        let keypair = PluginKeypair::generate();
+        let key_hex = keypair.public_key_hex();
+        let key_bytes = hex::decode(&key_hex).unwrap();
+        let wasm = b"plugin";
+        let signature = keypair.sign(wasm);
+
+        let mut signer = PluginSigner::new();
+        signer.add_trusted_key_hex(&key_hex).unwrap();
+        signer.revoke_key(&key_bytes).unwrap();
+        signer.add_trusted_key_hex(&key_hex).unwrap();
+        assert!(!signer.verify_plugin(wasm, &signature).unwrap());
+
+        assert!(signer.unrevoke_key(&key_bytes).unwrap());
+        assert!(!signer.unrevoke_key(&key_bytes).unwrap());
+        assert!(signer.verify_plugin(wasm, &signature).unwrap());
+    }
+
+    #[test]
+    fn test_revoke_key_not_present() {
+        let mut signer = PluginSigner::new();
+        signer.add_trusted_key_hex(TEST_PUBLIC_KEY_HEX).unwrap();
+
+        let other = PluginKeypair::generate();
+        assert!(!signer.revoke_key_hex(&other.public_key_hex()).unwrap());
+        assert_eq!(signer.trusted_key_count(), 1);
+    }
+
+    #[test]
+    fn test_revoke_key_invalid_length() {
+        let mut signer = PluginSigner::new();
+        assert!(signer.revoke_key(&[0u8; 16]).is_err());
+    }
+
+    fn trusting(keypair: &PluginKeypair) -> PluginSigner {
+        let mut signer = PluginSigner::new();
+        signer
+            .add_trusted_key_hex(&keypair.public_key_hex())
+            .unwrap();
+        signer
+    }
+
+    #[test]
+    fn test_verify_detached_well_formed_sidecar() {
+        let keypair = PluginKeypair::generate();
+        let wasm = b"plugin";
+        let sidecar = keypair.sign_detached(wasm).to_json();
+
+        let sig = DetachedSignature::parse(sidecar.as_bytes()).unwrap();
+        assert_eq!(sig.algorithm, "ed25519");
+        assert_eq!(sig.key_id, keypair.key_id());
+
+        let signer = trusting(&keypair);
+        assert!(signer.verify_detached(wasm, &sig).unwrap());
+        assert!(!signer.verify_detached(b"tampered", &sig).unwrap());
+    }
+
+    #[test]
+    fn test_verify_detached_unknown_algorithm() {
+        let keypair = PluginKeypair::generate();
+        let json = format!(
+            r#"{{"algorithm":"rsa-sha256","key_id":"{}","signature":"{}"}}"#,
+            keypair.key_id(),
+            hex::encode(keypair.sign(b"plugin"))
+        );
+        let sig = DetachedSignature::parse(json.as_bytes()).unwrap();
+
+        let result = trusting(&keypair).verify_detached(b"plugin", &sig);
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Unsupported signature algorithm")
+        );
+    }
+
+    #[test]
+    fn test_verify_detached_key_id_mismatch() {
+        let trusted = PluginKeypair::generate();
+        let other = PluginKeypair::generate();
+        let mut signer = trusting(&trusted);
+        signer.add_trusted_key_hex(&other.public_key_hex()).unwrap();
+
+        // Signed by a trusted key but labelled with another trusted key's id.
+        let mut sig = trusted.sign_detached(b"plugin");
+        sig.key_id = other.key_id();
+        assert!(!signer.verify_detached(b"plugin", &sig).unwrap());
+
+        sig.key_id = "0000000000000000".to_string();
+        assert!(!signer.verify_detached(b"plugin", &sig).unwrap());
+    }
+
+    #[test]
+    fn test_detached_signature_parse_invalid() {
+        assert!(DetachedSignature::parse(b"not json").is_err());
+        assert!(
+            DetachedSignature::parse(br#"{"algorithm":"ed25519","key_id":"x","signature":"zz"}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_compute_checksum_reader_matches_slice() {
+        // Spans several chunks with a partial final chunk.
+        let data: Vec<u8> = (0..CHECKSUM_CHUNK_SIZE * 3 + 17)
+            .map(|i| (i % 251) as u8)
+            .collect();
+
+        let streamed = PluginSigner::compute_checksum_reader(&mut data.as_slice()).unwrap();
+        assert_eq!(streamed, PluginSigner::compute_checksum(&data));
+    }
+
+    #[test]
+    fn test_compute_checksum_reader_empty() {
+        let streamed = PluginSigner::compute_checksum_reader(&mut io::empty()).unwrap();
+        assert_eq!(streamed, PluginSigner::compute_checksum(b""));
+    }
 }
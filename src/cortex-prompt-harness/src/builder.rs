@@ -8,6 +8,7 @@ use std::collections::HashMap;
 use indexmap::IndexMap;
 
 use crate::context::ToolDefinition;
+use crate::estimate_tokens;
 use crate::sections::{self, PromptSection, SectionPriority};
 
 /// Builder for constructing system prompts.
@@ -218,13 +219,6 @@ impl SystemPromptBuilder {
     }
 }
 
-/// Estimate token count for a string.
-///
-/// Uses a simple approximation of ~4 characters per token.
-fn estimate_tokens(text: &str) -> u32 {
-    (text.len() as f64 / 4.0).ceil() as u32
-}
-
 /// Convenience function to build a simple prompt.
 pub fn build_simple_prompt(base: &str, sections: &[(&str, &str)]) -> String {
     let mut builder = SystemPromptBuilder::with_base(base);
@@ -80,6 +80,7 @@ pub mod notifications;
 pub mod prompts;
 pub mod sections;
 pub mod state;
+pub mod tokens;
 pub mod tracker;
 
 // Re-exports
@@ -88,6 +89,7 @@ pub use context::{AgentConfig, PromptContext, TaskConfig, ToolDefinition};
 pub use notifications::{AgentNotification, NotificationKind, NotificationType, UpdateNotifier};
 pub use sections::{PromptSection, SectionPriority};
 pub use state::{AgentState, PromptState};
+pub use tokens::{TokenEstimateMode, estimate_tokens, estimate_tokens_with};
 pub use tracker::{ChangeEvent, StateChange, StateTracker};
 
 use std::collections::HashMap;
@@ -73,6 +73,9 @@ load_skill([skill1, skill2, ...])
 | `debugging` | Failure protocol and error handling | Encountering errors, troubleshooting |
 | `security` | Security rules and secrets handling | Handling sensitive data, auth, keys |
 | `planning` | Task decomposition and cognitive phases | Complex multi-step tasks |
+| `rust` | Cargo workflow and Rust idioms | Building, testing, or fixing Rust code |
+| `docker` | Container images and compose setups | Writing Dockerfiles or compose files |
+| `testing` | Test structure, fixtures, and mocking | Writing or restructuring tests |
 
 ### Skill Loading Examples
 
@@ -81,6 +84,7 @@ load_skill([skill1, skill2, ...])
 - "Add new feature" → `load_skill(["planning", "code-quality", "file-operations"])`
 - "Review code security" → `load_skill(["security", "code-quality"])`
 - "Refactor this module" → `load_skill(["code-quality", "file-operations"])`
+- "Write tests for the parser" → `load_skill(["testing", "code-quality"])`
 
 ## Essential Rules
 
@@ -138,6 +142,9 @@ pub const AVAILABLE_SKILLS: &[&str] = &[
     "debugging",
     "security",
     "planning",
+    "rust",
+    "docker",
+    "testing",
 ];
 
 /// Skill metadata for display and recommendation.
@@ -149,6 +156,43 @@ pub struct SkillInfo {
     pub description: &'static str,
     /// Keywords that trigger this skill recommendation.
     pub keywords: &'static [&'static str],
+    /// Phrases that suppress this skill even when a keyword matched, to
+    /// avoid false positives (e.g. "dev env" matching "env").
+    pub anti_keywords: &'static [&'static str],
+    /// Position when injected alongside other skills; lower comes first.
+    pub priority: u8,
+}
+
+impl SkillInfo {
+    /// Create skill metadata with no anti-keywords that sorts last.
+    #[must_use]
+    pub const fn new(
+        name: &'static str,
+        description: &'static str,
+        keywords: &'static [&'static str],
+    ) -> Self {
+        Self {
+            name,
+            description,
+            keywords,
+            anti_keywords: &[],
+            priority: u8::MAX,
+        }
+    }
+
+    /// Set the phrases that suppress this skill.
+    #[must_use]
+    pub const fn with_anti_keywords(mut self, anti_keywords: &'static [&'static str]) -> Self {
+        self.anti_keywords = anti_keywords;
+        self
+    }
+
+    /// Set the injection priority.
+    #[must_use]
+    pub const fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
 }
 
 /// Metadata for all available skills.
@@ -173,6 +217,8 @@ pub const SKILL_METADATA: &[SkillInfo] = &[
             "log",
             "blame",
         ],
+        anti_keywords: &[],
+        priority: 60,
     },
     SkillInfo {
         name: "code-quality",
@@ -196,6 +242,8 @@ pub const SKILL_METADATA: &[SkillInfo] = &[
             "pytest",
             "cargo test",
         ],
+        anti_keywords: &[],
+        priority: 40,
     },
     SkillInfo {
         name: "file-operations",
@@ -214,6 +262,8 @@ pub const SKILL_METADATA: &[SkillInfo] = &[
             "path",
             "backup",
         ],
+        anti_keywords: &[],
+        priority: 50,
     },
     SkillInfo {
         name: "debugging",
@@ -234,6 +284,8 @@ pub const SKILL_METADATA: &[SkillInfo] = &[
             "failing",
             "broken",
         ],
+        anti_keywords: &[],
+        priority: 30,
     },
     SkillInfo {
         name: "security",
@@ -256,6 +308,8 @@ pub const SKILL_METADATA: &[SkillInfo] = &[
             "env",
             "environment variable",
         ],
+        anti_keywords: &["dev env", "environment setup"],
+        priority: 70,
     },
     SkillInfo {
         name: "planning",
@@ -274,13 +328,45 @@ pub const SKILL_METADATA: &[SkillInfo] = &[
             "phase",
             "implement feature",
         ],
+        anti_keywords: &[],
+        priority: 10,
+    },
+    SkillInfo {
+        name: "rust",
+        description: "Cargo workflow and Rust idioms",
+        keywords: &["cargo", "clippy", "rustc", "borrow", "lifetime"],
+        anti_keywords: &[],
+        priority: 20,
+    },
+    SkillInfo {
+        name: "docker",
+        description: "Container images and compose setups",
+        keywords: &["docker", "dockerfile", "container", "compose", "image"],
+        anti_keywords: &[],
+        priority: 25,
+    },
+    SkillInfo {
+        name: "testing",
+        description: "Test structure, fixtures, and mocking",
+        keywords: &[
+            "test",
+            "unit test",
+            "integration test",
+            "mock",
+            "fixture",
+            "coverage",
+            "assert",
+        ],
+        anti_keywords: &[],
+        priority: 45,
     },
 ];
 
 /// Get recommended skills based on task keywords.
 ///
 /// This function analyzes the task description and returns a list of
-/// recommended skills based on keyword matching.
+/// recommended skills based on keyword matching, most relevant first
+/// (see [`get_recommended_skills_scored`]).
 ///
 /// # Arguments
 ///
@@ -305,26 +391,61 @@ pub const SKILL_METADATA: &[SkillInfo] = &[
 /// ```
 #[must_use]
 pub fn get_recommended_skills(task: &str) -> Vec<&'static str> {
+    get_recommended_skills_scored(task)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// Get recommended skills with a relevance score.
+///
+/// The score is the number of distinct keywords of the skill found in the
+/// task. A skill is skipped entirely if any of its anti-keywords is present.
+/// Results are sorted by descending score; ties keep the [`SKILL_METADATA`]
+/// order. Use this to keep only the top-N skills when the context budget is
+/// tight.
+///
+/// A task over 100 characters with no keyword matches gets `("planning", 0)`.
+///
+/// # Examples
+///
+/// ```rust
+/// use cortex_prompt_harness::prompts::base_agent::get_recommended_skills_scored;
+///
+/// let scored = get_recommended_skills_scored("Fix the crash and commit the fix");
+/// assert_eq!(scored[0], ("debugging", 2));
+/// ```
+#[must_use]
+pub fn get_recommended_skills_scored(task: &str) -> Vec<(&'static str, u32)> {
     let task_lower = task.to_lowercase();
-    let mut recommended: Vec<&'static str> = Vec::new();
+    let mut scored: Vec<(&'static str, u32)> = Vec::new();
 
     for skill in SKILL_METADATA {
-        for keyword in skill.keywords {
-            if task_lower.contains(keyword) {
-                if !recommended.contains(&skill.name) {
-                    recommended.push(skill.name);
-                }
-                break;
-            }
+        if skill
+            .anti_keywords
+            .iter()
+            .any(|anti| task_lower.contains(anti))
+        {
+            continue;
+        }
+        let score = skill
+            .keywords
+            .iter()
+            .filter(|keyword| task_lower.contains(*keyword))
+            .count() as u32;
+        if score > 0 && !scored.iter().any(|(name, _)| *name == skill.name) {
+            scored.push((skill.name, score));
         }
     }
 
     // Default to planning for complex-sounding tasks with no specific matches
-    if recommended.is_empty() && task.len() > 100 {
-        recommended.push("planning");
+    if scored.is_empty() && task.len() > 100 {
+        scored.push(("planning", 0));
     }
 
-    recommended
+    // Stable sort keeps metadata order among equal scores
+    scored.sort_by(|a, b| b.1.cmp(&a.1));
+    scored
 }
 
 /// Format a skill loading prompt call.
@@ -358,6 +479,45 @@ pub fn format_skill_loading_prompt(skills: &[&str]) -> String {
     format!("load_skill([{}])", quoted.join(", "))
 }
 
+/// Summarize loaded skills as a compact, single-line string.
+///
+/// Invalid skill names are omitted from the summary. Intended for status
+/// bars and log lines.
+///
+/// # Arguments
+///
+/// * `skills` - The list of loaded skills
+///
+/// # Returns
+///
+/// A one-line summary such as `"skills: git, debugging (2 loaded)"`, or
+/// `"no skills loaded"` when no valid skills remain.
+///
+/// # Examples
+///
+/// ```rust
+/// use cortex_prompt_harness::prompts::base_agent::summarize_loaded_skills;
+///
+/// let summary = summarize_loaded_skills(&["git", "debugging"]);
+/// assert_eq!(summary, "skills: git, debugging (2 loaded)");
+///
+/// assert_eq!(summarize_loaded_skills(&[]), "no skills loaded");
+/// ```
+#[must_use]
+pub fn summarize_loaded_skills(skills: &[&str]) -> String {
+    let valid: Vec<&str> = skills
+        .iter()
+        .copied()
+        .filter(|s| is_valid_skill(s))
+        .collect();
+
+    if valid.is_empty() {
+        return String::from("no skills loaded");
+    }
+
+    format!("skills: {} ({} loaded)", valid.join(", "), valid.len())
+}
+
 /// Check if a skill name is valid.
 ///
 /// # Arguments
@@ -389,6 +549,40 @@ pub fn get_skill_description(skill: &str) -> Option<&'static str> {
         .map(|s| s.description)
 }
 
+/// Get the injection priority of a skill; lower comes first.
+///
+/// Names match case-insensitively. Skills without metadata (e.g.
+/// runtime-registered ones) sort last.
+///
+/// # Examples
+///
+/// ```rust
+/// use cortex_prompt_harness::prompts::base_agent::skill_priority;
+///
+/// assert!(skill_priority("planning") < skill_priority("security"));
+/// assert_eq!(skill_priority("custom"), u8::MAX);
+/// ```
+#[must_use]
+pub fn skill_priority(skill: &str) -> u8 {
+    SKILL_METADATA
+        .iter()
+        .find(|s| s.name.eq_ignore_ascii_case(skill))
+        .map_or(u8::MAX, |s| s.priority)
+}
+
+/// Extract the skill names listed in the `CORTEX_BASE_PROMPT` skill table.
+///
+/// Names are returned in table order. This is used to keep the hardcoded
+/// table in sync with [`AVAILABLE_SKILLS`].
+#[must_use]
+pub fn base_prompt_skill_names() -> Vec<&'static str> {
+    CORTEX_BASE_PROMPT
+        .lines()
+        .filter_map(|line| line.strip_prefix("| `"))
+        .filter_map(|rest| rest.split_once('`').map(|(name, _)| name))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -500,6 +694,92 @@ mod tests {
         assert!(skills.contains(&"security"));
     }
 
+    #[test]
+    fn test_get_recommended_skills_scored_ranks_by_matches() {
+        let scored = get_recommended_skills_scored("fix the failing test bug crash");
+        let score = |skill: &str| {
+            scored
+                .iter()
+                .find(|(name, _)| *name == skill)
+                .map_or(0, |(_, score)| *score)
+        };
+
+        assert_eq!(scored[0].0, "debugging");
+        assert_eq!(score("debugging"), 4);
+        assert!(score("debugging") > score("git"));
+        assert!(scored.windows(2).all(|w| w[0].1 >= w[1].1));
+    }
+
+    #[test]
+    fn test_get_recommended_skills_matches_scored_order() {
+        let task = "Commit the fix, then push the branch and open a pull request";
+        let names: Vec<&str> = get_recommended_skills_scored(task)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+
+        assert_eq!(get_recommended_skills(task), names);
+        assert_eq!(names[0], "git");
+    }
+
+    #[test]
+    fn test_get_recommended_skills_rust() {
+        let skills = get_recommended_skills("Fix the borrow checker error");
+        assert!(skills.contains(&"rust"));
+
+        let skills = get_recommended_skills("Make cargo clippy pass");
+        assert!(skills.contains(&"rust"));
+        assert!(skills.contains(&"code-quality"));
+    }
+
+    #[test]
+    fn test_get_recommended_skills_testing() {
+        let skills = get_recommended_skills("write tests");
+        assert!(skills.contains(&"testing"));
+        assert!(skills.contains(&"code-quality"));
+
+        let skills = get_recommended_skills("Add a unit test with a mock client");
+        assert_eq!(skills[0], "testing");
+    }
+
+    #[test]
+    fn test_get_recommended_skills_docker() {
+        let skills = get_recommended_skills("Write a Dockerfile for the API");
+        assert!(skills.contains(&"docker"));
+
+        let skills = get_recommended_skills("Add a compose file to run the container locally");
+        assert_eq!(skills[0], "docker");
+    }
+
+    #[test]
+    fn test_get_recommended_skills_anti_keywords_suppress() {
+        // "env" alone would match security.
+        assert!(!get_recommended_skills("Set up the dev env").contains(&"security"));
+        assert!(
+            !get_recommended_skills("Environment setup for the new laptop").contains(&"security")
+        );
+
+        // A positive match without an anti-keyword still counts.
+        assert!(get_recommended_skills("Read the token from an env var").contains(&"security"));
+
+        // Suppression drops the skill even with several positive matches.
+        assert!(
+            !get_recommended_skills("Configure the auth token in the dev env")
+                .contains(&"security")
+        );
+    }
+
+    #[test]
+    fn test_skill_info_new_defaults() {
+        const DEPLOY: SkillInfo = SkillInfo::new("deploy", "Deployments", &["deploy"]);
+        assert!(DEPLOY.anti_keywords.is_empty());
+        assert_eq!(DEPLOY.priority, u8::MAX);
+
+        let deploy = DEPLOY.with_anti_keywords(&["undeploy"]).with_priority(3);
+        assert_eq!(deploy.anti_keywords, &["undeploy"]);
+        assert_eq!(deploy.priority, 3);
+    }
+
     #[test]
     fn test_get_recommended_skills_planning() {
         let skills = get_recommended_skills("Design the new architecture");
@@ -575,6 +855,32 @@ mod tests {
         }
     }
 
+    // =========================================================================
+    // Skill Summary Tests
+    // =========================================================================
+
+    #[test]
+    fn test_summarize_loaded_skills_empty() {
+        assert_eq!(summarize_loaded_skills(&[]), "no skills loaded");
+    }
+
+    #[test]
+    fn test_summarize_loaded_skills_single() {
+        assert_eq!(summarize_loaded_skills(&["git"]), "skills: git (1 loaded)");
+    }
+
+    #[test]
+    fn test_summarize_loaded_skills_drops_invalid() {
+        let result = summarize_loaded_skills(&["git", "not-a-skill", "debugging", "security"]);
+        assert_eq!(result, "skills: git, debugging, security (3 loaded)");
+        assert!(!result.contains("not-a-skill"));
+    }
+
+    #[test]
+    fn test_summarize_loaded_skills_only_invalid() {
+        assert_eq!(summarize_loaded_skills(&["bogus"]), "no skills loaded");
+    }
+
     // =========================================================================
     // Validation Tests
     // =========================================================================
@@ -619,7 +925,7 @@ mod tests {
 
     #[test]
     fn test_available_skills_count() {
-        assert_eq!(AVAILABLE_SKILLS.len(), 6);
+        assert_eq!(AVAILABLE_SKILLS.len(), 9);
     }
 
     #[test]
@@ -634,6 +940,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_skill_priorities_are_unique() {
+        let mut priorities: Vec<u8> = SKILL_METADATA.iter().map(|s| s.priority).collect();
+        priorities.sort_unstable();
+        priorities.dedup();
+        assert_eq!(priorities.len(), SKILL_METADATA.len());
+
+        let mut by_priority: Vec<&str> = AVAILABLE_SKILLS.to_vec();
+        by_priority.sort_by_key(|s| skill_priority(s));
+        assert_eq!(by_priority.first(), Some(&"planning"));
+        assert_eq!(by_priority.last(), Some(&"security"));
+        assert_eq!(skill_priority("Planning"), skill_priority("planning"));
+    }
+
+    #[test]
+    fn test_base_prompt_skill_table_matches_available() {
+        assert_eq!(base_prompt_skill_names(), AVAILABLE_SKILLS);
+    }
+
+    #[test]
+    fn test_base_prompt_loading_examples_use_available_skills() {
+        for line in CORTEX_BASE_PROMPT.lines() {
+            let Some((_, rest)) = line.split_once("load_skill([\"") else {
+                continue;
+            };
+            let list = rest.split_once("\"])").map_or(rest, |(list, _)| list);
+            for name in list.split("\", \"") {
+                assert!(
+                    AVAILABLE_SKILLS.contains(&name),
+                    "Base prompt example references unknown skill: {}",
+                    name
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_skill_metadata_has_keywords() {
         for skill in SKILL_METADATA {
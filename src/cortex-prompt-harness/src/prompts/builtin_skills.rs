@@ -27,6 +27,16 @@
 //! - `description`: Brief description of when to use the skill
 //! - `version`: Semantic version for tracking changes
 //! - `tags`: Categories for organization and discovery
+//!
+//! # Custom Skills
+//!
+//! [`SkillRegistry`] holds the built-ins plus skills registered at runtime,
+//! e.g. project-local skills, behind the same lookup API.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 /// List of all available built-in skill names.
 pub const BUILTIN_SKILL_NAMES: &[&str] = &[
@@ -36,6 +46,9 @@ pub const BUILTIN_SKILL_NAMES: &[&str] = &[
     "debugging",
     "security",
     "planning",
+    "rust",
+    "docker",
+    "testing",
 ];
 
 /// Git operations skill - version control best practices.
@@ -317,6 +330,8 @@ go test -v ./...
 ```
 
 ### Test Quality Rules
+For test design (fixtures, mocking, property-based tests), load the `testing` skill.
+
 ```
 EVERY feature should have tests
 EVERY bug fix should have a regression test
@@ -1288,6 +1303,335 @@ XL (4+ hours): Major refactor, new system
 ```
 "#;
 
+/// Rust skill - cargo workflow and idiomatic Rust.
+///
+/// Load this skill when building, testing, or changing Rust code,
+/// or when working through borrow checker and lifetime errors.
+pub const SKILL_RUST: &str = r#"---
+name: rust
+description: Cargo workflow, clippy, error handling, and ownership idioms. Load when working in Rust code.
+version: "1.0.0"
+tags: [builtin, rust, language]
+---
+
+# Rust Skill
+
+## When to Use
+Load this skill when:
+- Working in a repository with a `Cargo.toml`
+- Building, testing, or linting with cargo
+- Fixing borrow checker or lifetime errors
+- Designing error types and `Result` flows
+- Adding or organizing tests
+
+## Cargo Workflow
+
+```
+cargo build                      # Compile; fix errors before anything else
+cargo test                       # Run unit, integration, and doc tests
+cargo clippy --all-targets -- -D warnings
+cargo fmt                        # Match the project's formatting
+```
+
+### In a Workspace
+```
+- Run from the directory with the workspace Cargo.toml
+- Use -p <crate> to focus on one crate while iterating
+- Finish with --workspace so dependents still build
+- Never edit Cargo.lock by hand
+```
+
+### Before Reporting Done
+```
+☐ cargo build passes
+☐ cargo clippy reports no new warnings
+☐ cargo test passes
+☐ cargo fmt leaves no diff
+```
+
+## Error Handling
+
+### Propagate With `?`
+```rust
+fn load(path: &Path) -> Result<Config, ConfigError> {
+    let text = std::fs::read_to_string(path)?;
+    let config = toml::from_str(&text)?;
+    Ok(config)
+}
+```
+
+### Rules
+```
+- Return Result for anything that can fail; reserve panics for bugs
+- No unwrap()/expect() in library code paths unless the invariant is proven
+- Reuse the crate's existing error type (thiserror enum, anyhow, ...)
+- Add context where the error is produced, not at every call site
+- Never silently discard a Result; handle it or explain with `let _ =`
+```
+
+## Ownership Pitfalls
+
+```
+- Borrow (&T / &mut T) by default; take ownership only when storing the value
+- Prefer &str over &String and &[T] over &Vec<T> in parameters
+- Don't clone to silence the borrow checker; restructure the borrow first
+- Keep mutable borrows short; end them before calling other &self methods
+- Returning references ties lifetimes to inputs; return owned data if unsure
+- Arc<Mutex<T>> for shared mutable state across threads; Rc<RefCell<T>> only in single-threaded code
+```
+
+### Common Borrow Checker Errors
+```
+E0502 (mutable and immutable borrow): split the borrow, or copy the needed value out first
+E0505 (move out while borrowed): reorder so the borrow ends before the move
+E0499 (two mutable borrows): use indices, split_at_mut, or separate scopes
+E0106 (missing lifetime): name the input the output borrows from
+```
+
+## Test Layout
+
+```rust
+pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        assert_eq!(add(2, 2), 4);
+    }
+}
+```
+
+```
+- Unit tests: #[cfg(test)] mod tests at the bottom of the file they test
+- Integration tests: tests/*.rs, using only the public API
+- Doc tests: examples in /// comments must compile and pass
+- Follow the crate's existing test naming and placement
+```
+"#;
+
+/// Docker skill - container images and compose setups.
+///
+/// Load this skill when writing Dockerfiles, compose files, or
+/// otherwise packaging and running software in containers.
+pub const SKILL_DOCKER: &str = r#"---
+name: docker
+description: Dockerfiles, multi-stage builds, layer caching, and compose setups. Load when containerizing software.
+version: "1.0.0"
+tags: [builtin, docker, containers]
+---
+
+# Docker Skill
+
+## When to Use
+Load this skill when:
+- Writing or reviewing a `Dockerfile`
+- Setting up `docker-compose.yml` / `compose.yaml` services
+- Shrinking image size or speeding up image builds
+- Hardening a container to run without root
+- Debugging a build that works locally but not in a container
+
+## Multi-Stage Builds
+
+Build in one stage, ship only the artifacts in another:
+
+```dockerfile
+FROM rust:1.85 AS build
+WORKDIR /src
+COPY . .
+RUN cargo build --release
+
+FROM debian:bookworm-slim
+COPY --from=build /src/target/release/app /usr/local/bin/app
+ENTRYPOINT ["app"]
+```
+
+```
+- Compilers, dev headers, and caches stay in the build stage
+- The runtime stage starts from a slim or distroless base
+- Name stages (AS build) and copy from them by name
+- Pin base images to a version tag, never just latest
+```
+
+## Layer Caching
+
+Order instructions from least to most frequently changed:
+
+```dockerfile
+COPY package.json package-lock.json ./
+RUN npm ci
+COPY . .
+RUN npm run build
+```
+
+```
+- Copy dependency manifests and install before copying the source
+- Combine apt-get update and install in one RUN, then clean the lists
+- Each RUN, COPY, and ADD creates a layer; deleting files later does not shrink the image
+- Prefer COPY over ADD unless extracting a local archive
+- Use BuildKit cache mounts (RUN --mount=type=cache) for package caches
+```
+
+## Non-Root Users
+
+```dockerfile
+RUN useradd --system --uid 10001 app
+USER app
+```
+
+```
+- Create a dedicated user and switch to it before ENTRYPOINT
+- COPY --chown=app:app files the process needs to write
+- Listen on ports above 1024 so no extra capabilities are needed
+- Never bake secrets into the image; pass them at runtime
+```
+
+## .dockerignore
+
+Keep the build context small and free of secrets:
+
+```
+.git
+target/
+node_modules/
+*.log
+.env
+```
+
+```
+- Add a .dockerignore next to every Dockerfile
+- Exclude build output, dependency folders, and VCS metadata
+- Exclude .env files and credentials so COPY . . cannot leak them
+```
+
+## Compose
+
+```
+- One service per process; connect them through service names, not localhost
+- Use named volumes for data that must survive restarts
+- Read configuration from environment variables or env_file
+- Add healthchecks and depends_on: condition: service_healthy for startup order
+- Verify with docker compose config before running
+```
+"#;
+
+/// Testing skill - test design, fixtures, and mocking.
+///
+/// Load this skill when a task is about writing or restructuring
+/// tests rather than general code quality.
+pub const SKILL_TESTING: &str = r#"---
+name: testing
+description: Test structure, fixtures, mocking, property-based testing, and coverage. Load when writing tests.
+version: "1.0.0"
+tags: [builtin, testing]
+---
+
+# Testing Skill
+
+## When to Use
+Load this skill when:
+- Writing unit or integration tests for new or existing code
+- Adding a regression test for a bug fix
+- Introducing fixtures, mocks, or test doubles
+- Tests are flaky, slow, or hard to read
+- Closing gaps reported by a coverage tool
+
+## Test Structure
+
+### Arrange, Act, Assert
+```python
+def test_discount_applies_to_large_orders():
+    order = Order(total=200)          # Arrange
+
+    price = apply_discount(order)     # Act
+
+    assert price == 180               # Assert
+```
+
+### Rules
+```
+- One behavior per test; name the test after that behavior
+- Put tests where the project already keeps them, using its naming scheme
+- Unit tests exercise one module; integration tests cross module or process boundaries
+- Assert on observable results, not on private implementation details
+- Prefer specific assertions (assert_eq, toEqual) over bare truthiness checks
+- Tests must be deterministic: no real clocks, random seeds, or network by default
+```
+
+## Fixtures
+
+```
+- Build test data with small helpers or factories, not copy-pasted literals
+- Keep each fixture minimal; only set the fields the test depends on
+- Create temporary files and directories in a temp dir that is cleaned up
+- Never share mutable state between tests; each test sets up its own
+- Reuse the framework's fixture mechanism (pytest fixtures, beforeEach, helper fns)
+```
+
+## Mocking
+
+```
+- Mock at the boundary: network, filesystem, clock, external services
+- Don't mock the code under test or simple value types
+- Prefer fakes (in-memory implementations) over deep mock call chains
+- Verify interactions only when the interaction is the behavior
+- Inject dependencies (traits, interfaces, parameters) so tests can swap them
+```
+
+## Property-Based Testing
+
+Check an invariant over many generated inputs instead of a few examples:
+
+```rust
+proptest! {
+    #[test]
+    fn roundtrip(s in ".*") {
+        assert_eq!(decode(&encode(&s)), s);
+    }
+}
+```
+
+```
+- Good properties: roundtrips, idempotence, ordering, invariants preserved
+- Keep the failing case the framework shrinks to as a regular regression test
+- Only add a property-testing library if the project already uses one
+```
+
+## Coverage
+
+```bash
+# Python
+pytest --cov
+
+# JavaScript/TypeScript
+npx jest --coverage
+
+# Rust
+cargo llvm-cov
+
+# Go
+go test -cover ./...
+```
+
+```
+- Use coverage to find untested branches, not as a target to game
+- Cover error paths and edge cases (empty, zero, maximum, invalid input)
+- A covered line is not a tested line; every test needs a meaningful assert
+```
+
+## Before Reporting Done
+```
+☐ New tests fail without the change and pass with it
+☐ The full test suite passes
+☐ No test depends on execution order
+☐ Test names describe the behavior under test
+```
+"#;
+
 /// Retrieve a built-in skill by name.
 ///
 /// # Arguments
@@ -1318,10 +1662,53 @@ pub fn get_builtin_skill(name: &str) -> Option<&'static str> {
         "debugging" => Some(SKILL_DEBUGGING),
         "security" => Some(SKILL_SECURITY),
         "planning" => Some(SKILL_PLANNING),
+        "rust" => Some(SKILL_RUST),
+        "docker" => Some(SKILL_DOCKER),
+        "testing" => Some(SKILL_TESTING),
         _ => None,
     }
 }
 
+/// `(name, description)` of each built-in skill, in [`BUILTIN_SKILL_NAMES`] order.
+const BUILTIN_SKILLS: &[(&str, &str)] = &[
+    (
+        "git",
+        "Git version control operations, commits, PRs, branches. Load when doing version control tasks.",
+    ),
+    (
+        "code-quality",
+        "Code quality standards, linting, testing, and style matching. Load when ensuring code quality.",
+    ),
+    (
+        "file-operations",
+        "Safe file operations, read-before-write patterns, and rollback strategies. Load when modifying files.",
+    ),
+    (
+        "debugging",
+        "Systematic debugging, error handling, and failure recovery. Load when troubleshooting issues.",
+    ),
+    (
+        "security",
+        "Secure coding practices, secrets handling, and input validation. Load when handling sensitive data.",
+    ),
+    (
+        "planning",
+        "Task decomposition, cognitive architecture, and systematic execution. Load for complex multi-step tasks.",
+    ),
+    (
+        "rust",
+        "Cargo workflow, clippy, error handling, and ownership idioms. Load when working in Rust code.",
+    ),
+    (
+        "docker",
+        "Dockerfiles, multi-stage builds, layer caching, and compose setups. Load when containerizing software.",
+    ),
+    (
+        "testing",
+        "Test structure, fixtures, mocking, property-based testing, and coverage. Load when writing tests.",
+    ),
+];
+
 /// List all built-in skills with their names and descriptions.
 ///
 /// # Returns
@@ -1335,39 +1722,33 @@ pub fn get_builtin_skill(name: &str) -> Option<&'static str> {
 /// use cortex_prompt_harness::prompts::builtin_skills::list_builtin_skills;
 ///
 /// let skills = list_builtin_skills();
-/// assert_eq!(skills.len(), 6);
+/// assert_eq!(skills.len(), 9);
 ///
 /// for (name, description) in skills {
 ///     println!("{}: {}", name, description);
 /// }
 /// ```
 pub fn list_builtin_skills() -> Vec<(&'static str, &'static str)> {
-    vec![
-        (
-            "git",
-            "Git version control operations, commits, PRs, branches. Load when doing version control tasks.",
-        ),
-        (
-            "code-quality",
-            "Code quality standards, linting, testing, and style matching. Load when ensuring code quality.",
-        ),
-        (
-            "file-operations",
-            "Safe file operations, read-before-write patterns, and rollback strategies. Load when modifying files.",
-        ),
-        (
-            "debugging",
-            "Systematic debugging, error handling, and failure recovery. Load when troubleshooting issues.",
-        ),
-        (
-            "security",
-            "Secure coding practices, secrets handling, and input validation. Load when handling sensitive data.",
-        ),
-        (
-            "planning",
-            "Task decomposition, cognitive architecture, and systematic execution. Load for complex multi-step tasks.",
-        ),
-    ]
+    iter_builtin_skills().collect()
+}
+
+/// Iterate over the built-in skills as `(name, description)` pairs without
+/// allocating.
+///
+/// # Example
+///
+/// ```rust
+/// use cortex_prompt_harness::prompts::builtin_skills::iter_builtin_skills;
+///
+/// assert!(iter_builtin_skills().any(|(name, _)| name == "git"));
+/// ```
+pub fn iter_builtin_skills() -> impl Iterator<Item = (&'static str, &'static str)> {
+    BUILTIN_SKILLS.iter().copied()
+}
+
+/// Iterate over the built-in skill names without allocating.
+pub fn iter_skill_names() -> impl Iterator<Item = &'static str> {
+    BUILTIN_SKILL_NAMES.iter().copied()
 }
 
 /// Get the total count of built-in skills.
@@ -1381,7 +1762,7 @@ pub fn list_builtin_skills() -> Vec<(&'static str, &'static str)> {
 /// ```rust
 /// use cortex_prompt_harness::prompts::builtin_skills::builtin_skill_count;
 ///
-/// assert_eq!(builtin_skill_count(), 6);
+/// assert_eq!(builtin_skill_count(), 9);
 /// ```
 pub fn builtin_skill_count() -> usize {
     BUILTIN_SKILL_NAMES.len()
@@ -1410,13 +1791,319 @@ pub fn is_builtin_skill(name: &str) -> bool {
     get_builtin_skill(name).is_some()
 }
 
+/// Structured metadata parsed from a skill's YAML frontmatter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillMeta {
+    /// Unique identifier for the skill.
+    pub name: String,
+    /// Brief description of when to use the skill.
+    pub description: String,
+    /// Semantic version of the skill content.
+    pub version: String,
+    /// Categories for organization and discovery.
+    pub tags: Vec<String>,
+}
+
+impl SkillMeta {
+    /// Parse the frontmatter at the start of a skill.
+    ///
+    /// Returns `None` if the content has no frontmatter or no `name` field.
+    /// Missing `description`/`version` fields are left empty.
+    #[must_use]
+    pub fn parse(content: &str) -> Option<Self> {
+        let (frontmatter, _) = split_frontmatter(content).ok()?;
+
+        let mut name = None;
+        let mut meta = Self {
+            name: String::new(),
+            description: String::new(),
+            version: String::new(),
+            tags: Vec::new(),
+        };
+        for line in frontmatter.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = unquote(value.trim());
+            match key.trim() {
+                "name" => name = Some(value.to_string()),
+                "description" => meta.description = value.to_string(),
+                "version" => meta.version = value.to_string(),
+                "tags" => {
+                    meta.tags = value
+                        .trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .split(',')
+                        .map(|tag| unquote(tag.trim()).to_string())
+                        .filter(|tag| !tag.is_empty())
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+        meta.name = name?;
+        Some(meta)
+    }
+}
+
+/// Frontmatter fields every skill must define.
+const REQUIRED_FRONTMATTER_FIELDS: &[&str] = &["name", "description", "version", "tags"];
+
+/// A structural problem found by [`validate_skill_content`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SkillValidationError {
+    #[error("skill must start with a `---` frontmatter fence")]
+    MissingFrontmatter,
+    #[error("skill frontmatter has no closing `---` fence")]
+    UnterminatedFrontmatter,
+    #[error("skill frontmatter is missing the `{0}` field")]
+    MissingField(&'static str),
+    #[error("skill is missing a `## When to Use` section")]
+    MissingWhenToUse,
+}
+
+/// Split a skill into its frontmatter and body.
+fn split_frontmatter(content: &str) -> Result<(&str, &str), SkillValidationError> {
+    let rest = content
+        .strip_prefix("---\n")
+        .ok_or(SkillValidationError::MissingFrontmatter)?;
+    let end = rest
+        .find("\n---")
+        .ok_or(SkillValidationError::UnterminatedFrontmatter)?;
+    Ok((&rest[..end], &rest[end + "\n---".len()..]))
+}
+
+/// Check that skill content has the structure the built-in skills follow.
+///
+/// A valid skill starts with a `---` frontmatter block defining `name`,
+/// `description`, `version` and `tags`, and its body has a
+/// `## When to Use` section. Use this to gate custom skills before
+/// registering them.
+///
+/// # Example
+///
+/// ```rust
+/// use cortex_prompt_harness::prompts::builtin_skills::{
+///     SKILL_GIT, SkillValidationError, validate_skill_content,
+/// };
+///
+/// assert!(validate_skill_content(SKILL_GIT).is_ok());
+/// assert_eq!(
+///     validate_skill_content("# Deploy"),
+///     Err(SkillValidationError::MissingFrontmatter)
+/// );
+/// ```
+pub fn validate_skill_content(content: &str) -> Result<(), SkillValidationError> {
+    let (frontmatter, body) = split_frontmatter(content)?;
+
+    for &field in REQUIRED_FRONTMATTER_FIELDS {
+        let present = frontmatter.lines().any(|line| {
+            line.split_once(':')
+                .is_some_and(|(key, _)| key.trim() == field)
+        });
+        if !present {
+            return Err(SkillValidationError::MissingField(field));
+        }
+    }
+
+    if !body.lines().any(|line| line.trim_end() == "## When to Use") {
+        return Err(SkillValidationError::MissingWhenToUse);
+    }
+    Ok(())
+}
+
+/// Strip one pair of matching surrounding quotes from a YAML scalar.
+fn unquote(value: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|&q| value.strip_prefix(q)?.strip_suffix(q))
+        .unwrap_or(value)
+}
+
+/// Get the frontmatter metadata of a built-in skill.
+///
+/// # Arguments
+///
+/// * `name` - The name of the skill (case-insensitive)
+///
+/// # Example
+///
+/// ```rust
+/// use cortex_prompt_harness::prompts::builtin_skills::get_skill_metadata;
+///
+/// let meta = get_skill_metadata("git").unwrap();
+/// assert_eq!(meta.version, "1.0.0");
+/// assert!(meta.tags.iter().any(|tag| tag == "vcs"));
+/// ```
+#[must_use]
+pub fn get_skill_metadata(name: &str) -> Option<SkillMeta> {
+    SkillMeta::parse(get_builtin_skill(name)?)
+}
+
+/// Get the frontmatter metadata of every built-in skill, in
+/// [`BUILTIN_SKILL_NAMES`] order.
+#[must_use]
+pub fn list_skill_metadata() -> Vec<SkillMeta> {
+    BUILTIN_SKILL_NAMES
+        .iter()
+        .filter_map(|name| get_skill_metadata(name))
+        .collect()
+}
+
+/// Find the built-in skills whose frontmatter tags include `tag`.
+///
+/// Tags are compared case-insensitively; names are returned in
+/// [`BUILTIN_SKILL_NAMES`] order.
+///
+/// # Example
+///
+/// ```rust
+/// use cortex_prompt_harness::prompts::builtin_skills::find_skills_by_tag;
+///
+/// assert_eq!(find_skills_by_tag("safety"), vec!["file-operations"]);
+/// ```
+#[must_use]
+pub fn find_skills_by_tag(tag: &str) -> Vec<&'static str> {
+    BUILTIN_SKILL_NAMES
+        .iter()
+        .copied()
+        .filter(|name| {
+            get_skill_metadata(name)
+                .is_some_and(|meta| meta.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+        })
+        .collect()
+}
+
+/// Skills available to an agent: the built-ins plus any registered at runtime.
+///
+/// Names are case-insensitive. Registering a skill under a built-in name
+/// overrides the built-in content.
+///
+/// # Example
+///
+/// ```rust
+/// use cortex_prompt_harness::prompts::builtin_skills::SkillRegistry;
+///
+/// let mut registry = SkillRegistry::new();
+/// registry.register_skill("deploy", "# Deploy Skill\n\nUse `make deploy`.");
+/// registry.set_keywords("deploy", ["deploy", "release"]);
+///
+/// assert!(registry.get("git").is_some());
+/// assert!(registry.get("Deploy").unwrap().contains("make deploy"));
+/// assert!(registry.recommended_skills("Release v2").contains(&"deploy".to_string()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SkillRegistry {
+    /// Skill content by lowercase name.
+    skills: HashMap<String, String>,
+    /// Recommendation keywords for runtime-registered skills, by lowercase name.
+    keywords: HashMap<String, Vec<String>>,
+}
+
+impl Default for SkillRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SkillRegistry {
+    /// Create a registry seeded with the built-in skills.
+    #[must_use]
+    pub fn new() -> Self {
+        let skills = BUILTIN_SKILL_NAMES
+            .iter()
+            .filter_map(|name| Some((name.to_string(), get_builtin_skill(name)?.to_string())))
+            .collect();
+        Self {
+            skills,
+            keywords: HashMap::new(),
+        }
+    }
+
+    /// Register (or replace) a skill.
+    pub fn register_skill(&mut self, name: impl Into<String>, content: impl Into<String>) {
+        self.skills
+            .insert(name.into().to_lowercase(), content.into());
+    }
+
+    /// Set the keywords that make [`recommended_skills`](Self::recommended_skills)
+    /// suggest a skill. Keywords are matched case-insensitively as substrings.
+    pub fn set_keywords(
+        &mut self,
+        name: &str,
+        keywords: impl IntoIterator<Item = impl Into<String>>,
+    ) {
+        let keywords = keywords
+            .into_iter()
+            .map(|k| k.into().to_lowercase())
+            .collect();
+        self.keywords.insert(name.to_lowercase(), keywords);
+    }
+
+    /// Get a skill's content, falling back to the static built-ins.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.skills
+            .get(&name.to_lowercase())
+            .map(String::as_str)
+            .or_else(|| get_builtin_skill(name))
+    }
+
+    /// Check whether a skill is available.
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// All skill names: built-ins first in their usual order, then
+    /// runtime-registered skills sorted by name.
+    #[must_use]
+    pub fn names(&self) -> Vec<&str> {
+        let mut custom: Vec<&str> = self
+            .skills
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !is_builtin_skill(name))
+            .collect();
+        custom.sort_unstable();
+
+        BUILTIN_SKILL_NAMES.iter().copied().chain(custom).collect()
+    }
+
+    /// Recommend skills for a task.
+    ///
+    /// Built-in recommendations come from
+    /// [`get_recommended_skills`](super::base_agent::get_recommended_skills);
+    /// runtime-registered skills are recommended when one of their keywords
+    /// appears in the task.
+    #[must_use]
+    pub fn recommended_skills(&self, task: &str) -> Vec<String> {
+        let mut recommended: Vec<String> = super::base_agent::get_recommended_skills(task)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+
+        let task_lower = task.to_lowercase();
+        for name in self.names() {
+            let matched = self
+                .keywords
+                .get(name)
+                .is_some_and(|keywords| keywords.iter().any(|k| task_lower.contains(k.as_str())));
+            if matched && !recommended.iter().any(|r| r == name) {
+                recommended.push(name.to_string());
+            }
+        }
+        recommended
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_builtin_skill_names_count() {
-        assert_eq!(BUILTIN_SKILL_NAMES.len(), 6);
+        assert_eq!(BUILTIN_SKILL_NAMES.len(), 9);
     }
 
     #[test]
@@ -1427,6 +2114,9 @@ mod tests {
         assert!(BUILTIN_SKILL_NAMES.contains(&"debugging"));
         assert!(BUILTIN_SKILL_NAMES.contains(&"security"));
         assert!(BUILTIN_SKILL_NAMES.contains(&"planning"));
+        assert!(BUILTIN_SKILL_NAMES.contains(&"rust"));
+        assert!(BUILTIN_SKILL_NAMES.contains(&"docker"));
+        assert!(BUILTIN_SKILL_NAMES.contains(&"testing"));
     }
 
     #[test]
@@ -1505,6 +2195,48 @@ mod tests {
         assert!(content.contains("CLOSE"));
     }
 
+    #[test]
+    fn test_get_builtin_skill_rust() {
+        let skill = get_builtin_skill("rust");
+        assert!(skill.is_some());
+        let content = skill.unwrap();
+        assert!(content.contains("name: rust"));
+        assert!(content.contains("Rust Skill"));
+        assert!(content.contains("Cargo Workflow"));
+        assert!(content.contains("cargo clippy"));
+        assert!(content.contains("Error Handling"));
+        assert!(content.contains("Ownership Pitfalls"));
+        assert!(content.contains("#[cfg(test)]"));
+    }
+
+    #[test]
+    fn test_get_builtin_skill_docker() {
+        let skill = get_builtin_skill("docker");
+        assert!(skill.is_some());
+        let content = skill.unwrap();
+        assert!(content.contains("name: docker"));
+        assert!(content.contains("Docker Skill"));
+        assert!(content.contains("Multi-Stage Builds"));
+        assert!(content.contains("Layer Caching"));
+        assert!(content.contains("Non-Root Users"));
+        assert!(content.contains(".dockerignore"));
+        assert!(validate_skill_content(content).is_ok());
+    }
+
+    #[test]
+    fn test_get_builtin_skill_testing() {
+        let skill = get_builtin_skill("testing");
+        assert!(skill.is_some());
+        let content = skill.unwrap();
+        assert!(content.contains("name: testing"));
+        assert!(content.contains("Testing Skill"));
+        assert!(content.contains("Fixtures"));
+        assert!(content.contains("Mocking"));
+        assert!(content.contains("Property-Based Testing"));
+        assert!(content.contains("Coverage"));
+        assert!(validate_skill_content(content).is_ok());
+    }
+
     #[test]
     fn test_get_builtin_skill_case_insensitive() {
         assert!(get_builtin_skill("git").is_some());
@@ -1524,7 +2256,7 @@ mod tests {
     #[test]
     fn test_list_builtin_skills() {
         let skills = list_builtin_skills();
-        assert_eq!(skills.len(), 6);
+        assert_eq!(skills.len(), 9);
 
         let names: Vec<&str> = skills.iter().map(|(name, _)| *name).collect();
         assert!(names.contains(&"git"));
@@ -1533,6 +2265,9 @@ mod tests {
         assert!(names.contains(&"debugging"));
         assert!(names.contains(&"security"));
         assert!(names.contains(&"planning"));
+        assert!(names.contains(&"rust"));
+        assert!(names.contains(&"docker"));
+        assert!(names.contains(&"testing"));
 
         // Check all descriptions are non-empty
         for (_, description) in &skills {
@@ -1540,9 +2275,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_iter_builtin_skills_matches_list() {
+        let listed = list_builtin_skills();
+        assert_eq!(iter_builtin_skills().collect::<Vec<_>>(), listed);
+        assert_eq!(iter_builtin_skills().count(), builtin_skill_count());
+
+        let names: Vec<&str> = iter_skill_names().collect();
+        assert_eq!(names, BUILTIN_SKILL_NAMES);
+        assert!(
+            names
+                .iter()
+                .zip(&listed)
+                .all(|(name, (listed_name, _))| name == listed_name)
+        );
+    }
+
     #[test]
     fn test_builtin_skill_count() {
-        assert_eq!(builtin_skill_count(), 6);
+        assert_eq!(builtin_skill_count(), 9);
         assert_eq!(builtin_skill_count(), BUILTIN_SKILL_NAMES.len());
     }
 
@@ -1555,12 +2306,54 @@ mod tests {
         assert!(is_builtin_skill("debugging"));
         assert!(is_builtin_skill("security"));
         assert!(is_builtin_skill("planning"));
+        assert!(is_builtin_skill("rust"));
+        assert!(is_builtin_skill("docker"));
+        assert!(is_builtin_skill("testing"));
 
         assert!(!is_builtin_skill("nonexistent"));
         assert!(!is_builtin_skill(""));
         assert!(!is_builtin_skill("custom"));
     }
 
+    #[test]
+    fn test_skill_registry_seeded_with_builtins() {
+        let registry = SkillRegistry::new();
+        for name in BUILTIN_SKILL_NAMES {
+            assert_eq!(registry.get(name), get_builtin_skill(name));
+        }
+        assert_eq!(registry.names(), BUILTIN_SKILL_NAMES);
+        assert!(registry.get("deploy").is_none());
+    }
+
+    #[test]
+    fn test_skill_registry_register_and_override() {
+        let mut registry = SkillRegistry::new();
+        registry.register_skill("Deploy", "# Deploy Skill");
+        registry.register_skill("git", "# Team Git Rules");
+
+        assert_eq!(registry.get("deploy"), Some("# Deploy Skill"));
+        assert_eq!(registry.get("GIT"), Some("# Team Git Rules"));
+        assert!(registry.contains("DEPLOY"));
+        assert_eq!(registry.names().last(), Some(&"deploy"));
+    }
+
+    #[test]
+    fn test_skill_registry_recommends_custom_skills() {
+        let mut registry = SkillRegistry::new();
+        registry.register_skill("deploy", "# Deploy Skill");
+        registry.set_keywords("deploy", ["Deploy", "release"]);
+
+        let recommended = registry.recommended_skills("Commit and deploy to staging");
+        assert!(recommended.contains(&"git".to_string()));
+        assert!(recommended.contains(&"deploy".to_string()));
+
+        assert!(
+            !registry
+                .recommended_skills("Rename a variable")
+                .contains(&"deploy".to_string())
+        );
+    }
+
     #[test]
     fn test_skill_yaml_frontmatter_format() {
         // All skills should have proper YAML frontmatter
@@ -1571,6 +2364,9 @@ mod tests {
             SKILL_DEBUGGING,
             SKILL_SECURITY,
             SKILL_PLANNING,
+            SKILL_RUST,
+            SKILL_DOCKER,
+            SKILL_TESTING,
         ];
 
         for skill in skills {
@@ -1605,6 +2401,9 @@ mod tests {
             SKILL_DEBUGGING,
             SKILL_SECURITY,
             SKILL_PLANNING,
+            SKILL_RUST,
+            SKILL_DOCKER,
+            SKILL_TESTING,
         ];
 
         for skill in skills {
@@ -1628,6 +2427,127 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_skill_metadata_git() {
+        let meta = get_skill_metadata("Git").unwrap();
+        assert_eq!(meta.name, "git");
+        assert_eq!(meta.version, "1.0.0");
+        assert_eq!(meta.tags, vec!["builtin", "vcs", "git"]);
+        assert!(
+            meta.description
+                .starts_with("Git version control operations")
+        );
+
+        assert!(get_skill_metadata("nonexistent").is_none());
+    }
+
+    #[test]
+    fn test_list_skill_metadata_matches_builtins() {
+        let metadata = list_skill_metadata();
+        assert_eq!(metadata.len(), builtin_skill_count());
+
+        for (meta, (name, description)) in metadata.iter().zip(list_builtin_skills()) {
+            assert_eq!(meta.name, name);
+            assert_eq!(meta.description, description);
+            assert!(meta.tags.iter().any(|tag| tag == "builtin"));
+        }
+    }
+
+    #[test]
+    fn test_skill_meta_parse() {
+        let meta = SkillMeta::parse(
+            "---\nname: deploy\nversion: '2.1.0'\ntags: [\"ops\", release]\n---\n\n# Deploy",
+        )
+        .unwrap();
+        assert_eq!(meta.name, "deploy");
+        assert_eq!(meta.version, "2.1.0");
+        assert_eq!(meta.tags, vec!["ops", "release"]);
+        assert!(meta.description.is_empty());
+
+        assert!(SkillMeta::parse("# No frontmatter").is_none());
+        assert!(SkillMeta::parse("---\nversion: 1.0.0\n---\n").is_none());
+    }
+
+    #[test]
+    fn test_find_skills_by_tag() {
+        assert_eq!(find_skills_by_tag("safety"), vec!["file-operations"]);
+        assert_eq!(
+            find_skills_by_tag("testing"),
+            vec!["code-quality", "testing"]
+        );
+        assert_eq!(find_skills_by_tag("builtin").len(), builtin_skill_count());
+    }
+
+    #[test]
+    fn test_find_skills_by_tag_no_matches() {
+        assert!(find_skills_by_tag("kubernetes").is_empty());
+        assert!(find_skills_by_tag("").is_empty());
+    }
+
+    #[test]
+    fn test_find_skills_by_tag_case_insensitive() {
+        assert_eq!(find_skills_by_tag("VCS"), vec!["git"]);
+        assert_eq!(find_skills_by_tag("Safety"), find_skills_by_tag("safety"));
+    }
+
+    const VALID_SKILL: &str = "---\nname: deploy\ndescription: Release workflow.\nversion: \"1.0.0\"\ntags: [ops]\n---\n\n# Deploy Skill\n\n## When to Use\n\nWhen shipping.\n";
+
+    #[test]
+    fn test_validate_skill_content_valid() {
+        assert_eq!(validate_skill_content(VALID_SKILL), Ok(()));
+        for name in BUILTIN_SKILL_NAMES {
+            let skill = get_builtin_skill(name).unwrap();
+            assert_eq!(validate_skill_content(skill), Ok(()), "skill {}", name);
+        }
+    }
+
+    #[test]
+    fn test_validate_skill_content_frontmatter_fences() {
+        assert_eq!(
+            validate_skill_content("# Deploy Skill\n\n## When to Use\n"),
+            Err(SkillValidationError::MissingFrontmatter)
+        );
+        assert_eq!(
+            validate_skill_content(" ---\nname: deploy\n---\n"),
+            Err(SkillValidationError::MissingFrontmatter)
+        );
+        assert_eq!(
+            validate_skill_content("---\nname: deploy\n\n## When to Use\n"),
+            Err(SkillValidationError::UnterminatedFrontmatter)
+        );
+    }
+
+    #[test]
+    fn test_validate_skill_content_missing_fields() {
+        for field in REQUIRED_FRONTMATTER_FIELDS {
+            let content: String = VALID_SKILL
+                .lines()
+                .filter(|line| !line.starts_with(&format!("{field}:")))
+                .map(|line| format!("{line}\n"))
+                .collect();
+            assert_eq!(
+                validate_skill_content(&content),
+                Err(SkillValidationError::MissingField(*field))
+            );
+        }
+
+        // Fields only count inside the frontmatter.
+        let content = VALID_SKILL.replace("tags: [ops]\n---\n", "---\ntags: [ops]\n");
+        assert_eq!(
+            validate_skill_content(&content),
+            Err(SkillValidationError::MissingField("tags"))
+        );
+    }
+
+    #[test]
+    fn test_validate_skill_content_missing_when_to_use() {
+        let content = VALID_SKILL.replace("## When to Use", "## Usage");
+        assert_eq!(
+            validate_skill_content(&content),
+            Err(SkillValidationError::MissingWhenToUse)
+        );
+    }
+
     #[test]
     fn test_skill_tags_include_builtin() {
         let skills = list_builtin_skills();
@@ -26,6 +26,9 @@
 //!     .build();
 //! ```
 
+use cortex_common::model_presets::ModelPreset;
+use serde::{Deserialize, Serialize};
+
 // =============================================================================
 // Section Constants - Individual parts of the Cortex main prompt
 // =============================================================================
@@ -232,7 +235,7 @@ pub const SECTION_TOOLKIT: &str = r#"## 06 // TOOLKIT
 | `Fetch` | Get URL content |
 | `WebQuery` | Search the web |
 
-### Action  
+### Action
 | Tool | Function |
 |------|----------|
 | `Write` | Create new files |
@@ -252,6 +255,205 @@ pub const SECTION_TOOLKIT: &str = r#"## 06 // TOOLKIT
 | `UseSkill` | Invoke specialized skill |
 | `CreateAgent` | Define new agent |"#;
 
+/// Category a default tool is listed under in the TOOLKIT section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToolCategory {
+    /// Reading and discovering information.
+    Perception,
+    /// Changing files or running commands.
+    Action,
+    /// Planning and tracking work.
+    Cognition,
+    /// Working with other agents and skills.
+    Collaboration,
+}
+
+impl ToolCategory {
+    /// All categories in the order they appear in the TOOLKIT section.
+    pub const ALL: [ToolCategory; 4] = [
+        ToolCategory::Perception,
+        ToolCategory::Action,
+        ToolCategory::Cognition,
+        ToolCategory::Collaboration,
+    ];
+
+    /// Heading used for this category in the TOOLKIT section.
+    #[must_use]
+    pub fn title(self) -> &'static str {
+        match self {
+            ToolCategory::Perception => "Perception",
+            ToolCategory::Action => "Action",
+            ToolCategory::Cognition => "Cognition",
+            ToolCategory::Collaboration => "Collaboration",
+        }
+    }
+}
+
+/// The default Cortex tools: `(name, function, category)`.
+///
+/// This is the authoritative tool list; [`SECTION_TOOLKIT`] is its rendered
+/// form (see [`render_toolkit_section`]).
+pub const DEFAULT_TOOLS: &[(&str, &str, ToolCategory)] = &[
+    ("Read", "Read file contents", ToolCategory::Perception),
+    ("Tree", "Show directory structure", ToolCategory::Perception),
+    ("Search", "Regex search in files", ToolCategory::Perception),
+    (
+        "Find",
+        "Glob pattern file discovery",
+        ToolCategory::Perception,
+    ),
+    ("Fetch", "Get URL content", ToolCategory::Perception),
+    ("WebQuery", "Search the web", ToolCategory::Perception),
+    ("Write", "Create new files", ToolCategory::Action),
+    ("Patch", "Edit existing files", ToolCategory::Action),
+    ("Shell", "Run commands", ToolCategory::Action),
+    ("Plan", "Track task progress", ToolCategory::Cognition),
+    (
+        "Propose",
+        "Present plans for approval",
+        ToolCategory::Cognition,
+    ),
+    (
+        "Delegate",
+        "Send task to sub-agent",
+        ToolCategory::Collaboration,
+    ),
+    (
+        "UseSkill",
+        "Invoke specialized skill",
+        ToolCategory::Collaboration,
+    ),
+    (
+        "CreateAgent",
+        "Define new agent",
+        ToolCategory::Collaboration,
+    ),
+];
+
+/// Names of the default tools, in [`DEFAULT_TOOLS`] order.
+#[must_use]
+pub fn default_tool_names() -> Vec<&'static str> {
+    DEFAULT_TOOLS.iter().map(|(name, _, _)| *name).collect()
+}
+
+/// Render the TOOLKIT section from [`DEFAULT_TOOLS`].
+#[must_use]
+pub fn render_toolkit_section() -> String {
+    render_toolkit(|_| true)
+}
+
+/// Render the TOOLKIT section from the [`DEFAULT_TOOLS`] accepted by `keep`.
+///
+/// Categories left without any tool are omitted.
+fn render_toolkit(keep: impl Fn(&str) -> bool) -> String {
+    let mut blocks = vec![String::from("## 06 // TOOLKIT")];
+    for category in ToolCategory::ALL {
+        let mut tools = DEFAULT_TOOLS
+            .iter()
+            .filter(|(name, _, c)| *c == category && keep(*name))
+            .peekable();
+        if tools.peek().is_none() {
+            continue;
+        }
+        let mut block = format!(
+            "### {}\n| Tool | Function |\n|------|----------|",
+            category.title()
+        );
+        for (name, function, _) in tools {
+            block.push_str(&format!("\n| `{}` | {} |", name, function));
+        }
+        blocks.push(block);
+    }
+    blocks.join("\n\n")
+}
+
+/// A phase of the workflow described in the COGNITIVE ARCHITECTURE section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Understand before touching anything.
+    Recon,
+    /// Plan the attack.
+    Design,
+    /// Execute the plan.
+    Build,
+    /// Test everything.
+    Verify,
+    /// Wrap up.
+    Close,
+}
+
+impl Phase {
+    /// All phases in workflow order.
+    pub const ALL: [Phase; 5] = [
+        Phase::Recon,
+        Phase::Design,
+        Phase::Build,
+        Phase::Verify,
+        Phase::Close,
+    ];
+
+    /// Heading used for this phase in the COGNITIVE ARCHITECTURE section.
+    #[must_use]
+    pub fn title(self) -> &'static str {
+        match self {
+            Phase::Recon => "RECON",
+            Phase::Design => "DESIGN",
+            Phase::Build => "BUILD",
+            Phase::Verify => "VERIFY",
+            Phase::Close => "CLOSE",
+        }
+    }
+
+    /// Tools listed on this phase's `Tools:` line in
+    /// [`SECTION_COGNITIVE_ARCHITECTURE`].
+    #[must_use]
+    pub fn tools(self) -> Vec<&'static str> {
+        phase_block(SECTION_COGNITIVE_ARCHITECTURE, self)
+            .and_then(|block| block.lines().find_map(|line| line.strip_prefix("Tools:")))
+            .map(|tools| {
+                tools
+                    .split_whitespace()
+                    .map(|tool| tool.trim_matches('`'))
+                    .filter(|tool| !tool.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Extract the `### <PHASE>` block for `phase` from a COGNITIVE ARCHITECTURE
+/// section, up to the next `###` header.
+fn phase_block(section: &str, phase: Phase) -> Option<&str> {
+    let mut start = None;
+    let mut offset = 0;
+    for line in section.split_inclusive('\n') {
+        let header = line.trim_end();
+        match start {
+            Some(start) if header.starts_with("### ") => {
+                return Some(section[start..offset].trim_end());
+            }
+            None if header.strip_prefix("### ") == Some(phase.title()) => start = Some(offset),
+            _ => {}
+        }
+        offset += line.len();
+    }
+    start.map(|start| section[start..].trim_end())
+}
+
+/// Reduce a COGNITIVE ARCHITECTURE section to its header and the block for
+/// `phase`.
+fn phase_architecture_section(section: &str, phase: Phase) -> String {
+    let header = section.lines().next().unwrap_or_default();
+    let block = phase_block(section, phase).unwrap_or_default();
+    format!(
+        "{}\n\nYou are in the {} phase. Stay within it.\n\n{}",
+        header,
+        phase.title(),
+        block
+    )
+}
+
 /// Section 07: Response patterns - how to handle common requests.
 pub const SECTION_RESPONSE_PATTERNS: &str = r#"## 07 // RESPONSE PATTERNS
 
@@ -309,6 +511,53 @@ pub const SECTION_NAMES: &[&str] = &[
     "OUTPUT FORMAT",
 ];
 
+/// Separator [`CortexPromptBuilder::build`] places between sections.
+const SECTION_SEPARATOR: &str = "\n\n---\n\n";
+
+/// Default sections in prompt order, with their content.
+const DEFAULT_SECTIONS: &[(&str, &str)] = &[
+    ("HEADER", SECTION_HEADER),
+    ("PRIME DIRECTIVES", SECTION_PRIME_DIRECTIVES),
+    ("COGNITIVE ARCHITECTURE", SECTION_COGNITIVE_ARCHITECTURE),
+    ("FAILURE PROTOCOL", SECTION_FAILURE_PROTOCOL),
+    ("CODE DISCIPLINE", SECTION_CODE_DISCIPLINE),
+    ("QUALITY CHECKPOINTS", SECTION_QUALITY_CHECKPOINTS),
+    ("TOOLKIT", SECTION_TOOLKIT),
+    ("RESPONSE PATTERNS", SECTION_RESPONSE_PATTERNS),
+    ("ANTI-PATTERNS", SECTION_ANTI_PATTERNS),
+    ("OUTPUT FORMAT", SECTION_OUTPUT_FORMAT),
+];
+
+/// Built-in content of the default section called `name`.
+fn default_section_content(name: &str) -> Option<&'static str> {
+    DEFAULT_SECTIONS
+        .iter()
+        .find(|(section, _)| *section == name)
+        .map(|(_, content)| *content)
+}
+
+/// Fraction of a model's context window above which [`CortexPromptBuilder::check_fit`]
+/// warns.
+///
+/// Past a quarter of the window, the system prompt leaves too little room for
+/// conversation history and tool output.
+pub const FIT_WARNING_FRACTION: f64 = 0.25;
+
+/// How much of a model's context window a built prompt consumes.
+///
+/// Returned by [`CortexPromptBuilder::check_fit`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitReport {
+    /// Estimated prompt tokens (see [`crate::estimate_tokens`]).
+    pub est_tokens: u32,
+    /// Context window of the model, in tokens.
+    pub window: i64,
+    /// `est_tokens / window`.
+    pub fraction: f64,
+    /// Set when `fraction` exceeds [`FIT_WARNING_FRACTION`].
+    pub warning: Option<String>,
+}
+
 /// Builder for constructing the Cortex system prompt dynamically.
 ///
 /// This builder allows you to:
@@ -335,6 +584,12 @@ pub struct CortexPromptBuilder {
     custom_tools: Vec<(String, String)>,
     /// Whether to include the default toolkit or replace it entirely.
     use_custom_toolkit_only: bool,
+    /// Whether to rewrite `## NN //` section numbers to be contiguous.
+    renumber: bool,
+    /// Phase the prompt is tailored to, if any.
+    phase: Option<Phase>,
+    /// `{{KEY}}` placeholder replacements, in registration order.
+    variables: Vec<(String, String)>,
 }
 
 /// Represents a section of the Cortex prompt.
@@ -346,6 +601,8 @@ struct CortexSection {
     content: String,
     /// Whether this section is enabled.
     enabled: bool,
+    /// Whether custom tools render as a table in this section.
+    tool_target: bool,
 }
 
 impl CortexSection {
@@ -354,10 +611,77 @@ impl CortexSection {
             name: name.into(),
             content: content.into(),
             enabled: true,
+            tool_target: false,
         }
     }
 }
 
+/// Serializable snapshot of how a [`CortexPromptBuilder`] is configured.
+///
+/// Produced by [`CortexPromptBuilder::to_config`]; feeding it to
+/// [`CortexPromptBuilder::from_config`] rebuilds a builder producing the
+/// same prompt.
+///
+/// # Example
+///
+/// ```rust
+/// use cortex_prompt_harness::prompts::core::CortexPromptBuilder;
+///
+/// let builder = CortexPromptBuilder::new()
+///     .without_section("ANTI-PATTERNS")
+///     .add_tool("Analyze", "Analyze code");
+///
+/// let json = serde_json::to_string(&builder.to_config()).unwrap();
+/// let restored = CortexPromptBuilder::from_config(serde_json::from_str(&json).unwrap());
+/// assert_eq!(restored.build(), builder.build());
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptBuilderConfig {
+    /// Every section in prompt order, enabled or not.
+    pub sections: Vec<SectionConfig>,
+    /// Custom tools as `(name, description)` pairs.
+    pub custom_tools: Vec<(String, String)>,
+    /// Whether the custom tools replace the default toolkit.
+    pub use_custom_toolkit_only: bool,
+    /// Whether section numbers are rewritten to be contiguous.
+    #[serde(default)]
+    pub renumber: bool,
+    /// Phase the prompt is tailored to, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phase: Option<Phase>,
+    /// `{{KEY}}` placeholder replacements, in registration order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<(String, String)>,
+}
+
+impl PromptBuilderConfig {
+    /// Names of the enabled sections, in prompt order.
+    #[must_use]
+    pub fn enabled_sections(&self) -> Vec<&str> {
+        self.sections
+            .iter()
+            .filter(|s| s.enabled)
+            .map(|s| s.name.as_str())
+            .collect()
+    }
+}
+
+/// One section of a [`PromptBuilderConfig`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionConfig {
+    /// Section name.
+    pub name: String,
+    /// Whether the section is rendered.
+    pub enabled: bool,
+    /// Section content; omitted for default sections whose content is
+    /// unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Whether custom tools render as a table in this section.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tool_target: bool,
+}
+
 impl CortexPromptBuilder {
     /// Create a new builder with all default sections enabled.
     ///
@@ -375,20 +699,15 @@ impl CortexPromptBuilder {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            sections: vec![
-                CortexSection::new("HEADER", SECTION_HEADER),
-                CortexSection::new("PRIME DIRECTIVES", SECTION_PRIME_DIRECTIVES),
-                CortexSection::new("COGNITIVE ARCHITECTURE", SECTION_COGNITIVE_ARCHITECTURE),
-                CortexSection::new("FAILURE PROTOCOL", SECTION_FAILURE_PROTOCOL),
-                CortexSection::new("CODE DISCIPLINE", SECTION_CODE_DISCIPLINE),
-                CortexSection::new("QUALITY CHECKPOINTS", SECTION_QUALITY_CHECKPOINTS),
-                CortexSection::new("TOOLKIT", SECTION_TOOLKIT),
-                CortexSection::new("RESPONSE PATTERNS", SECTION_RESPONSE_PATTERNS),
-                CortexSection::new("ANTI-PATTERNS", SECTION_ANTI_PATTERNS),
-                CortexSection::new("OUTPUT FORMAT", SECTION_OUTPUT_FORMAT),
-            ],
+            sections: DEFAULT_SECTIONS
+                .iter()
+                .map(|(name, content)| CortexSection::new(*name, *content))
+                .collect(),
             custom_tools: Vec::new(),
             use_custom_toolkit_only: false,
+            renumber: false,
+            phase: None,
+            variables: Vec::new(),
         }
     }
 
@@ -443,6 +762,70 @@ impl CortexPromptBuilder {
         self
     }
 
+    /// Disable the section at `index` among all sections, default and custom,
+    /// in prompt order.
+    ///
+    /// Unlike [`without_section`](Self::without_section), this can target
+    /// one of several custom sections sharing a name. Out-of-range indices
+    /// are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cortex_prompt_harness::prompts::core::CortexPromptBuilder;
+    ///
+    /// let builder = CortexPromptBuilder::new();
+    /// let last = builder.section_count() - 1;
+    /// let builder = builder.without_section_at(last);
+    ///
+    /// assert!(!builder.is_section_enabled("OUTPUT FORMAT"));
+    /// ```
+    #[must_use]
+    pub fn without_section_at(mut self, index: usize) -> Self {
+        if let Some(section) = self.sections.get_mut(index) {
+            section.enabled = false;
+        }
+        self
+    }
+
+    /// Enable the section at `index` among all sections, default and custom,
+    /// in prompt order. Out-of-range indices are ignored.
+    #[must_use]
+    pub fn with_section_at(mut self, index: usize) -> Self {
+        if let Some(section) = self.sections.get_mut(index) {
+            section.enabled = true;
+        }
+        self
+    }
+
+    /// Append content to the end of an existing section.
+    ///
+    /// The section keeps its position and original content; `extra` is added
+    /// after a blank line. Section names are case-insensitive. Unknown names
+    /// are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cortex_prompt_harness::prompts::core::CortexPromptBuilder;
+    ///
+    /// let prompt = CortexPromptBuilder::new()
+    ///     .append_to_section("CODE DISCIPLINE", "- Run `cargo fmt` before committing")
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn append_to_section(mut self, section_name: &str, extra: &str) -> Self {
+        let name_upper = section_name.to_uppercase();
+        for section in &mut self.sections {
+            if section.name.to_uppercase() == name_upper {
+                section.content.push_str("\n\n");
+                section.content.push_str(extra);
+                break;
+            }
+        }
+        self
+    }
+
     /// Add a custom tool to the toolkit section.
     ///
     /// The tool will be appended to the default toolkit (unless `with_custom_toolkit`
@@ -488,6 +871,40 @@ impl CortexPromptBuilder {
         self
     }
 
+    /// Remove custom tools by name.
+    ///
+    /// Every custom tool whose name matches case-insensitively is dropped.
+    /// Default toolkit tools are not affected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cortex_prompt_harness::prompts::core::CortexPromptBuilder;
+    ///
+    /// let prompt = CortexPromptBuilder::new()
+    ///     .add_tool("Analyze", "Analyze code")
+    ///     .remove_tool("analyze")
+    ///     .build();
+    ///
+    /// assert!(!prompt.contains("`Analyze`"));
+    /// ```
+    #[must_use]
+    pub fn remove_tool(mut self, name: &str) -> Self {
+        self.custom_tools
+            .retain(|(tool, _)| !tool.eq_ignore_ascii_case(name));
+        self
+    }
+
+    /// Remove all custom tools and restore the default toolkit.
+    ///
+    /// This also undoes [`with_custom_toolkit`](Self::with_custom_toolkit).
+    #[must_use]
+    pub fn clear_custom_tools(mut self) -> Self {
+        self.custom_tools.clear();
+        self.use_custom_toolkit_only = false;
+        self
+    }
+
     /// Replace the toolkit section entirely with custom tools.
     ///
     /// This will remove all default tools and only include the specified ones.
@@ -535,6 +952,234 @@ impl CortexPromptBuilder {
         self
     }
 
+    /// Add a custom section that receives the custom tools table.
+    ///
+    /// Tools added with `add_tool` or `with_tools` render as a table at the end
+    /// of this section instead of in the TOOLKIT's "Custom" block. The default
+    /// TOOLKIT tools are unaffected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cortex_prompt_harness::prompts::core::CortexPromptBuilder;
+    ///
+    /// let prompt = CortexPromptBuilder::new()
+    ///     .add_custom_tool_section("EXTRA TOOLS", "## EXTRA TOOLS")
+    ///     .add_tool("Analyze", "Analyze code for issues")
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn add_custom_tool_section(mut self, name: &str, content: &str) -> Self {
+        let mut section = CortexSection::new(name.to_string(), content.to_string());
+        section.tool_target = true;
+        self.sections.push(section);
+        self
+    }
+
+    /// Rewrite the `## NN //` section numbers so they stay contiguous.
+    ///
+    /// When enabled, numbered sections are renumbered in order of appearance
+    /// among the enabled sections, so disabling a section doesn't leave gaps.
+    /// Sections without a numbered header (e.g. HEADER) are left untouched.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cortex_prompt_harness::prompts::core::CortexPromptBuilder;
+    ///
+    /// let prompt = CortexPromptBuilder::new()
+    ///     .without_section("COGNITIVE ARCHITECTURE")
+    ///     .renumber(true)
+    ///     .build();
+    ///
+    /// assert!(prompt.contains("## 02 // FAILURE PROTOCOL"));
+    /// ```
+    #[must_use]
+    pub fn renumber(mut self, enabled: bool) -> Self {
+        self.renumber = enabled;
+        self
+    }
+
+    /// Tailor the prompt to a single workflow phase.
+    ///
+    /// The COGNITIVE ARCHITECTURE section is reduced to the block for
+    /// `phase`, and the default toolkit only lists the tools that block
+    /// names on its `Tools:` line. Custom tools are kept.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cortex_prompt_harness::prompts::core::{CortexPromptBuilder, Phase};
+    ///
+    /// let prompt = CortexPromptBuilder::new().for_phase(Phase::Verify).build();
+    ///
+    /// assert!(prompt.contains("### VERIFY"));
+    /// assert!(!prompt.contains("### RECON"));
+    /// ```
+    #[must_use]
+    pub fn for_phase(mut self, phase: Phase) -> Self {
+        self.phase = Some(phase);
+        self
+    }
+
+    /// Replace `{{KEY}}` with `value` in every enabled section at build time.
+    ///
+    /// Applies to default and custom sections alike. Setting the same key
+    /// again overwrites the previous value. Placeholders with no registered
+    /// variable are left untouched.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cortex_prompt_harness::prompts::core::CortexPromptBuilder;
+    ///
+    /// let prompt = CortexPromptBuilder::new()
+    ///     .add_custom_section("ENVIRONMENT", "Working in {{CWD}} with {{MODEL}}.")
+    ///     .with_variable("CWD", "/workspace")
+    ///     .build();
+    ///
+    /// assert!(prompt.contains("Working in /workspace with {{MODEL}}."));
+    /// ```
+    #[must_use]
+    pub fn with_variable(mut self, key: &str, value: &str) -> Self {
+        match self.variables.iter_mut().find(|(k, _)| k == key) {
+            Some((_, existing)) => *existing = value.to_string(),
+            None => self.variables.push((key.to_string(), value.to_string())),
+        }
+        self
+    }
+
+    /// Apply the registered `{{KEY}}` replacements to `content`.
+    fn substitute_variables(&self, mut content: String) -> String {
+        for (key, value) in &self.variables {
+            content = content.replace(&format!("{{{{{}}}}}", key), value);
+        }
+        content
+    }
+
+    /// Combine `other` into this builder, e.g. a team overlay onto a shared base.
+    ///
+    /// Precedence:
+    /// - Default sections keep `self`'s content; a section is enabled if it
+    ///   is enabled in either builder.
+    /// - Custom sections from `other` are appended in order. One with the same
+    ///   name as a custom section in `self` replaces its content in place.
+    /// - Custom tools from `other` are appended; a tool whose name matches one
+    ///   in `self` (case-insensitively) overwrites its description in place.
+    /// - Variables from `other` overwrite `self`'s. `self`'s phase wins if set.
+    /// - The custom-toolkit-only and renumber flags are on if on in either.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cortex_prompt_harness::prompts::core::CortexPromptBuilder;
+    ///
+    /// let base = CortexPromptBuilder::new().add_tool("Analyze", "Analyze code");
+    /// let overlay = CortexPromptBuilder::new()
+    ///     .add_custom_section("TEAM RULES", "## TEAM RULES\n\nShip small PRs.");
+    ///
+    /// let prompt = base.merge(&overlay).build();
+    /// assert!(prompt.contains("`Analyze`"));
+    /// assert!(prompt.contains("Ship small PRs."));
+    /// ```
+    #[must_use]
+    pub fn merge(mut self, other: &CortexPromptBuilder) -> Self {
+        for section in &other.sections {
+            let is_default = SECTION_NAMES.contains(&section.name.as_str());
+            match self.sections.iter_mut().find(|s| s.name == section.name) {
+                Some(existing) if is_default => existing.enabled |= section.enabled,
+                Some(existing) => *existing = section.clone(),
+                None => self.sections.push(section.clone()),
+            }
+        }
+
+        for (name, description) in &other.custom_tools {
+            match self
+                .custom_tools
+                .iter_mut()
+                .find(|(existing, _)| existing.eq_ignore_ascii_case(name))
+            {
+                Some((_, existing)) => existing.clone_from(description),
+                None => self.custom_tools.push((name.clone(), description.clone())),
+            }
+        }
+
+        for (key, value) in &other.variables {
+            self = self.with_variable(key, value);
+        }
+
+        self.use_custom_toolkit_only |= other.use_custom_toolkit_only;
+        self.renumber |= other.renumber;
+        self.phase = self.phase.or(other.phase);
+        self
+    }
+
+    /// Snapshot this builder's configuration, e.g. to record how a prompt
+    /// was assembled.
+    ///
+    /// Default sections with unchanged content are recorded by name only;
+    /// all other section content is included so
+    /// [`from_config`](Self::from_config) is lossless.
+    #[must_use]
+    pub fn to_config(&self) -> PromptBuilderConfig {
+        let sections = self
+            .sections
+            .iter()
+            .map(|section| {
+                let is_default = default_section_content(&section.name)
+                    .is_some_and(|content| content == section.content);
+                SectionConfig {
+                    name: section.name.clone(),
+                    enabled: section.enabled,
+                    content: (!is_default).then(|| section.content.clone()),
+                    tool_target: section.tool_target,
+                }
+            })
+            .collect();
+
+        PromptBuilderConfig {
+            sections,
+            custom_tools: self.custom_tools.clone(),
+            use_custom_toolkit_only: self.use_custom_toolkit_only,
+            renumber: self.renumber,
+            phase: self.phase,
+            variables: self.variables.clone(),
+        }
+    }
+
+    /// Rebuild a builder from a [`to_config`](Self::to_config) snapshot.
+    ///
+    /// A section without content takes the built-in content of the default
+    /// section with that name; one naming no default section is skipped.
+    #[must_use]
+    pub fn from_config(config: PromptBuilderConfig) -> Self {
+        let sections = config
+            .sections
+            .into_iter()
+            .filter_map(|section| {
+                let content = match section.content {
+                    Some(content) => content,
+                    None => default_section_content(&section.name)?.to_string(),
+                };
+                Some(CortexSection {
+                    name: section.name,
+                    content,
+                    enabled: section.enabled,
+                    tool_target: section.tool_target,
+                })
+            })
+            .collect();
+
+        Self {
+            sections,
+            custom_tools: config.custom_tools,
+            use_custom_toolkit_only: config.use_custom_toolkit_only,
+            renumber: config.renumber,
+            phase: config.phase,
+            variables: config.variables,
+        }
+    }
+
     /// Check if a section is enabled.
     #[must_use]
     pub fn is_section_enabled(&self, section_name: &str) -> bool {
@@ -544,6 +1189,15 @@ impl CortexPromptBuilder {
             .any(|s| s.name.to_uppercase() == name_upper && s.enabled)
     }
 
+    /// Number of sections, enabled or not, including custom sections.
+    ///
+    /// Valid indices for [`without_section_at`](Self::without_section_at)
+    /// and [`with_section_at`](Self::with_section_at) are `0..section_count()`.
+    #[must_use]
+    pub fn section_count(&self) -> usize {
+        self.sections.len()
+    }
+
     /// Get the list of enabled section names.
     #[must_use]
     pub fn enabled_sections(&self) -> Vec<&str> {
@@ -554,29 +1208,50 @@ impl CortexPromptBuilder {
             .collect()
     }
 
+    /// Render the custom tools as a markdown table.
+    fn build_custom_tools_table(&self) -> String {
+        let mut content = String::from("| Tool | Function |\n");
+        content.push_str("|------|----------|\n");
+        for (name, description) in &self.custom_tools {
+            content.push_str(&format!("| `{}` | {} |\n", name, description));
+        }
+        content
+    }
+
+    /// Whether an enabled custom section receives the custom tools table.
+    fn has_tool_target(&self) -> bool {
+        self.sections.iter().any(|s| s.enabled && s.tool_target)
+    }
+
+    /// The default toolkit, restricted to the phase's tools when one is set.
+    fn default_toolkit(&self) -> String {
+        match self.phase {
+            Some(phase) => {
+                let tools = phase.tools();
+                render_toolkit(|name| tools.iter().any(|tool| *tool == name))
+            }
+            None => render_toolkit_section(),
+        }
+    }
+
     /// Build the toolkit section with optional custom tools.
+    ///
+    /// A custom-only toolkit with no tools left falls back to the default
+    /// toolkit rather than rendering an empty table.
     fn build_toolkit_section(&self) -> String {
-        if self.use_custom_toolkit_only {
+        if self.use_custom_toolkit_only && !self.custom_tools.is_empty() {
             // Build a custom toolkit from scratch
             let mut content = String::from("## 06 // TOOLKIT\n\n");
-            content.push_str("| Tool | Function |\n");
-            content.push_str("|------|----------|\n");
-            for (name, description) in &self.custom_tools {
-                content.push_str(&format!("| `{}` | {} |\n", name, description));
-            }
+            content.push_str(&self.build_custom_tools_table());
             content
-        } else if self.custom_tools.is_empty() {
+        } else if self.custom_tools.is_empty() || self.has_tool_target() {
             // Use the default toolkit as-is
-            SECTION_TOOLKIT.to_string()
+            self.default_toolkit()
         } else {
             // Append custom tools to the default toolkit
-            let mut content = SECTION_TOOLKIT.to_string();
+            let mut content = self.default_toolkit();
             content.push_str("\n\n### Custom\n");
-            content.push_str("| Tool | Function |\n");
-            content.push_str("|------|----------|\n");
-            for (name, description) in &self.custom_tools {
-                content.push_str(&format!("| `{}` | {} |\n", name, description));
-            }
+            content.push_str(&self.build_custom_tools_table());
             content
         }
     }
@@ -587,31 +1262,168 @@ impl CortexPromptBuilder {
     /// and any custom tools or sections that have been added.
     #[must_use]
     pub fn build(&self) -> String {
-        let mut parts: Vec<String> = Vec::new();
+        self.rendered_sections()
+            .into_iter()
+            .map(|(_, content)| content)
+            .collect::<Vec<_>>()
+            .join(SECTION_SEPARATOR)
+    }
+
+    /// Raw content of a section, enabled or not, by case-insensitive name.
+    ///
+    /// This is the stored content before toolkit expansion, variable
+    /// substitution, or renumbering; see [`rendered_sections`](Self::rendered_sections)
+    /// for what [`build`](Self::build) emits.
+    #[must_use]
+    pub fn section_content(&self, section_name: &str) -> Option<&str> {
+        let name_upper = section_name.to_uppercase();
+        self.sections
+            .iter()
+            .find(|s| s.name.to_uppercase() == name_upper)
+            .map(|s| s.content.as_str())
+    }
+
+    /// The enabled sections as `(name, content)` pairs, exactly as
+    /// [`build`](Self::build) renders them.
+    ///
+    /// TOOLKIT is expanded with any custom tools, variables are substituted,
+    /// and headers are renumbered if [`renumber`](Self::renumber) is on.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cortex_prompt_harness::prompts::core::CortexPromptBuilder;
+    ///
+    /// let builder = CortexPromptBuilder::new().add_tool("Analyze", "Analyze code");
+    /// let sections = builder.rendered_sections();
+    ///
+    /// let (_, toolkit) = sections.iter().find(|(name, _)| name == "TOOLKIT").unwrap();
+    /// assert!(toolkit.contains("| `Analyze` | Analyze code |"));
+    /// ```
+    #[must_use]
+    pub fn rendered_sections(&self) -> Vec<(String, String)> {
+        let mut parts: Vec<(String, String)> = Vec::new();
+        let mut number = 0;
 
         for section in &self.sections {
             if !section.enabled {
                 continue;
             }
 
-            if section.name == "TOOLKIT" {
-                parts.push(self.build_toolkit_section());
+            let content = if section.name == "TOOLKIT" {
+                self.build_toolkit_section()
+            } else if let Some(phase) = self.phase
+                && section.name == "COGNITIVE ARCHITECTURE"
+            {
+                phase_architecture_section(&section.content, phase)
+            } else if section.tool_target
+                && !self.use_custom_toolkit_only
+                && !self.custom_tools.is_empty()
+            {
+                format!("{}\n\n{}", section.content, self.build_custom_tools_table())
             } else {
-                parts.push(section.content.clone());
-            }
+                section.content.clone()
+            };
+            let content = self.substitute_variables(content);
+
+            let content = if self.renumber && numbered_header_title(&content).is_some() {
+                number += 1;
+                renumber_header(&content, number)
+            } else {
+                content
+            };
+            parts.push((section.name.clone(), content));
+        }
+
+        parts
+    }
+
+    /// Build the prompt and return an estimated token count.
+    ///
+    /// Uses [`crate::estimate_tokens`].
+    #[must_use]
+    pub fn build_with_token_estimate(&self) -> (String, u32) {
+        let prompt = self.build();
+        let tokens = crate::estimate_tokens(&prompt);
+        (prompt, tokens)
+    }
+
+    /// Build the prompt and estimate its tokens with the given mode.
+    ///
+    /// [`build_with_token_estimate`](Self::build_with_token_estimate) is
+    /// equivalent to `TokenEstimateMode::default()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cortex_prompt_harness::TokenEstimateMode;
+    /// use cortex_prompt_harness::prompts::core::CortexPromptBuilder;
+    ///
+    /// let (_, tokens) = CortexPromptBuilder::new()
+    ///     .build_with_token_estimate_mode(TokenEstimateMode::WordAndPunct);
+    /// assert!(tokens > 0);
+    /// ```
+    #[must_use]
+    pub fn build_with_token_estimate_mode(&self, mode: crate::TokenEstimateMode) -> (String, u32) {
+        let prompt = self.build();
+        let tokens = crate::estimate_tokens_with(&prompt, mode);
+        (prompt, tokens)
+    }
+
+    /// Check how much of `model`'s context window the system prompt alone uses.
+    ///
+    /// The report carries a warning when the estimate exceeds
+    /// [`FIT_WARNING_FRACTION`] of the window, or when the window is unknown.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cortex_common::model_presets::get_model_preset;
+    /// use cortex_prompt_harness::prompts::core::CortexPromptBuilder;
+    ///
+    /// let model = get_model_preset("claude-3-5-sonnet").unwrap();
+    /// let report = CortexPromptBuilder::new().check_fit(model);
+    /// assert!(report.warning.is_none());
+    /// ```
+    #[must_use]
+    pub fn check_fit(&self, model: &ModelPreset) -> FitReport {
+        let est_tokens = crate::estimate_tokens(&self.build());
+        let window = model.context_window;
+        if window <= 0 {
+            return FitReport {
+                est_tokens,
+                window,
+                fraction: 0.0,
+                warning: Some(format!("{} has no known context window", model.id)),
+            };
         }
 
-        parts.join("\n\n---\n\n")
+        let fraction = f64::from(est_tokens) / window as f64;
+        let warning = (fraction > FIT_WARNING_FRACTION).then(|| {
+            format!(
+                "system prompt uses ~{} tokens, {:.0}% of {}'s {}-token context window",
+                est_tokens,
+                fraction * 100.0,
+                model.id,
+                window
+            )
+        });
+        FitReport {
+            est_tokens,
+            window,
+            fraction,
+            warning,
+        }
     }
 
-    /// Build the prompt and return an estimated token count.
+    /// Build the prompt in a whitespace-normalized form for golden tests.
     ///
-    /// Uses a simple approximation of ~4 characters per token.
+    /// Trailing whitespace is stripped from every line, runs of three or more
+    /// blank lines collapse to two, and the result ends with exactly one
+    /// newline. Section content and order are otherwise identical to `build()`.
     #[must_use]
-    pub fn build_with_token_estimate(&self) -> (String, u32) {
-        let prompt = self.build();
-        let tokens = (prompt.len() as f64 / 4.0).ceil() as u32;
-        (prompt, tokens)
+    pub fn build_canonical(&self) -> String {
+        canonicalize_whitespace(&self.build())
     }
 }
 
@@ -621,6 +1433,185 @@ impl Default for CortexPromptBuilder {
     }
 }
 
+/// Normalize cosmetic whitespace; see [`CortexPromptBuilder::build_canonical`].
+fn canonicalize_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank_run = 0;
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() {
+            blank_run += 1;
+            if blank_run > 2 {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    let trimmed_len = out.trim_end().len();
+    out.truncate(trimmed_len);
+    out.push('\n');
+    out
+}
+
+/// Return the text following the `## NN // ` prefix of a numbered section header.
+fn numbered_header_title(content: &str) -> Option<&str> {
+    let rest = content.strip_prefix("## ")?;
+    let digits = rest.get(..2)?;
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    rest[2..].strip_prefix(" // ")
+}
+
+/// Replace the number of a numbered section header with `number`.
+fn renumber_header(content: &str, number: usize) -> String {
+    match numbered_header_title(content) {
+        Some(title) => format!("## {:02} // {}", number, title),
+        None => content.to_string(),
+    }
+}
+
+// =============================================================================
+// Tool documentation - derived from TOOLKIT and RESPONSE PATTERNS
+// =============================================================================
+
+/// Prompt documentation for a single tool.
+///
+/// Assembled from [`DEFAULT_TOOLS`] and [`SECTION_RESPONSE_PATTERNS`], so it
+/// always matches what the model is told.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToolDoc {
+    /// The tool's function as listed in the TOOLKIT table.
+    pub function: &'static str,
+    /// The expected response from RESPONSE PATTERNS, if the tool has one.
+    pub response_pattern: Option<&'static str>,
+}
+
+impl std::fmt::Display for ToolDoc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.response_pattern {
+            Some(pattern) => write!(f, "{} → {}", self.function, pattern),
+            None => f.write_str(self.function),
+        }
+    }
+}
+
+/// Look up the prompt documentation for a tool by name.
+///
+/// Tool names are case-insensitive. Returns `None` for tools not in
+/// [`DEFAULT_TOOLS`].
+///
+/// # Example
+///
+/// ```rust
+/// use cortex_prompt_harness::prompts::core::tool_doc;
+///
+/// let doc = tool_doc("Read").unwrap();
+/// assert_eq!(doc.to_string(), "Read file contents → Brief summary");
+/// ```
+#[must_use]
+pub fn tool_doc(name: &str) -> Option<ToolDoc> {
+    let function = DEFAULT_TOOLS
+        .iter()
+        .find(|(tool, _, _)| tool.eq_ignore_ascii_case(name))
+        .map(|(_, function, _)| *function)?;
+
+    let response_pattern = SECTION_RESPONSE_PATTERNS.lines().find_map(|line| {
+        let mut parts = line.split('→').map(str::trim);
+        let (_request, tool, response) = (parts.next()?, parts.next()?, parts.next()?);
+        tool.eq_ignore_ascii_case(name).then_some(response)
+    });
+
+    Some(ToolDoc {
+        function,
+        response_pattern,
+    })
+}
+
+// =============================================================================
+// Prompt diffing
+// =============================================================================
+
+/// A per-section difference reported by [`diff_prompts`].
+///
+/// Sections are identified by their first line, e.g. `## 01 // PRIME DIRECTIVES`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectionDiff {
+    /// Section present only in the second prompt.
+    Added { header: String },
+    /// Section present only in the first prompt.
+    Removed { header: String },
+    /// Section present in both prompts with different content.
+    Changed {
+        header: String,
+        before: String,
+        after: String,
+    },
+}
+
+/// Compare two built prompts section by section.
+///
+/// Both prompts are split on the separator used by
+/// [`CortexPromptBuilder::build`] and sections are aligned by their header
+/// line; repeated headers are paired in order of appearance. Removed and
+/// changed sections are reported in the order of `a`, followed by added
+/// sections in the order of `b`. Identical sections are not reported.
+///
+/// # Example
+///
+/// ```rust
+/// use cortex_prompt_harness::prompts::core::{CortexPromptBuilder, SectionDiff, diff_prompts};
+///
+/// let full = CortexPromptBuilder::new().build();
+/// let trimmed = CortexPromptBuilder::new().without_section("ANTI-PATTERNS").build();
+///
+/// let diffs = diff_prompts(&full, &trimmed);
+/// assert!(matches!(
+///     &diffs[..],
+///     [SectionDiff::Removed { header }] if header.contains("ANTI-PATTERNS")
+/// ));
+/// ```
+#[must_use]
+pub fn diff_prompts(a: &str, b: &str) -> Vec<SectionDiff> {
+    fn header(section: &str) -> &str {
+        section.lines().next().unwrap_or_default()
+    }
+
+    let mut unmatched: Vec<Option<&str>> = b.split(SECTION_SEPARATOR).map(Some).collect();
+    let mut diffs = Vec::new();
+
+    for before in a.split(SECTION_SEPARATOR) {
+        let matched = unmatched
+            .iter_mut()
+            .find(|slot| slot.is_some_and(|after| header(after) == header(before)))
+            .and_then(Option::take);
+        match matched {
+            Some(after) if after == before => {}
+            Some(after) => diffs.push(SectionDiff::Changed {
+                header: header(before).to_string(),
+                before: before.to_string(),
+                after: after.to_string(),
+            }),
+            None => diffs.push(SectionDiff::Removed {
+                header: header(before).to_string(),
+            }),
+        }
+    }
+
+    diffs.extend(
+        unmatched
+            .into_iter()
+            .flatten()
+            .map(|after| SectionDiff::Added {
+                header: header(after).to_string(),
+            }),
+    );
+    diffs
+}
+
 // =============================================================================
 // Original CORTEX_MAIN_PROMPT (kept for backward compatibility)
 // =============================================================================
@@ -1229,6 +2220,480 @@ mod tests {
         assert!(prompt.contains("Final description"));
     }
 
+    #[test]
+    fn test_builder_empty_custom_toolkit_falls_back_to_default() {
+        let default = CortexPromptBuilder::new().build();
+
+        let removed = CortexPromptBuilder::new()
+            .with_custom_toolkit(&[("OnlyTool", "Only description")])
+            .remove_tool("OnlyTool")
+            .build();
+        assert_eq!(removed, default);
+
+        let empty = CortexPromptBuilder::new().with_custom_toolkit(&[]).build();
+        assert_eq!(empty, default);
+        assert!(empty.contains("### Perception"));
+    }
+
+    #[test]
+    fn test_builder_append_to_section() {
+        let extra = "- Run `cargo fmt` before committing";
+        let prompt = CortexPromptBuilder::new()
+            .append_to_section("code discipline", extra)
+            .build();
+
+        let start = prompt.find("## 04 // CODE DISCIPLINE").unwrap();
+        let end = prompt.find("## 05 // QUALITY CHECKPOINTS").unwrap();
+        let section = &prompt[start..end];
+        assert!(section.starts_with(SECTION_CODE_DISCIPLINE));
+        assert!(section.contains(extra));
+        assert_eq!(prompt.matches(extra).count(), 1);
+    }
+
+    #[test]
+    fn test_builder_append_to_unknown_section_is_noop() {
+        let prompt = CortexPromptBuilder::new()
+            .append_to_section("NOT A SECTION", "extra")
+            .build();
+
+        assert_eq!(prompt, CortexPromptBuilder::new().build());
+    }
+
+    #[test]
+    fn test_builder_build_canonical_normalizes_whitespace() {
+        let canonical = CortexPromptBuilder::new()
+            .add_custom_section("NOTES", "## NOTES   \nfirst  \n\n\n\n\nsecond\t\n\n\n")
+            .build_canonical();
+
+        assert!(canonical.lines().all(|line| line == line.trim_end()));
+        assert!(!canonical.contains("\n\n\n\n"));
+        assert!(canonical.ends_with("second\n"));
+        assert!(!canonical.ends_with("\n\n"));
+        assert!(canonical.contains("## NOTES\nfirst\n\n\nsecond"));
+
+        // Section content and order are preserved.
+        let header = canonical.find("# CORTEX").unwrap();
+        let toolkit = canonical.find("TOOLKIT").unwrap();
+        let notes = canonical.find("## NOTES").unwrap();
+        assert!(header < toolkit && toolkit < notes);
+    }
+
+    #[test]
+    fn test_builder_build_canonical_is_idempotent_for_clean_prompt() {
+        let builder = CortexPromptBuilder::new();
+        let canonical = builder.build_canonical();
+
+        assert_eq!(canonicalize_whitespace(&canonical), canonical);
+        let expected = builder
+            .build()
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(canonical, format!("{}\n", expected.trim_end()));
+    }
+
+    #[test]
+    fn test_toolkit_section_rendered_from_default_tools() {
+        assert_eq!(render_toolkit_section(), SECTION_TOOLKIT);
+    }
+
+    #[test]
+    fn test_response_pattern_tools_are_default_tools() {
+        let names = default_tool_names();
+        for line in SECTION_RESPONSE_PATTERNS.lines() {
+            let Some(tool) = line.split('→').nth(1).map(str::trim) else {
+                continue;
+            };
+            assert!(
+                names.contains(&tool),
+                "RESPONSE PATTERNS references unknown tool: {}",
+                tool
+            );
+        }
+    }
+
+    #[test]
+    fn test_default_tool_names() {
+        let names = default_tool_names();
+        assert_eq!(names.len(), DEFAULT_TOOLS.len());
+        assert_eq!(names.first(), Some(&"Read"));
+        assert!(names.contains(&"Shell"));
+    }
+
+    #[test]
+    fn test_tool_doc_search() {
+        let doc = tool_doc("Search").unwrap();
+        assert_eq!(doc.function, "Regex search in files");
+        assert_eq!(doc.response_pattern, Some("Concise findings"));
+    }
+
+    #[test]
+    fn test_tool_doc_case_insensitive_and_display() {
+        let doc = tool_doc("read").unwrap();
+        assert_eq!(doc.to_string(), "Read file contents → Brief summary");
+    }
+
+    #[test]
+    fn test_tool_doc_without_response_pattern() {
+        let doc = tool_doc("Plan").unwrap();
+        assert_eq!(doc.function, "Track task progress");
+        assert_eq!(doc.response_pattern, None);
+        assert_eq!(doc.to_string(), "Track task progress");
+    }
+
+    #[test]
+    fn test_tool_doc_unknown_tool() {
+        assert_eq!(tool_doc("Teleport"), None);
+    }
+
+    #[test]
+    fn test_phase_tools_match_cognitive_architecture() {
+        assert_eq!(Phase::Verify.tools(), vec!["Shell", "Read", "Search"]);
+        assert_eq!(Phase::Design.tools(), vec!["Plan", "Propose", "Delegate"]);
+        for phase in Phase::ALL {
+            assert!(!phase.tools().is_empty(), "{:?} lists no tools", phase);
+            for tool in phase.tools() {
+                assert!(
+                    default_tool_names().contains(&tool),
+                    "unknown tool {}",
+                    tool
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_builder_for_phase_verify() {
+        let prompt = CortexPromptBuilder::new().for_phase(Phase::Verify).build();
+
+        assert!(prompt.contains("## 02 // COGNITIVE ARCHITECTURE"));
+        assert!(prompt.contains("### VERIFY"));
+        assert!(prompt.contains("Trust nothing. Test everything."));
+        for other in ["### RECON", "### DESIGN", "### BUILD", "### CLOSE"] {
+            assert!(!prompt.contains(other), "prompt still contains {}", other);
+        }
+
+        for tool in ["`Shell`", "`Read`", "`Search`"] {
+            assert!(prompt.contains(&format!("| {} |", tool)));
+        }
+        for tool in ["`Write`", "`Patch`", "`Delegate`", "`Plan`"] {
+            assert!(!prompt.contains(&format!("| {} |", tool)));
+        }
+        assert!(!prompt.contains("### Cognition"));
+        assert!(!prompt.contains("### Collaboration"));
+    }
+
+    #[test]
+    fn test_check_fit_warns_on_small_window() {
+        let model = cortex_common::model_presets::get_model_preset("command-light").unwrap();
+        assert_eq!(model.context_window, 4_096);
+
+        let report = CortexPromptBuilder::new().check_fit(model);
+        assert_eq!(report.window, 4_096);
+        assert_eq!(
+            report.est_tokens,
+            crate::estimate_tokens(&CortexPromptBuilder::new().build())
+        );
+        assert!(report.fraction > FIT_WARNING_FRACTION);
+        let warning = report.warning.expect("4k window should warn");
+        assert!(warning.contains("command-light"));
+    }
+
+    #[test]
+    fn test_check_fit_8k_window_reports_high_fraction() {
+        let model = cortex_common::model_presets::get_model_preset("llama3-8b-8192").unwrap();
+        let report = CortexPromptBuilder::new().check_fit(model);
+
+        assert_eq!(report.window, 8_192);
+        assert!(report.fraction > 0.1);
+    }
+
+    #[test]
+    fn test_check_fit_large_window_is_quiet() {
+        let model = cortex_common::model_presets::get_model_preset("claude-3-5-sonnet").unwrap();
+        let report = CortexPromptBuilder::new().check_fit(model);
+
+        assert!(report.fraction < FIT_WARNING_FRACTION);
+        assert!(report.warning.is_none());
+    }
+
+    #[test]
+    fn test_builder_with_variable() {
+        let prompt = CortexPromptBuilder::new()
+            .add_custom_section(
+                "ENVIRONMENT",
+                "Model: {{MODEL}}\nCwd: {{CWD}}\nDate: {{DATE}}",
+            )
+            .with_variable("MODEL", "old-model")
+            .with_variable("MODEL", "cortex-large")
+            .with_variable("CWD", "/workspace")
+            .build();
+
+        assert!(prompt.contains("Model: cortex-large"));
+        assert!(prompt.contains("Cwd: /workspace"));
+        // Unset variables are left as-is rather than blanked.
+        assert!(prompt.contains("Date: {{DATE}}"));
+        assert!(!prompt.contains("old-model"));
+    }
+
+    #[test]
+    fn test_builder_with_variable_applies_to_default_sections() {
+        let prompt = CortexPromptBuilder::new()
+            .append_to_section("CODE DISCIPLINE", "- Only modify files under {{CWD}}")
+            .with_variable("CWD", "/workspace")
+            .build();
+
+        assert!(prompt.contains("- Only modify files under /workspace"));
+    }
+
+    #[test]
+    fn test_builder_remove_tool() {
+        let prompt = CortexPromptBuilder::new()
+            .add_tool("Analyze", "Analyze code")
+            .add_tool("Refactor", "Refactor code")
+            .add_tool("Document", "Write docs")
+            .remove_tool("REFACTOR")
+            .build();
+
+        assert!(prompt.contains(
+            "### Custom\n| Tool | Function |\n|------|----------|\n\
+             | `Analyze` | Analyze code |\n\
+             | `Document` | Write docs |\n"
+        ));
+        assert!(!prompt.contains("Refactor"));
+    }
+
+    #[test]
+    fn test_builder_clear_custom_tools_restores_default_toolkit() {
+        let builder = CortexPromptBuilder::new()
+            .with_custom_toolkit(&[("OnlyTool", "The only tool")])
+            .clear_custom_tools();
+        let prompt = builder.build();
+
+        assert!(prompt.contains(&render_toolkit_section()));
+        assert!(!prompt.contains("OnlyTool"));
+        assert!(!prompt.contains("### Custom"));
+
+        // Removing the last custom tool also falls back to the default toolkit.
+        let prompt = CortexPromptBuilder::new()
+            .add_tool("Analyze", "Analyze code")
+            .remove_tool("Analyze")
+            .build();
+        assert!(!prompt.contains("### Custom"));
+    }
+
+    #[test]
+    fn test_builder_section_content() {
+        let builder = CortexPromptBuilder::new().without_section("ANTI-PATTERNS");
+
+        assert_eq!(
+            builder.section_content("anti-patterns"),
+            Some(SECTION_ANTI_PATTERNS)
+        );
+        assert_eq!(builder.section_content("TOOLKIT"), Some(SECTION_TOOLKIT));
+        assert_eq!(builder.section_content("NO SUCH SECTION"), None);
+    }
+
+    #[test]
+    fn test_builder_rendered_sections_match_build() {
+        let builder = CortexPromptBuilder::new()
+            .without_section("ANTI-PATTERNS")
+            .add_tool("Analyze", "Analyze code");
+        let sections = builder.rendered_sections();
+
+        let names: Vec<&str> = sections.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, builder.enabled_sections());
+
+        let (_, toolkit) = sections.iter().find(|(name, _)| name == "TOOLKIT").unwrap();
+        assert!(toolkit.contains("### Custom"));
+        assert!(toolkit.contains("| `Analyze` | Analyze code |"));
+
+        let joined: Vec<&str> = sections
+            .iter()
+            .map(|(_, content)| content.as_str())
+            .collect();
+        assert_eq!(joined.join("\n\n---\n\n"), builder.build());
+    }
+
+    #[test]
+    fn test_builder_merge_ors_enabled_sections() {
+        let base = CortexPromptBuilder::new().without_section("ANTI-PATTERNS");
+        let overlay = CortexPromptBuilder::new()
+            .without_section("ANTI-PATTERNS")
+            .with_section("ANTI-PATTERNS")
+            .without_section("OUTPUT FORMAT");
+
+        let merged = base.merge(&overlay);
+        assert!(merged.is_section_enabled("ANTI-PATTERNS"));
+        // Disabled only in the overlay, so still enabled.
+        assert!(merged.is_section_enabled("OUTPUT FORMAT"));
+        assert_eq!(merged.enabled_sections().len(), SECTION_NAMES.len());
+
+        // Disabled in both stays disabled.
+        let merged = CortexPromptBuilder::new()
+            .without_section("ANTI-PATTERNS")
+            .merge(&CortexPromptBuilder::new().without_section("ANTI-PATTERNS"));
+        assert!(!merged.is_section_enabled("ANTI-PATTERNS"));
+    }
+
+    #[test]
+    fn test_diff_prompts_removed_section() {
+        let full = CortexPromptBuilder::new().build();
+        let trimmed = CortexPromptBuilder::new()
+            .without_section("ANTI-PATTERNS")
+            .build();
+
+        let diffs = diff_prompts(&full, &trimmed);
+        assert_eq!(diffs.len(), 1);
+        let SectionDiff::Removed { header } = &diffs[0] else {
+            panic!("expected Removed, got {:?}", diffs[0]);
+        };
+        assert!(header.contains("ANTI-PATTERNS"));
+
+        assert_eq!(
+            diff_prompts(&trimmed, &full),
+            vec![SectionDiff::Added {
+                header: header.clone()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diff_prompts_changed_and_identical() {
+        let base = CortexPromptBuilder::new();
+        let prompt = base.build();
+        assert!(diff_prompts(&prompt, &prompt).is_empty());
+
+        let edited = base
+            .clone()
+            .append_to_section("CODE DISCIPLINE", "- Run `cargo fmt`")
+            .build();
+        let diffs = diff_prompts(&prompt, &edited);
+        assert_eq!(diffs.len(), 1);
+        match &diffs[0] {
+            SectionDiff::Changed {
+                header,
+                before,
+                after,
+            } => {
+                assert!(header.contains("CODE DISCIPLINE"));
+                assert!(!before.contains("cargo fmt"));
+                assert!(after.ends_with("- Run `cargo fmt`"));
+            }
+            other => panic!("expected Changed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_diff_prompts_pairs_repeated_headers_in_order() {
+        let a = CortexPromptBuilder::new()
+            .add_custom_section("NOTES", "## NOTES\n\nFirst")
+            .build();
+        let b = CortexPromptBuilder::new()
+            .add_custom_section("NOTES", "## NOTES\n\nFirst")
+            .add_custom_section("NOTES", "## NOTES\n\nSecond")
+            .build();
+
+        assert_eq!(
+            diff_prompts(&a, &b),
+            vec![SectionDiff::Added {
+                header: "## NOTES".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_builder_config_round_trip() {
+        let builder = CortexPromptBuilder::new()
+            .without_section("ANTI-PATTERNS")
+            .append_to_section("CODE DISCIPLINE", "- Run `cargo fmt`")
+            .add_custom_section("NOTES", "## NOTES\n\nWorking in {{CWD}}.")
+            .add_custom_section("NOTES", "## NOTES\n\nSecond notes")
+            .add_custom_tool_section("EXTRA TOOLS", "## EXTRA TOOLS")
+            .add_tool("Analyze", "Analyze code for issues")
+            .with_variable("CWD", "/workspace")
+            .renumber(true)
+            .for_phase(Phase::Build);
+        let builder = builder.without_section_at(builder.section_count() - 2);
+
+        let json = serde_json::to_string(&builder.to_config()).unwrap();
+        let config: PromptBuilderConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(config, builder.to_config());
+
+        let restored = CortexPromptBuilder::from_config(config);
+        assert_eq!(restored.build(), builder.build());
+        assert_eq!(restored.enabled_sections(), builder.enabled_sections());
+    }
+
+    #[test]
+    fn test_builder_config_records_default_sections_by_name() {
+        let config = CortexPromptBuilder::new()
+            .without_section("TOOLKIT")
+            .append_to_section("HEADER", "Extra header")
+            .to_config();
+
+        let expected: Vec<&str> = SECTION_NAMES
+            .iter()
+            .copied()
+            .filter(|name| *name != "TOOLKIT")
+            .collect();
+        assert_eq!(config.enabled_sections(), expected);
+
+        assert!(
+            config.sections[0]
+                .content
+                .as_deref()
+                .unwrap()
+                .ends_with("Extra header")
+        );
+        assert!(config.sections[1..].iter().all(|s| s.content.is_none()));
+        assert!(!config.use_custom_toolkit_only);
+    }
+
+    #[test]
+    fn test_builder_from_config_skips_unknown_sections_without_content() {
+        let mut config = CortexPromptBuilder::new().to_config();
+        config.sections.push(SectionConfig {
+            name: "MISSING".to_string(),
+            enabled: true,
+            content: None,
+            tool_target: false,
+        });
+
+        let builder = CortexPromptBuilder::from_config(config);
+        assert_eq!(builder.section_count(), SECTION_NAMES.len());
+        assert_eq!(builder.build(), CortexPromptBuilder::new().build());
+    }
+
+    #[test]
+    fn test_builder_merge_custom_sections_and_tools() {
+        let base = CortexPromptBuilder::new()
+            .add_tool("Analyze", "Analyze code")
+            .add_tool("Refactor", "Refactor code")
+            .add_custom_section("TEAM RULES", "## TEAM RULES\n\nOld rules.");
+        let overlay = CortexPromptBuilder::new()
+            .add_tool("analyze", "Analyze code deeply")
+            .add_tool("Document", "Write docs")
+            .add_custom_section("TEAM RULES", "## TEAM RULES\n\nNew rules.")
+            .add_custom_section("ONCALL", "## ONCALL\n\nPage the oncall.");
+
+        let merged = base.merge(&overlay);
+        let prompt = merged.build();
+
+        assert!(prompt.contains(
+            "| `Analyze` | Analyze code deeply |\n\
+             | `Refactor` | Refactor code |\n\
+             | `Document` | Write docs |\n"
+        ));
+        assert!(prompt.contains("New rules."));
+        assert!(!prompt.contains("Old rules."));
+        assert_eq!(
+            merged.enabled_sections()[SECTION_NAMES.len()..],
+            ["TEAM RULES", "ONCALL"]
+        );
+    }
+
     #[test]
     fn test_builder_add_custom_section() {
         let prompt = CortexPromptBuilder::new()
@@ -1242,6 +2707,66 @@ mod tests {
         assert!(prompt.contains("Follow these special rules"));
     }
 
+    #[test]
+    fn test_builder_without_section_at_targets_duplicate_name() {
+        let builder = CortexPromptBuilder::new()
+            .add_custom_section("NOTES", "## NOTES\n\nFirst notes")
+            .add_custom_section("NOTES", "## NOTES\n\nSecond notes");
+        assert_eq!(builder.section_count(), SECTION_NAMES.len() + 2);
+
+        let second = builder.section_count() - 1;
+        let builder = builder.without_section_at(second);
+        let prompt = builder.build();
+        assert!(prompt.contains("First notes"));
+        assert!(!prompt.contains("Second notes"));
+        assert!(builder.is_section_enabled("NOTES"));
+
+        let prompt = builder.with_section_at(second).build();
+        assert!(prompt.contains("Second notes"));
+    }
+
+    #[test]
+    fn test_builder_section_at_out_of_range_is_ignored() {
+        let builder = CortexPromptBuilder::new();
+        let count = builder.section_count();
+        let expected = builder.build();
+
+        let builder = builder
+            .without_section_at(count)
+            .with_section_at(usize::MAX);
+        assert_eq!(builder.section_count(), count);
+        assert_eq!(builder.build(), expected);
+    }
+
+    #[test]
+    fn test_builder_custom_tool_section_receives_tools() {
+        let prompt = CortexPromptBuilder::new()
+            .add_custom_tool_section("EXTRA TOOLS", "## EXTRA TOOLS")
+            .add_tool("Analyze", "Analyze code for issues")
+            .add_tool("Refactor", "Refactor code automatically")
+            .build();
+
+        let extra = prompt
+            .split("\n\n---\n\n")
+            .find(|part| part.starts_with("## EXTRA TOOLS"))
+            .expect("custom tool section should be rendered");
+        assert!(extra.contains("| `Analyze` | Analyze code for issues |"));
+        assert!(extra.contains("| `Refactor` | Refactor code automatically |"));
+
+        // TOOLKIT keeps its defaults but no longer carries the Custom block
+        assert!(!prompt.contains("### Custom"));
+        assert!(prompt.contains("| `Read` |"));
+    }
+
+    #[test]
+    fn test_builder_custom_tool_section_without_tools() {
+        let prompt = CortexPromptBuilder::new()
+            .add_custom_tool_section("EXTRA TOOLS", "## EXTRA TOOLS")
+            .build();
+
+        assert!(prompt.ends_with("## EXTRA TOOLS"));
+    }
+
     #[test]
     fn test_builder_is_section_enabled() {
         let builder = CortexPromptBuilder::new().without_section("ANTI-PATTERNS");
@@ -1362,6 +2887,35 @@ mod tests {
         assert!(!prompt.contains("| `Delegate` |"));
     }
 
+    #[test]
+    fn test_builder_renumber_after_disabling_section() {
+        let prompt = CortexPromptBuilder::new()
+            .without_section("COGNITIVE ARCHITECTURE")
+            .renumber(true)
+            .build();
+
+        assert!(prompt.contains("## 01 // PRIME DIRECTIVES"));
+        assert!(prompt.contains("## 02 // FAILURE PROTOCOL"));
+        assert!(prompt.contains("## 05 // TOOLKIT"));
+        assert!(prompt.contains("## 08 // OUTPUT FORMAT"));
+        assert!(!prompt.contains("## 09 //"));
+        // Section bodies are preserved
+        assert!(prompt.contains("TIER 1: RETRY"));
+    }
+
+    #[test]
+    fn test_builder_renumber_disabled_by_default() {
+        let prompt = CortexPromptBuilder::new()
+            .without_section("COGNITIVE ARCHITECTURE")
+            .build();
+
+        assert!(prompt.contains("## 03 // FAILURE PROTOCOL"));
+        assert_eq!(
+            CortexPromptBuilder::new().renumber(true).build(),
+            CortexPromptBuilder::new().build()
+        );
+    }
+
     #[test]
     fn test_builder_clone() {
         let builder = CortexPromptBuilder::new()
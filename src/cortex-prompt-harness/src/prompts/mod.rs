@@ -58,18 +58,24 @@ pub use agents::{
 };
 pub use base_agent::{
     AVAILABLE_SKILLS, CORTEX_BASE_PROMPT, CORTEX_BASE_PROMPT_WITH_SKILLS_PRELOADED,
-    format_skill_loading_prompt, get_recommended_skills,
+    base_prompt_skill_names, format_skill_loading_prompt, get_recommended_skills,
+    get_recommended_skills_scored, skill_priority, summarize_loaded_skills,
 };
 pub use builtin_skills::{
-    BUILTIN_SKILL_NAMES, SKILL_CODE_QUALITY, SKILL_DEBUGGING, SKILL_FILE_OPERATIONS, SKILL_GIT,
-    SKILL_PLANNING, SKILL_SECURITY, builtin_skill_count, get_builtin_skill, is_builtin_skill,
-    list_builtin_skills,
+    BUILTIN_SKILL_NAMES, SKILL_CODE_QUALITY, SKILL_DEBUGGING, SKILL_DOCKER, SKILL_FILE_OPERATIONS,
+    SKILL_GIT, SKILL_PLANNING, SKILL_RUST, SKILL_SECURITY, SKILL_TESTING, SkillMeta, SkillRegistry,
+    SkillValidationError, builtin_skill_count, find_skills_by_tag, get_builtin_skill,
+    get_skill_metadata, is_builtin_skill, iter_builtin_skills, iter_skill_names,
+    list_builtin_skills, list_skill_metadata, validate_skill_content,
 };
 pub use core::{
-    CORTEX_MAIN_PROMPT, CortexPromptBuilder, SECTION_ANTI_PATTERNS, SECTION_CODE_DISCIPLINE,
+    CORTEX_MAIN_PROMPT, CortexPromptBuilder, DEFAULT_TOOLS, FIT_WARNING_FRACTION, FitReport, Phase,
+    PromptBuilderConfig, SECTION_ANTI_PATTERNS, SECTION_CODE_DISCIPLINE,
     SECTION_COGNITIVE_ARCHITECTURE, SECTION_FAILURE_PROTOCOL, SECTION_HEADER, SECTION_NAMES,
     SECTION_OUTPUT_FORMAT, SECTION_PRIME_DIRECTIVES, SECTION_QUALITY_CHECKPOINTS,
-    SECTION_RESPONSE_PATTERNS, SECTION_TOOLKIT, TUI_SYSTEM_PROMPT_TEMPLATE,
+    SECTION_RESPONSE_PATTERNS, SECTION_TOOLKIT, SectionConfig, SectionDiff,
+    TUI_SYSTEM_PROMPT_TEMPLATE, ToolCategory, ToolDoc, default_tool_names, diff_prompts,
+    render_toolkit_section, tool_doc,
 };
 pub use tasks::{COMPACTION_PROMPT, SUMMARIZATION_PROMPT};
 pub use top_agent::{
@@ -1378,11 +1378,11 @@ impl TopAgentPromptBuilder {
 
     /// Build the prompt and return an estimated token count.
     ///
-    /// Uses a simple approximation of ~4 characters per token.
+    /// Uses the shared [`crate::estimate_tokens`] heuristic.
     #[must_use]
     pub fn build_with_token_estimate(&self) -> (String, u32) {
         let prompt = self.build();
-        let tokens = (prompt.len() as f64 / 4.0).ceil() as u32;
+        let tokens = crate::estimate_tokens(&prompt);
         (prompt, tokens)
     }
 }
@@ -1679,8 +1679,7 @@ mod tests {
 
         assert!(!prompt.is_empty());
         assert!(tokens > 0);
-        let expected_approx = (prompt.len() as f64 / 4.0).ceil() as u32;
-        assert_eq!(tokens, expected_approx);
+        assert_eq!(tokens, crate::estimate_tokens(&prompt));
     }
 
     #[test]
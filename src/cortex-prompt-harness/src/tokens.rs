@@ -0,0 +1,193 @@
+//! Token estimation shared by every prompt builder.
+//!
+//! All token estimates in Cortex go through [`estimate_tokens`] so they stay
+//! consistent and can be swapped for a real tokenizer in one place.
+
+/// Estimate the number of tokens in a string.
+///
+/// Uses a simple approximation of ~4 bytes per token, rounded up.
+///
+/// # Example
+///
+/// ```rust
+/// use cortex_prompt_harness::estimate_tokens;
+///
+/// assert_eq!(estimate_tokens(""), 0);
+/// assert_eq!(estimate_tokens("Hello, world!"), 4);
+/// ```
+#[must_use]
+pub fn estimate_tokens(text: &str) -> u32 {
+    (text.len() as f64 / 4.0).ceil() as u32
+}
+
+/// How [`estimate_tokens_with`] approximates a token count.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenEstimateMode {
+    /// A fixed number of bytes per token, rounded up. `CharsPerToken(4.0)` is
+    /// what [`estimate_tokens`] uses; non-positive values fall back to it.
+    CharsPerToken(f64),
+    /// Count words and punctuation runs, roughly matching BPE tokenizers on
+    /// ASCII text.
+    ///
+    /// Runs of ASCII letters, digits and `_` cost one token per 6 characters,
+    /// runs of ASCII punctuation one token per 4 characters, every non-ASCII
+    /// character one token, and whitespace nothing.
+    WordAndPunct,
+}
+
+impl Default for TokenEstimateMode {
+    fn default() -> Self {
+        Self::CharsPerToken(4.0)
+    }
+}
+
+/// Estimate the number of tokens in a string using the given mode.
+///
+/// # Example
+///
+/// ```rust
+/// use cortex_prompt_harness::tokens::{TokenEstimateMode, estimate_tokens_with};
+///
+/// assert_eq!(estimate_tokens_with("Hello, world!", TokenEstimateMode::WordAndPunct), 4);
+/// assert_eq!(estimate_tokens_with("abcdef", TokenEstimateMode::CharsPerToken(3.0)), 2);
+/// ```
+#[must_use]
+pub fn estimate_tokens_with(text: &str, mode: TokenEstimateMode) -> u32 {
+    match mode {
+        TokenEstimateMode::CharsPerToken(chars) if chars > 0.0 => {
+            (text.len() as f64 / chars).ceil() as u32
+        }
+        TokenEstimateMode::CharsPerToken(_) => estimate_tokens(text),
+        TokenEstimateMode::WordAndPunct => estimate_word_and_punct(text),
+    }
+}
+
+/// Character classes used by [`TokenEstimateMode::WordAndPunct`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Word,
+    Punct,
+    Space,
+    Other,
+}
+
+impl CharClass {
+    fn of(c: char) -> Self {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            Self::Word
+        } else if c.is_whitespace() {
+            Self::Space
+        } else if c.is_ascii() {
+            Self::Punct
+        } else {
+            Self::Other
+        }
+    }
+
+    /// Tokens for a run of `len` characters of this class.
+    fn run_tokens(self, len: usize) -> usize {
+        match self {
+            Self::Word => len.div_ceil(6),
+            Self::Punct => len.div_ceil(4),
+            Self::Space => 0,
+            Self::Other => len,
+        }
+    }
+}
+
+fn estimate_word_and_punct(text: &str) -> u32 {
+    let mut tokens = 0;
+    let mut run: Option<(CharClass, usize)> = None;
+
+    for c in text.chars() {
+        let class = CharClass::of(c);
+        if let Some((current, len)) = run.as_mut()
+            && *current == class
+        {
+            *len += 1;
+        } else if let Some((current, len)) = run.replace((class, 1)) {
+            tokens += current.run_tokens(len);
+        }
+    }
+    if let Some((current, len)) = run {
+        tokens += current.run_tokens(len);
+    }
+
+    tokens as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompts::core::CortexPromptBuilder;
+
+    #[test]
+    fn test_estimate_tokens_rounds_up() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
+
+    #[test]
+    fn test_word_and_punct_counts_runs() {
+        let mode = TokenEstimateMode::WordAndPunct;
+        assert_eq!(estimate_tokens_with("", mode), 0);
+        assert_eq!(estimate_tokens_with("Hello, world!", mode), 4);
+        // Long identifiers split, punctuation runs merge.
+        assert_eq!(estimate_tokens_with("configuration", mode), 3);
+        assert_eq!(estimate_tokens_with("|------|", mode), 2);
+        assert_eq!(estimate_tokens_with("→ ─", mode), 2);
+    }
+
+    #[test]
+    fn test_estimate_modes_on_main_prompt() {
+        let prompt = crate::prompts::CORTEX_MAIN_PROMPT;
+        let default = estimate_tokens(prompt);
+
+        assert_eq!(
+            estimate_tokens_with(prompt, TokenEstimateMode::default()),
+            default
+        );
+        assert_eq!(
+            estimate_tokens_with(prompt, TokenEstimateMode::CharsPerToken(0.0)),
+            default
+        );
+        assert!(estimate_tokens_with(prompt, TokenEstimateMode::CharsPerToken(2.0)) > default);
+    }
+
+    #[test]
+    fn test_word_and_punct_close_to_real_tokenizer() {
+        // Token counts from the cl100k_base BPE tokenizer.
+        let samples = [
+            ("The quick brown fox jumps over the lazy dog.", 10),
+            ("Hello, world!", 4),
+            ("You are a helpful assistant.", 6),
+            ("fn main() {}", 4),
+            ("## Instructions\n\n- Read the file first.", 9),
+        ];
+
+        let mut estimated = 0;
+        let mut actual: u32 = 0;
+        for (text, tokens) in samples {
+            estimated += estimate_tokens_with(text, TokenEstimateMode::WordAndPunct);
+            actual += tokens;
+        }
+        let error = (f64::from(estimated) - f64::from(actual)).abs() / f64::from(actual);
+        assert!(error <= 0.15, "estimated {estimated}, actual {actual}");
+    }
+
+    #[test]
+    fn test_estimate_tokens_matches_builders() {
+        let (prompt, tokens) = CortexPromptBuilder::new().build_with_token_estimate();
+        assert_eq!(estimate_tokens(&prompt), tokens);
+
+        let mode = TokenEstimateMode::WordAndPunct;
+        let (prompt, tokens) = CortexPromptBuilder::new().build_with_token_estimate_mode(mode);
+        assert_eq!(estimate_tokens_with(&prompt, mode), tokens);
+
+        let (prompt, tokens) =
+            crate::SystemPromptBuilder::with_base("You are a helpful assistant.")
+                .build_with_token_estimate();
+        assert_eq!(estimate_tokens(&prompt), tokens);
+    }
+}